@@ -1,7 +1,8 @@
-from sqlalchemy import create_engine
+from sqlalchemy import create_engine, text
 from sqlalchemy.ext.declarative import declarative_base
 from sqlalchemy.orm import sessionmaker
 import os
+from typing import Dict, List
 from dotenv import load_dotenv
 
 load_dotenv()
@@ -17,6 +18,53 @@ SessionLocal = sessionmaker(autocommit=False, autoflush=False, bind=engine)
 Base = declarative_base()
 
 
+def add_enum_values(type_name: str, values: List[str]) -> None:
+    """
+    Add values to an existing PostgreSQL enum type.
+    
+    create_all() never alters existing types, so values added to a Python
+    enum after the database was created have to be added here.
+    """
+    if engine.dialect.name != "postgresql":
+        return
+    # ALTER TYPE ... ADD VALUE cannot run inside a transaction on older servers
+    with engine.connect().execution_options(isolation_level="AUTOCOMMIT") as conn:
+        for value in values:
+            conn.execute(text(f"ALTER TYPE {type_name} ADD VALUE IF NOT EXISTS '{value}'"))
+
+
+def add_enum_type(type_name: str, values: List[str]) -> None:
+    """
+    Create a PostgreSQL enum type if it does not exist yet.
+    
+    create_all() only creates the types of tables it creates, so an enum used
+    by a column added to an existing table has to be created here.
+    """
+    if engine.dialect.name != "postgresql":
+        return
+    with engine.begin() as conn:
+        exists = conn.execute(
+            text("SELECT 1 FROM pg_type WHERE typname = :name"), {"name": type_name}
+        ).first()
+        if not exists:
+            labels = ", ".join(f"'{value}'" for value in values)
+            conn.execute(text(f"CREATE TYPE {type_name} AS ENUM ({labels})"))
+
+
+def add_columns(table_name: str, columns: Dict[str, str]) -> None:
+    """
+    Add columns, given as name and SQL type, to an existing PostgreSQL table.
+    
+    create_all() never alters existing tables, so columns added to a model
+    after the database was created have to be added here.
+    """
+    if engine.dialect.name != "postgresql":
+        return
+    with engine.begin() as conn:
+        for name, sql_type in columns.items():
+            conn.execute(text(f"ALTER TABLE {table_name} ADD COLUMN IF NOT EXISTS {name} {sql_type}"))
+
+
 def get_db():
     db = SessionLocal()
     try:
@@ -4,13 +4,16 @@ Symgen - Volatility3 Linux Symbol Generator
 A standalone application for generating Volatility3 Linux symbols using Docker containers.
 """
 
+import asyncio
 import logging
 from contextlib import asynccontextmanager
 
 from fastapi import FastAPI
 from fastapi.middleware.cors import CORSMiddleware
+from fastapi.routing import APIRoute
 
-from app.database import engine, Base
+from app.database import engine, Base, add_columns, add_enum_type, add_enum_values
+from app.models import JobPriority
 from app.routers import symgen
 
 # Configure logging
@@ -29,19 +32,50 @@ async def lifespan(app: FastAPI):
     
     # Create database tables
     Base.metadata.create_all(bind=engine)
+    add_enum_values("symgenstatus", ["cancelled", "evicted"])
+    add_enum_type("jobpriority", [priority.value for priority in JobPriority])
+    add_columns("symbol_generations", {
+        "priority": "jobpriority DEFAULT 'interactive'",
+        "submitted_by": "VARCHAR",
+        "last_accessed_at": "TIMESTAMP WITH TIME ZONE",
+    })
     logger.info("Database tables created/verified")
     
+    # Pick up jobs the previous process left unfinished
+    from app.services.symgen import symbol_generator
+    await symbol_generator.resume_jobs()
+    
+    # Expire stored symbol files past SYMBOL_RETENTION
+    from app.services.symgen import SYMBOL_RETENTION
+    retention_task = None
+    if SYMBOL_RETENTION:
+        retention_task = asyncio.create_task(symbol_generator.run_retention_sweeps())
+        logger.info(f"Symbol files expire {SYMBOL_RETENTION}s after their last download")
+    
     yield
     
     # Shutdown
     logger.info("Shutting down Symgen application...")
+    if retention_task:
+        retention_task.cancel()
+    await symbol_generator.shutdown()
+
+
+def operation_id(route: APIRoute) -> str:
+    """Name OpenAPI operations after their handlers, so generated SDKs get readable method names."""
+    return route.name
 
 
+# The spec and docs live under /api/ so they are reachable through nginx
 app = FastAPI(
     title="Symgen",
     description="Volatility3 Linux Symbol Generator - Generate Linux kernel symbols automatically using Docker containers",
     version="1.0.0",
-    lifespan=lifespan
+    lifespan=lifespan,
+    openapi_url="/api/openapi.json",
+    docs_url="/api/docs",
+    redoc_url="/api/redoc",
+    generate_unique_id_function=operation_id,
 )
 
 # Configure CORS
@@ -64,7 +98,8 @@ def root():
         "name": "Symgen",
         "description": "Volatility3 Linux Symbol Generator",
         "version": "1.0.0",
-        "docs": "/docs",
+        "docs": "/api/docs",
+        "openapi": "/api/openapi.json",
         "endpoints": {
             "status": "/api/symgen/status",
             "generate": "/api/symgen/generate",
@@ -1,3 +1,6 @@
+from datetime import datetime, timedelta
+from typing import Optional
+
 from sqlalchemy import Column, Integer, BigInteger, String, DateTime, func, Text, Enum as SQLEnum
 from app.database import Base
 import enum
@@ -11,6 +14,13 @@ class SymGenStatus(str, enum.Enum):
     GENERATING_SYMBOL = "generating_symbol"
     COMPLETED = "completed"
     FAILED = "failed"
+    CANCELLED = "cancelled"
+    EVICTED = "evicted"  # Completed, but the symbol file was removed to free space
+
+
+class JobPriority(str, enum.Enum):
+    INTERACTIVE = "interactive"  # An analyst waiting on the result
+    BULK = "bulk"  # Backfills and pack generation; yields to interactive jobs
 
 
 class LinuxDistro(str, enum.Enum):
@@ -89,6 +99,9 @@ class SymbolGeneration(Base):
     # Job status
     status = Column(SQLEnum(SymGenStatus, values_callable=lambda x: [e.value for e in x]), default=SymGenStatus.PENDING)
     status_message = Column(String, nullable=True)  # Human-readable progress
+    # Scheduling
+    priority = Column(SQLEnum(JobPriority, values_callable=lambda x: [e.value for e in x]), default=JobPriority.INTERACTIVE)
+    submitted_by = Column(String, nullable=True)  # X-Symgen-User header, or the client address
     error_message = Column(Text, nullable=True)
     # Docker container info
     container_id = Column(String, nullable=True)
@@ -98,7 +111,17 @@ class SymbolGeneration(Base):
     symbol_file_size = Column(BigInteger, nullable=True)
     # Download tracking
     download_count = Column(Integer, default=0)
+    last_accessed_at = Column(DateTime(timezone=True), nullable=True)  # Orders eviction under a storage quota
     # Timestamps
     started_at = Column(DateTime(timezone=True), nullable=True)
     completed_at = Column(DateTime(timezone=True), nullable=True)
     created_at = Column(DateTime(timezone=True), server_default=func.now())
+
+    @property
+    def expires_at(self) -> Optional[datetime]:
+        """When the retention sweep removes the symbol file, if SYMBOL_RETENTION is set."""
+        from app.services.symgen import SYMBOL_RETENTION
+        if not SYMBOL_RETENTION or self.status != SymGenStatus.COMPLETED or not self.symbol_file_path:
+            return None
+        last_used = self.last_accessed_at or self.completed_at
+        return last_used + timedelta(seconds=SYMBOL_RETENTION) if last_used else None
@@ -6,10 +6,12 @@ API endpoints for generating and downloading Volatility3 Linux symbols.
 
 import os
 import math
+from datetime import datetime
+from email.utils import formatdate
 from typing import Optional
 
-from fastapi import APIRouter, Depends, HTTPException, status, BackgroundTasks, WebSocket, WebSocketDisconnect
-from fastapi.responses import FileResponse
+from fastapi import APIRouter, Depends, HTTPException, Request, status, BackgroundTasks, WebSocket, WebSocketDisconnect
+from fastapi.responses import FileResponse, Response, StreamingResponse
 from sqlalchemy.orm import Session
 from sqlalchemy import desc
 
@@ -22,11 +24,11 @@ from app.models import (
 from app.schemas import (
     SymGenCreate, SymGenResponse, SymGenListResponse, 
     GeneratedSymbolResponse, SymbolPortalResponse, KernelParseResponse,
-    MetricsResponse
+    MetricsResponse, SymGenStatusResponse, ActionResponse, DistrosResponse
 )
 from app.services.symgen import (
-    symbol_generator, parse_kernel_version,
-    get_symbol_filename, SYMBOLS_DIR
+    symbol_generator, job_queue, parse_kernel_version,
+    get_symbol_filename, SYMBOLS_DIR, ANONYMOUS_USER, SYMBOL_STORAGE_QUOTA
 )
 from app.websocket import manager
 
@@ -37,7 +39,7 @@ router = APIRouter(prefix="/api/symgen", tags=["symgen"])
 # Symbol Generation Endpoints
 # ============================================================
 
-@router.get("/status")
+@router.get("/status", response_model=SymGenStatusResponse)
 def get_symgen_status():
     """Check if symbol generation is available (Docker connected)."""
     queue_status = symbol_generator.get_queue_status()
@@ -144,6 +146,7 @@ def get_metrics(db: Session = Depends(get_db)):
         total_symbols=total_symbols,
         total_storage_bytes=total_storage_bytes,
         total_storage_formatted=total_storage_formatted,
+        storage_quota_bytes=SYMBOL_STORAGE_QUOTA,
         total_downloads=total_downloads,
         avg_completion_time_seconds=avg_completion_time_seconds,
         avg_completion_time_formatted=avg_completion_time_formatted,
@@ -152,10 +155,23 @@ def get_metrics(db: Session = Depends(get_db)):
     )
 
 
+def submitter(http_request: Request) -> str:
+    """Identify who submitted a request, for fair scheduling."""
+    user = http_request.headers.get("X-Symgen-User", "").strip()
+    if user:
+        return user[:128]
+    # nginx passes the real client address on
+    address = http_request.headers.get("X-Real-IP")
+    if address:
+        return address
+    return http_request.client.host if http_request.client else ANONYMOUS_USER
+
+
 @router.post("/generate", response_model=SymGenResponse)
 async def create_symbol_generation(
     request: SymGenCreate,
     background_tasks: BackgroundTasks,
+    http_request: Request,
     db: Session = Depends(get_db)
 ):
     """
@@ -163,6 +179,10 @@ async def create_symbol_generation(
     
     Spins up a Linux Docker container (Ubuntu or Debian), downloads kernel debug symbols,
     and generates a Volatility3 symbol file.
+    
+    Jobs are scheduled by `priority` (bulk jobs yield to interactive ones) and
+    shared fairly between submitters, identified by the `X-Symgen-User` header
+    or, without it, the client address.
     """
     if not symbol_generator.is_available():
         raise HTTPException(
@@ -170,6 +190,12 @@ async def create_symbol_generation(
             detail="Docker is not available. Symbol generation requires Docker access."
         )
     
+    if job_queue.draining:
+        raise HTTPException(
+            status_code=status.HTTP_503_SERVICE_UNAVAILABLE,
+            detail="The server is shutting down and not accepting new jobs. Retry shortly."
+        )
+    
     # Validate distro-specific version is provided
     version_requirements = {
         LinuxDistro.UBUNTU: ("ubuntu_version", request.ubuntu_version),
@@ -219,6 +245,8 @@ async def create_symbol_generation(
     if completed_job:
         # Verify the symbol file still exists
         if completed_job.symbol_file_path and os.path.exists(completed_job.symbol_file_path):
+            completed_job.last_accessed_at = datetime.utcnow()
+            db.commit()
             return completed_job
         # Symbol file missing, mark job as failed so user can retry
         completed_job.status = SymGenStatus.FAILED
@@ -254,6 +282,8 @@ async def create_symbol_generation(
         alma_version=request.alma_version,
         status=SymGenStatus.PENDING,
         status_message="Job created, waiting to start...",
+        priority=request.priority,
+        submitted_by=submitter(http_request),
     )
     db.add(job)
     db.commit()
@@ -276,6 +306,8 @@ async def create_symbol_generation(
             "alma_version": job.alma_version.value if job.alma_version else None,
             "status": job.status.value if job.status else None,
             "status_message": job.status_message,
+            "priority": job.priority.value if job.priority else None,
+            "submitted_by": job.submitted_by,
             "error_message": job.error_message,
             "symbol_filename": job.symbol_filename,
             "symbol_file_size": job.symbol_file_size,
@@ -300,6 +332,8 @@ async def create_symbol_generation(
         request.oracle_version,
         request.rocky_version,
         request.alma_version,
+        priority=request.priority,
+        user=job.submitted_by,
     )
     
     return job
@@ -353,6 +387,8 @@ def list_generation_jobs(
     status_filter can be:
     - 'completed' - only completed jobs
     - 'failed' - only failed jobs  
+    - 'cancelled' - only cancelled jobs
+    - 'evicted' - completed jobs whose symbol file was removed
     - 'in_progress' - pending, pulling_image, running, downloading_kernel, generating_symbol
     - A specific SymGenStatus value
     """
@@ -412,12 +448,16 @@ def get_generation_job(
     return job
 
 
-@router.post("/jobs/{job_id}/cancel")
-def cancel_generation_job(
+@router.post("/jobs/{job_id}/cancel", response_model=ActionResponse)
+async def cancel_generation_job(
     job_id: int,
     db: Session = Depends(get_db)
 ):
-    """Cancel a running symbol generation job."""
+    """
+    Cancel a queued or running symbol generation job.
+    
+    Stops its container, removes its scratch space and marks it cancelled.
+    """
     job = db.query(SymbolGeneration).filter(SymbolGeneration.id == job_id).first()
     if not job:
         raise HTTPException(
@@ -425,13 +465,13 @@ def cancel_generation_job(
             detail="Symbol generation job not found"
         )
     
-    if job.status in (SymGenStatus.COMPLETED, SymGenStatus.FAILED):
+    if job.status in (SymGenStatus.COMPLETED, SymGenStatus.FAILED, SymGenStatus.CANCELLED, SymGenStatus.EVICTED):
         raise HTTPException(
             status_code=status.HTTP_400_BAD_REQUEST,
             detail=f"Cannot cancel job with status: {job.status.value}"
         )
     
-    success = symbol_generator.cancel_job(job_id)
+    success = await symbol_generator.cancel_job_async(job_id)
     
     return {
         "success": success,
@@ -439,7 +479,7 @@ def cancel_generation_job(
     }
 
 
-@router.delete("/jobs/{job_id}")
+@router.delete("/jobs/{job_id}", response_model=ActionResponse)
 def delete_generation_job(
     job_id: int,
     db: Session = Depends(get_db)
@@ -540,17 +580,84 @@ def list_available_symbols(
     )
 
 
-@router.get("/download/{job_id}")
-def download_symbol(
-    job_id: int,
-    db: Session = Depends(get_db)
-):
-    """
-    Download a generated symbol file.
+RANGE_CHUNK_SIZE = 1024 * 1024  # Bytes read per chunk when serving a range
+
+
+def _parse_range(header: str, size: int) -> Optional[tuple]:
+    """Parse a single `bytes=start-end` range into inclusive offsets; None when unsatisfiable."""
+    unit, _, spec = header.partition("=")
+    if unit.strip() != "bytes" or "," in spec:
+        return None
+    start, _, end = spec.strip().partition("-")
+    try:
+        if not start:
+            # Suffix form: the last N bytes
+            length = int(end)
+            if length <= 0:
+                return None
+            return max(size - length, 0), size - 1
+        first = int(start)
+        last = min(int(end), size - 1) if end else size - 1
+    except ValueError:
+        return None
+    if first >= size or first > last:
+        return None
+    return first, last
+
+
+def _symbol_file_response(request: Request, job: SymbolGeneration) -> Response:
+    """Serve a symbol file, honouring Range and If-Range so interrupted downloads can resume."""
+    stat = os.stat(job.symbol_file_path)
+    size = stat.st_size
+    etag = f'"{size:x}-{int(stat.st_mtime):x}"'
+    headers = {
+        "Accept-Ranges": "bytes",
+        "ETag": etag,
+        "Last-Modified": formatdate(stat.st_mtime, usegmt=True),
+        "Content-Disposition": f'attachment; filename="{job.symbol_filename}"',
+    }
     
-    This endpoint is public for the symbol portal.
-    """
-    job = db.query(SymbolGeneration).filter(SymbolGeneration.id == job_id).first()
+    range_header = request.headers.get("range")
+    if_range = request.headers.get("if-range")
+    # A stale If-Range means the file changed since the client's partial copy; send it whole
+    if range_header and (not if_range or if_range in (etag, headers["Last-Modified"])):
+        byte_range = _parse_range(range_header, size)
+        if byte_range is None:
+            return Response(
+                status_code=status.HTTP_416_REQUESTED_RANGE_NOT_SATISFIABLE,
+                headers={"Content-Range": f"bytes */{size}", "Accept-Ranges": "bytes"},
+            )
+        first, last = byte_range
+        
+        def read_range():
+            with open(job.symbol_file_path, "rb") as f:
+                f.seek(first)
+                remaining = last - first + 1
+                while remaining > 0:
+                    chunk = f.read(min(RANGE_CHUNK_SIZE, remaining))
+                    if not chunk:
+                        break
+                    remaining -= len(chunk)
+                    yield chunk
+        
+        headers["Content-Range"] = f"bytes {first}-{last}/{size}"
+        headers["Content-Length"] = str(last - first + 1)
+        return StreamingResponse(
+            read_range(),
+            status_code=status.HTTP_206_PARTIAL_CONTENT,
+            media_type="application/x-xz",
+            headers=headers,
+        )
+    
+    return FileResponse(
+        path=job.symbol_file_path,
+        media_type="application/x-xz",
+        headers=headers,
+    )
+
+
+def _serve_symbol(job: Optional[SymbolGeneration], request: Request, db: Session) -> Response:
+    """Check a job's symbol file is downloadable, record the download and serve it."""
     if not job:
         raise HTTPException(
             status_code=status.HTTP_404_NOT_FOUND,
@@ -569,18 +676,49 @@ def download_symbol(
             detail="Symbol file not found on disk"
         )
     
-    # Increment download count
-    job.download_count += 1
+    # Count a download once, not for every resumed range; any access extends retention
+    range_header = request.headers.get("range", "")
+    if not range_header or range_header.replace(" ", "").startswith("bytes=0-"):
+        job.download_count += 1
+    job.last_accessed_at = datetime.utcnow()
     db.commit()
     
-    return FileResponse(
-        path=job.symbol_file_path,
-        filename=job.symbol_filename,
-        media_type="application/x-xz"
-    )
+    return _symbol_file_response(request, job)
+
+
+@router.get("/download/{job_id}")
+def download_symbol(
+    job_id: int,
+    request: Request,
+    db: Session = Depends(get_db)
+):
+    """
+    Download a generated symbol file.
+    
+    This endpoint is public for the symbol portal.
+    """
+    job = db.query(SymbolGeneration).filter(SymbolGeneration.id == job_id).first()
+    return _serve_symbol(job, request, db)
+
+
+@router.get("/jobs/{job_id}/artifact")
+def download_job_artifact(
+    job_id: int,
+    request: Request,
+    db: Session = Depends(get_db)
+):
+    """
+    Download the symbol file a job produced.
+    
+    Supports Range and If-Range requests, so clients on unreliable links can
+    resume large files (e.g. `curl -C -`). Downloading resets the retention
+    period of the file.
+    """
+    job = db.query(SymbolGeneration).filter(SymbolGeneration.id == job_id).first()
+    return _serve_symbol(job, request, db)
 
 
-@router.get("/distros")
+@router.get("/distros", response_model=DistrosResponse)
 def get_supported_distros():
     """Get list of supported Linux distributions and their versions."""
     return {
@@ -1,8 +1,8 @@
 from pydantic import BaseModel
 from datetime import datetime
-from typing import Optional, List
+from typing import Optional, List, Dict
 from app.models import (
-    SymGenStatus, LinuxDistro,
+    SymGenStatus, LinuxDistro, JobPriority,
     UbuntuVersion, DebianVersion, FedoraVersion, CentOSVersion,
     RHELVersion, OracleVersion, RockyVersion, AlmaVersion
 )
@@ -24,6 +24,7 @@ class SymGenCreate(BaseModel):
     oracle_version: Optional[OracleVersion] = None
     rocky_version: Optional[RockyVersion] = None
     alma_version: Optional[AlmaVersion] = None
+    priority: JobPriority = JobPriority.INTERACTIVE
 
 
 class SymGenResponse(BaseModel):
@@ -41,6 +42,8 @@ class SymGenResponse(BaseModel):
     alma_version: Optional[AlmaVersion] = None
     status: SymGenStatus
     status_message: Optional[str] = None
+    priority: Optional[JobPriority] = None
+    submitted_by: Optional[str] = None
     error_message: Optional[str] = None
     symbol_filename: Optional[str] = None
     symbol_file_size: Optional[int] = None
@@ -48,6 +51,7 @@ class SymGenResponse(BaseModel):
     started_at: Optional[datetime] = None
     completed_at: Optional[datetime] = None
     created_at: datetime
+    expires_at: Optional[datetime] = None  # When SYMBOL_RETENTION removes the symbol file
 
     class Config:
         from_attributes = True
@@ -118,8 +122,50 @@ class MetricsResponse(BaseModel):
     total_symbols: int
     total_storage_bytes: int
     total_storage_formatted: str
+    storage_quota_bytes: Optional[int] = None  # None when storage is unlimited
     total_downloads: int
     avg_completion_time_seconds: Optional[float] = None
     avg_completion_time_formatted: Optional[str] = None
     fastest_job_seconds: Optional[float] = None
     slowest_job_seconds: Optional[float] = None
+
+
+class QueueStatusResponse(BaseModel):
+    """Job queue occupancy."""
+    running: int
+    queued: int
+    queued_by_priority: Dict[str, int]
+    max_concurrent: int
+    draining: bool = False  # The server is shutting down and starts no new jobs
+
+
+class SymGenStatusResponse(BaseModel):
+    """Whether symbol generation is available, with the queue state."""
+    available: bool
+    message: str
+    queue: QueueStatusResponse
+
+
+class ActionResponse(BaseModel):
+    """Outcome of a job action such as cancel or delete."""
+    success: bool
+    message: str
+
+
+class DistroOption(BaseModel):
+    """A selectable distribution or distribution version."""
+    value: str
+    label: str
+
+
+class DistrosResponse(BaseModel):
+    """Supported distributions and their versions."""
+    distros: List[DistroOption]
+    ubuntu_versions: List[DistroOption]
+    debian_versions: List[DistroOption]
+    fedora_versions: List[DistroOption]
+    centos_versions: List[DistroOption]
+    rhel_versions: List[DistroOption]
+    oracle_versions: List[DistroOption]
+    rocky_versions: List[DistroOption]
+    alma_versions: List[DistroOption]
@@ -14,16 +14,17 @@ import shutil
 import glob as glob_module
 import threading
 import queue
-from datetime import datetime
-from typing import Optional, Tuple, Dict, Any
+from datetime import datetime, timedelta
+from typing import Optional, Tuple, Dict, Any, List, Set
 from collections import deque
 
 import docker
 from docker.errors import ImageNotFound, ContainerError, APIError
+from sqlalchemy import func
 from sqlalchemy.orm import Session
 
 from app.models import (
-    SymbolGeneration, SymGenStatus, LinuxDistro,
+    SymbolGeneration, SymGenStatus, LinuxDistro, JobPriority,
     UbuntuVersion, DebianVersion, FedoraVersion, CentOSVersion,
     RHELVersion, OracleVersion, RockyVersion, AlmaVersion
 )
@@ -35,6 +36,12 @@ logger = logging.getLogger(__name__)
 # Maximum concurrent jobs
 MAX_CONCURRENT_JOBS = 2
 
+# Interactive jobs are dispatched before bulk ones
+PRIORITY_ORDER = (JobPriority.INTERACTIVE, JobPriority.BULK)
+
+# Submitter recorded when a request carries no identity
+ANONYMOUS_USER = "anonymous"
+
 # Directory paths
 UPLOAD_DIR = os.getenv("UPLOAD_DIR", "/app/uploads")
 SYMBOLS_DIR = os.path.join(UPLOAD_DIR, "symbols")  # Centralized symbol storage
@@ -42,6 +49,59 @@ SYMBOLS_DIR = os.path.join(UPLOAD_DIR, "symbols")  # Centralized symbol storage
 # Docker volume name for uploads (must match docker-compose.yml)
 DOCKER_VOLUME_NAME = os.getenv("DOCKER_VOLUME_NAME", "symgen_storage")
 
+
+def parse_size(value: str) -> Optional[int]:
+    """Parse a byte count with an optional K/M/G/T suffix (binary units); empty or 0 means none."""
+    value = value.strip().upper().removesuffix("B").removesuffix("I")
+    if not value:
+        return None
+    units = {"K": 1024, "M": 1024 ** 2, "G": 1024 ** 3, "T": 1024 ** 4}
+    multiplier = units.get(value[-1], 1)
+    number = value[:-1] if value[-1] in units else value
+    size = int(float(number) * multiplier)
+    return size or None
+
+
+def parse_duration(value: str, name: str) -> Optional[int]:
+    """
+    Parse a duration in seconds with an optional s/m/h/d suffix; empty or 0 means none.
+    
+    An invalid value is logged and treated as none, naming the setting it came from.
+    """
+    value = value.strip().lower()
+    if not value:
+        return None
+    match = re.fullmatch(r"(\d+(?:\.\d+)?)\s*([smhd]?)", value)
+    if not match:
+        logger.error(f"Ignoring invalid {name} {value!r}; expected a number with an optional s, m, h or d suffix, e.g. 30d")
+        return None
+    units = {"": 1, "s": 1, "m": 60, "h": 3600, "d": 86400}
+    seconds = int(float(match.group(1)) * units[match.group(2)])
+    return seconds or None
+
+
+# Stored symbol files not generated or downloaded for this long are removed; unset keeps them
+SYMBOL_RETENTION = parse_duration(os.getenv("SYMBOL_RETENTION", ""), "SYMBOL_RETENTION")
+RETENTION_SWEEP_INTERVAL = 3600  # Seconds between retention sweeps
+
+# Disk quotas; unset leaves the resource unlimited
+SYMBOL_STORAGE_QUOTA = parse_size(os.getenv("SYMBOL_STORAGE_QUOTA", ""))  # All stored symbol files
+JOB_SCRATCH_QUOTA = parse_size(os.getenv("JOB_SCRATCH_QUOTA", ""))  # Disk written by one job's container
+SCRATCH_CHECK_INTERVAL = 30  # Seconds between scratch usage checks
+
+# Exit code reported by _monitor_container when a job outgrew its scratch quota
+EXIT_SCRATCH_QUOTA = -2
+
+# Seconds running jobs get to finish when the server stops; keep below the
+# container's stop timeout (stop_grace_period in docker-compose.yml)
+SHUTDOWN_GRACE_PERIOD = int(os.getenv("SHUTDOWN_GRACE_PERIOD", "600"))
+
+# Statuses of jobs that have not finished
+ACTIVE_STATUSES = (
+    SymGenStatus.PENDING, SymGenStatus.PULLING_IMAGE, SymGenStatus.RUNNING,
+    SymGenStatus.DOWNLOADING_KERNEL, SymGenStatus.GENERATING_SYMBOL,
+)
+
 # Ubuntu base images
 UBUNTU_IMAGES = {
     UbuntuVersion.UBUNTU_20_04: "ubuntu:20.04",
@@ -56,6 +116,13 @@ UBUNTU_CODENAMES = {
     UbuntuVersion.UBUNTU_24_04: "noble",
 }
 
+# Ubuntu kernel flavors (the "aws" in 5.15.0-1050-aws)
+UBUNTU_FLAVORS = (
+    "generic", "generic-64k", "generic-lpae", "lowlatency", "lowlatency-64k",
+    "aws", "azure", "azure-fde", "gcp", "gke", "gkeop", "oracle", "ibm", "kvm",
+    "oem", "nvidia", "intel-iotg", "raspi",
+)
+
 # Debian base images
 DEBIAN_IMAGES = {
     DebianVersion.DEBIAN_10: "debian:10",
@@ -109,16 +176,37 @@ ALMA_IMAGES = {
 }
 
 
+class QueuedJob:
+    """A job waiting for, or holding, a run slot."""
+
+    def __init__(self, job_id: int, priority: JobPriority, user: str, args: tuple, kwargs: dict):
+        self.job_id = job_id
+        self.priority = priority
+        self.user = user
+        self.args = args
+        self.kwargs = kwargs
+
+
 class JobQueue:
     """
     Manages a queue of symbol generation jobs with limited concurrency.
     Only MAX_CONCURRENT_JOBS jobs run at a time, others wait in queue.
+
+    Interactive jobs always run before bulk ones, and take the slot of a running
+    bulk job when every slot is busy; the bulk job is requeued at the front.
+    Within a priority, users take turns, so one user's backfill cannot hold up
+    everyone else.
     """
     
     def __init__(self, max_concurrent: int = MAX_CONCURRENT_JOBS):
         self.max_concurrent = max_concurrent
-        self._running_jobs: Dict[int, asyncio.Task] = {}  # job_id -> task
-        self._pending_queue: deque = deque()  # Queue of (job_id, args, kwargs)
+        self._running_jobs: Dict[int, asyncio.Task] = {}  # job_id -> generation task
+        self._running_info: Dict[int, QueuedJob] = {}  # job_id -> job holding the slot
+        self._pending: List[QueuedJob] = []  # In submission order; see _dispatch_order
+        self._preempted: Set[int] = set()  # Running jobs cancelled to be requeued
+        self._last_served: Dict[str, int] = {}  # user -> dispatch counter when last started
+        self._dispatch_count = 0
+        self.draining = False  # Set on shutdown; no further jobs are started
         self._lock = asyncio.Lock()
         self._generator = None  # Will be set by SymbolGenerator
         logger.info(f"JobQueue initialized with max_concurrent={max_concurrent}")
@@ -135,12 +223,42 @@ class JobQueue:
     @property
     def queued_count(self) -> int:
         """Number of jobs waiting in queue."""
-        return len(self._pending_queue)
+        return len(self._pending)
+    
+    def queued_by_priority(self) -> Dict[str, int]:
+        """Number of waiting jobs per priority."""
+        return {
+            priority.value: sum(1 for job in self._pending if job.priority == priority)
+            for priority in PRIORITY_ORDER
+        }
+    
+    def _dispatch_order(self) -> List[QueuedJob]:
+        """
+        Waiting jobs in the order they will start: by priority, then round-robin
+        across users, starting with the user with the fewest running jobs and
+        the longest wait since their last start.
+        """
+        order = []
+        for priority in PRIORITY_ORDER:
+            by_user: Dict[str, deque] = {}
+            for job in self._pending:
+                if job.priority == priority:
+                    by_user.setdefault(job.user, deque()).append(job)
+            
+            users = sorted(by_user, key=lambda user: (
+                sum(1 for job in self._running_info.values() if job.user == user),
+                self._last_served.get(user, 0),
+            ))
+            while users:
+                for user in users:
+                    order.append(by_user[user].popleft())
+                users = [user for user in users if by_user[user]]
+        return order
     
     def get_queue_position(self, job_id: int) -> Optional[int]:
         """Get position in queue (1-based), or None if not queued."""
-        for i, (queued_id, _, _) in enumerate(self._pending_queue):
-            if queued_id == job_id:
+        for i, job in enumerate(self._dispatch_order()):
+            if job.job_id == job_id:
                 return i + 1
         return None
     
@@ -148,25 +266,48 @@ class JobQueue:
         """Check if a job is currently running."""
         return job_id in self._running_jobs
     
-    async def submit_job(self, job_id: int, *args, **kwargs) -> bool:
+    async def submit_job(self, job_id: int, *args, priority: JobPriority = JobPriority.INTERACTIVE,
+                         user: str = ANONYMOUS_USER, **kwargs) -> bool:
         """
         Submit a job to the queue.
         Returns True if job started immediately, False if queued.
         """
+        job = QueuedJob(job_id, priority, user, args, kwargs)
         async with self._lock:
-            if self.running_count < self.max_concurrent:
+            if self.running_count < self.max_concurrent and not self.draining:
                 # Start immediately
-                await self._start_job(job_id, args, kwargs)
+                await self._start_job(job)
                 return True
-            else:
-                # Add to queue
-                self._pending_queue.append((job_id, args, kwargs))
-                queue_pos = len(self._pending_queue)
-                logger.info(f"Job {job_id} queued at position {queue_pos}")
-                
-                # Update job status to show queue position
-                self._update_queued_status(job_id, queue_pos)
-                return False
+            
+            # Add to queue
+            self._pending.append(job)
+            if priority == JobPriority.INTERACTIVE:
+                self._preempt_bulk_job()
+            logger.info(f"Job {job_id} ({priority.value}, {user}) queued at position {self.get_queue_position(job_id)}")
+            
+            # Update job status to show queue position
+            self._refresh_queued_status()
+            return False
+    
+    def _preempt_bulk_job(self):
+        """Cancel the most recently started bulk job so an interactive job can take its slot."""
+        victims = [
+            job_id for job_id, job in self._running_info.items()
+            if job.priority == JobPriority.BULK and job_id not in self._preempted
+        ]
+        if not victims:
+            return
+        
+        # Dicts keep insertion order, so the last one started has done the least work
+        victim = victims[-1]
+        logger.info(f"Preempting bulk job {victim} for an interactive job")
+        self._preempted.add(victim)
+        self._running_jobs[victim].cancel()
+    
+    def _refresh_queued_status(self):
+        """Update every waiting job's status with its queue position."""
+        for i, job in enumerate(self._dispatch_order()):
+            self._update_queued_status(job.job_id, i + 1)
     
     def _update_queued_status(self, job_id: int, position: int):
         """Update job status to show it's queued."""
@@ -179,47 +320,86 @@ class JobQueue:
         finally:
             db.close()
     
-    async def _start_job(self, job_id: int, args: tuple, kwargs: dict):
+    def _mark_requeued(self, job_id: int):
+        """Reset a preempted job to pending."""
+        db = SessionLocal()
+        try:
+            job = db.query(SymbolGeneration).filter(SymbolGeneration.id == job_id).first()
+            if job:
+                job.status = SymGenStatus.PENDING
+                job.status_message = "Paused for an interactive job, requeued"
+                job.container_id = None
+                db.commit()
+        finally:
+            db.close()
+    
+    async def _start_job(self, job: QueuedJob):
         """Start a job and track it."""
         if not self._generator:
             logger.error("Generator not set on JobQueue")
             return
         
-        # Create task for the job
+        self._dispatch_count += 1
+        self._last_served[job.user] = self._dispatch_count
+        
+        # Create task for the job; the wrapper waits on it separately, so
+        # cancelling the generation never interrupts the queue bookkeeping
         task = asyncio.create_task(
-            self._run_job_wrapper(job_id, args, kwargs)
+            self._generator._execute_generation(job.job_id, *job.args, **job.kwargs)
         )
-        self._running_jobs[job_id] = task
-        logger.info(f"Job {job_id} started. Running: {self.running_count}, Queued: {self.queued_count}")
+        self._running_jobs[job.job_id] = task
+        self._running_info[job.job_id] = job
+        asyncio.create_task(self._run_job_wrapper(job.job_id, task))
+        logger.info(f"Job {job.job_id} started. Running: {self.running_count}, Queued: {self.queued_count}")
     
-    async def _run_job_wrapper(self, job_id: int, args: tuple, kwargs: dict):
-        """Wrapper to run job and handle completion."""
+    async def _run_job_wrapper(self, job_id: int, task: asyncio.Task):
+        """Wait for a job's task and handle completion."""
         try:
-            await self._generator._execute_generation(job_id, *args, **kwargs)
+            await task
+        except asyncio.CancelledError:
+            logger.info(f"Job {job_id} cancelled")
         except Exception as e:
             logger.exception(f"Job {job_id} failed with exception")
         finally:
-            await self._on_job_complete(job_id)
+            await self._on_job_complete(job_id, preempted=task.cancelled() and job_id in self._preempted)
     
-    async def _on_job_complete(self, job_id: int):
+    async def _on_job_complete(self, job_id: int, preempted: bool = False):
         """Handle job completion - remove from running and start next queued job."""
         async with self._lock:
             # Remove from running
-            if job_id in self._running_jobs:
-                del self._running_jobs[job_id]
+            self._running_jobs.pop(job_id, None)
+            job = self._running_info.pop(job_id, None)
+            self._preempted.discard(job_id)
+            
+            if preempted and job:
+                # Back to the front of its user's bulk queue
+                self._pending.insert(0, job)
+                self._mark_requeued(job_id)
             
             logger.info(f"Job {job_id} completed. Running: {self.running_count}, Queued: {self.queued_count}")
             
             # Start next queued job if any
-            if self._pending_queue and self.running_count < self.max_concurrent:
-                next_job_id, next_args, next_kwargs = self._pending_queue.popleft()
-                logger.info(f"Starting queued job {next_job_id}")
+            if self._pending and self.running_count < self.max_concurrent and not self.draining:
+                next_job = self._dispatch_order()[0]
+                self._pending.remove(next_job)
+                logger.info(f"Starting queued job {next_job.job_id}")
                 
                 # Update queue positions for remaining jobs
-                for i, (queued_id, _, _) in enumerate(self._pending_queue):
-                    self._update_queued_status(queued_id, i + 1)
+                self._refresh_queued_status()
                 
-                await self._start_job(next_job_id, next_args, next_kwargs)
+                await self._start_job(next_job)
+    
+    async def drain(self, timeout: float) -> List[int]:
+        """
+        Stop starting jobs and wait up to `timeout` seconds for running ones.
+        Returns the IDs of jobs still running at the deadline.
+        """
+        self.draining = True
+        tasks = list(self._running_jobs.values())
+        if tasks:
+            logger.info(f"Waiting up to {timeout}s for {len(tasks)} running job(s) to finish")
+            await asyncio.wait(tasks, timeout=timeout)
+        return [job_id for job_id, task in self._running_jobs.items() if not task.done()]
     
     async def cancel_job(self, job_id: int) -> bool:
         """Cancel a job (running or queued)."""
@@ -235,12 +415,11 @@ class JobQueue:
                 return True
             
             # Check if queued
-            for i, (queued_id, _, _) in enumerate(self._pending_queue):
-                if queued_id == job_id:
-                    del self._pending_queue[i]
+            for job in self._pending:
+                if job.job_id == job_id:
+                    self._pending.remove(job)
                     # Update positions for remaining jobs
-                    for j, (qid, _, _) in enumerate(self._pending_queue):
-                        self._update_queued_status(qid, j + 1)
+                    self._refresh_queued_status()
                     return True
             
             return False
@@ -302,10 +481,11 @@ def parse_kernel_version(banner: str) -> Optional[dict]:
         if not kernel_match:
             kernel_match = re.search(r'(\d+\.\d+\.\d+-\d+-amd64)', banner)
     elif is_ubuntu:
-        # Ubuntu pattern: 5.15.0-91-generic
-        kernel_match = re.search(r'Linux version (\d+\.\d+\.\d+-\d+-[a-z]+)', banner)
+        # Ubuntu pattern: 5.15.0-91-generic, 5.15.0-1050-aws, 5.15.0-1045-intel-iotg
+        kernel_match = re.search(r'Linux version (\d+\.\d+\.\d+-\d+-[a-z][a-z0-9-]*)', banner)
         if not kernel_match:
-            kernel_match = re.search(r'(\d+\.\d+\.\d+-\d+-generic)', banner)
+            flavors = "|".join(sorted(UBUNTU_FLAVORS, key=len, reverse=True))
+            kernel_match = re.search(rf'(\d+\.\d+\.\d+-\d+-(?:{flavors}))\b', banner)
     elif is_fedora:
         # Fedora pattern: 6.5.6-300.fc39.x86_64
         kernel_match = re.search(r'Linux version (\d+\.\d+\.\d+-\d+\.fc\d+\.[a-z0-9_]+)', banner)
@@ -543,12 +723,14 @@ class SymbolGenerator:
             self._connect_docker()
         return self.docker_client is not None
     
-    def get_queue_status(self) -> Dict[str, int]:
+    def get_queue_status(self) -> Dict[str, Any]:
         """Get current queue status."""
         return {
             "running": job_queue.running_count,
             "queued": job_queue.queued_count,
+            "queued_by_priority": job_queue.queued_by_priority(),
             "max_concurrent": job_queue.max_concurrent,
+            "draining": job_queue.draining,
         }
     
     def _broadcast_job_update(self, job: SymbolGeneration):
@@ -573,6 +755,8 @@ class SymbolGenerator:
                 "alma_version": job.alma_version.value if job.alma_version else None,
                 "status": status_value,
                 "status_message": job.status_message,
+                "priority": job.priority.value if job.priority else None,
+                "submitted_by": job.submitted_by,
                 "error_message": job.error_message,
                 "symbol_filename": job.symbol_filename,
                 "symbol_file_size": job.symbol_file_size,
@@ -650,6 +834,7 @@ class SymbolGenerator:
             # Process logs with timeout
             timeout_seconds = 1800  # 30 minutes total
             start_time = datetime.utcnow()
+            last_scratch_check = start_time
             
             while True:
                 # Check timeout
@@ -659,6 +844,15 @@ class SymbolGenerator:
                     container.kill()
                     return -1
                 
+                # Check scratch usage
+                if JOB_SCRATCH_QUOTA and (datetime.utcnow() - last_scratch_check).total_seconds() > SCRATCH_CHECK_INTERVAL:
+                    last_scratch_check = datetime.utcnow()
+                    used = await asyncio.to_thread(self._scratch_usage, job_id, container)
+                    if used > JOB_SCRATCH_QUOTA:
+                        logger.error(f"[Job {job_id}] Scratch usage {used} exceeds quota {JOB_SCRATCH_QUOTA}")
+                        container.kill()
+                        return EXIT_SCRATCH_QUOTA
+                
                 # Check if container finished
                 container.reload()
                 if container.status in ('exited', 'dead'):
@@ -709,6 +903,138 @@ class SymbolGenerator:
             logger.exception(f"Error monitoring container for job {job_id}")
             return -1
     
+    def _scratch_usage(self, job_id: int, container) -> int:
+        """Bytes written by a job: the container's writable layer plus its output directory."""
+        used = 0
+        try:
+            # Sizes are only reported by the list endpoint
+            listed = self.docker_client.api.containers(all=True, filters={"id": container.id}, size=True)
+            if listed:
+                used += listed[0].get("SizeRw") or 0
+        except APIError as e:
+            logger.warning(f"Failed to read container size for job {job_id}: {e}")
+        
+        temp_dir = os.path.join(SYMBOLS_DIR, f"temp_{job_id}")
+        for dirpath, _, filenames in os.walk(temp_dir):
+            for filename in filenames:
+                try:
+                    used += os.path.getsize(os.path.join(dirpath, filename))
+                except OSError:
+                    pass
+        return used
+    
+    def _enforce_storage_quota(self, db: Session, keep_path: Optional[str]):
+        """
+        Evict least recently used symbol files until storage fits SYMBOL_STORAGE_QUOTA.
+        
+        Jobs answered from an existing file share its path, so each file counts
+        once and is as recent as the last use through any of its jobs.
+        """
+        if not SYMBOL_STORAGE_QUOTA:
+            return
+        
+        last_used = func.max(func.coalesce(SymbolGeneration.last_accessed_at, SymbolGeneration.completed_at))
+        stored = db.query(
+            SymbolGeneration.symbol_file_path,
+            func.max(SymbolGeneration.symbol_filename),
+            func.max(SymbolGeneration.symbol_file_size),
+        ).filter(
+            SymbolGeneration.status == SymGenStatus.COMPLETED,
+            SymbolGeneration.symbol_file_path.isnot(None),
+        ).group_by(SymbolGeneration.symbol_file_path).order_by(last_used.asc()).all()
+        used = sum(size or 0 for _, _, size in stored)
+        
+        for path, filename, size in stored:
+            if used <= SYMBOL_STORAGE_QUOTA:
+                break
+            # Never evict the file that was just generated
+            if path == keep_path:
+                continue
+            
+            try:
+                if os.path.exists(path):
+                    os.remove(path)
+            except OSError as e:
+                logger.warning(f"Failed to evict {path}: {e}")
+                continue
+            
+            logger.info(f"Evicted symbol file {filename} ({size} bytes) to stay within quota")
+            used -= size or 0
+            self._mark_evicted(
+                db, path,
+                "Symbol file evicted to stay within the storage quota; generate it again to restore it",
+            )
+        
+        if used > SYMBOL_STORAGE_QUOTA:
+            logger.warning(f"Symbol storage ({used} bytes) still exceeds quota ({SYMBOL_STORAGE_QUOTA} bytes)")
+    
+    def _mark_evicted(self, db: Session, path: str, reason: str):
+        """Mark every completed job pointing at a removed symbol file as evicted."""
+        jobs = db.query(SymbolGeneration).filter(
+            SymbolGeneration.status == SymGenStatus.COMPLETED,
+            SymbolGeneration.symbol_file_path == path,
+        ).all()
+        for job in jobs:
+            job.status = SymGenStatus.EVICTED
+            job.error_message = reason
+            job.symbol_file_path = None
+        db.commit()
+        for job in jobs:
+            self._broadcast_job_update(job)
+    
+    def expire_symbols(self) -> int:
+        """
+        Remove symbol files not generated or downloaded within SYMBOL_RETENTION, returning how many.
+        
+        A file shared by several jobs expires only once none of them used it within the period.
+        """
+        if not SYMBOL_RETENTION:
+            return 0
+        
+        cutoff = datetime.utcnow() - timedelta(seconds=SYMBOL_RETENTION)
+        db = SessionLocal()
+        try:
+            expired = db.query(
+                SymbolGeneration.symbol_file_path,
+                func.max(SymbolGeneration.symbol_filename),
+                func.max(SymbolGeneration.symbol_file_size),
+            ).filter(
+                SymbolGeneration.status == SymGenStatus.COMPLETED,
+                SymbolGeneration.symbol_file_path.isnot(None),
+            ).group_by(SymbolGeneration.symbol_file_path).having(
+                func.max(func.coalesce(SymbolGeneration.last_accessed_at, SymbolGeneration.completed_at)) < cutoff
+            ).all()
+            
+            removed = 0
+            for path, filename, size in expired:
+                try:
+                    if os.path.exists(path):
+                        os.remove(path)
+                except OSError as e:
+                    logger.warning(f"Failed to remove expired {path}: {e}")
+                    continue
+                
+                logger.info(f"Removed expired symbol file {filename} ({size} bytes)")
+                self._mark_evicted(
+                    db, path,
+                    "Symbol file expired after the retention period; generate it again to restore it",
+                )
+                removed += 1
+            return removed
+        finally:
+            db.close()
+    
+    async def run_retention_sweeps(self):
+        """Expire stored symbol files every RETENTION_SWEEP_INTERVAL seconds until cancelled."""
+        while True:
+            try:
+                removed = await asyncio.to_thread(self.expire_symbols)
+                if removed:
+                    logger.info(f"Retention sweep removed {removed} symbol file(s)")
+            except Exception:
+                logger.exception("Retention sweep failed")
+            await asyncio.sleep(RETENTION_SWEEP_INTERVAL)
+    
     def _generate_ubuntu_script(self, kernel_version: str, codename: str) -> str:
         """Generate the shell script to run inside Ubuntu container."""
         return f'''#!/bin/bash
@@ -755,9 +1081,13 @@ apt-get update -qq
 
 # Install kernel debug symbols package
 echo ">>> Installing kernel debug symbols for {kernel_version}..."
+# Signed flavors (generic, aws, azure, gcp, ...) may only ship the unsigned image's dbgsym
 if ! apt-get install -y -qq linux-image-{kernel_version}-dbgsym 2>/dev/null; then
-    echo "ERROR: Could not find/install debug symbols for kernel {kernel_version}"
-    exit 1
+    echo ">>> Trying unsigned image package name..."
+    if ! apt-get install -y -qq linux-image-unsigned-{kernel_version}-dbgsym 2>/dev/null; then
+        echo "ERROR: Could not find/install debug symbols for kernel {kernel_version}"
+        exit 1
+    fi
 fi
 
 # Install linux-modules package to get System.map
@@ -1169,6 +1499,85 @@ echo "=== Symbol generation completed successfully ==="
 ls -la "$OUTPUT_DIR"
 '''
 
+    async def resume_jobs(self):
+        """
+        Requeue jobs left unfinished by the previous server process.
+        
+        Jobs that were queued resume in their original order; jobs that were
+        running had their containers stopped, so they start over.
+        """
+        db = SessionLocal()
+        try:
+            jobs = db.query(SymbolGeneration).filter(
+                SymbolGeneration.status.in_(ACTIVE_STATUSES)
+            ).order_by(SymbolGeneration.created_at.asc()).all()
+            
+            for job in jobs:
+                if job.status != SymGenStatus.PENDING:
+                    self._remove_leftovers(job)
+                    job.status = SymGenStatus.PENDING
+                    job.status_message = "Interrupted by a server restart, requeued"
+                    job.container_id = None
+                    db.commit()
+                
+                await self.generate_symbol(
+                    job.id,
+                    job.kernel_version,
+                    job.distro,
+                    job.ubuntu_version,
+                    job.debian_version,
+                    job.fedora_version,
+                    job.centos_version,
+                    job.rhel_version,
+                    job.oracle_version,
+                    job.rocky_version,
+                    job.alma_version,
+                    priority=job.priority or JobPriority.INTERACTIVE,
+                    user=job.submitted_by or ANONYMOUS_USER,
+                )
+            
+            if jobs:
+                logger.info(f"Resumed {len(jobs)} unfinished job(s)")
+        finally:
+            db.close()
+    
+    async def shutdown(self, timeout: float = SHUTDOWN_GRACE_PERIOD):
+        """
+        Drain the queue: start no new jobs, let running ones finish within
+        `timeout`, and stop the rest so they restart cleanly on the next start.
+        Queued jobs stay pending in the database and are resumed by resume_jobs.
+        """
+        unfinished = await job_queue.drain(timeout)
+        for job_id in unfinished:
+            logger.warning(f"Job {job_id} did not finish before shutdown, stopping it")
+            await job_queue.cancel_job(job_id)
+            
+            db = SessionLocal()
+            try:
+                job = db.query(SymbolGeneration).filter(SymbolGeneration.id == job_id).first()
+                if job:
+                    job.status = SymGenStatus.PENDING
+                    job.status_message = "Interrupted by server shutdown, resumes on restart"
+                    job.container_id = None
+                    db.commit()
+            finally:
+                db.close()
+        
+        if job_queue.queued_count:
+            logger.info(f"{job_queue.queued_count} queued job(s) will resume on restart")
+    
+    def _remove_leftovers(self, job: SymbolGeneration):
+        """Remove the container and scratch directory of a job that will start over."""
+        if job.container_id and self.is_available():
+            try:
+                self.docker_client.containers.get(job.container_id).remove(force=True)
+            except Exception as e:
+                logger.debug(f"No container to remove for job {job.id}: {e}")
+        
+        temp_dir = os.path.join(SYMBOLS_DIR, f"temp_{job.id}")
+        if os.path.exists(temp_dir):
+            shutil.rmtree(temp_dir, ignore_errors=True)
+    
     async def generate_symbol(
         self,
         job_id: int,
@@ -1182,6 +1591,8 @@ ls -la "$OUTPUT_DIR"
         oracle_version: Optional[OracleVersion] = None,
         rocky_version: Optional[RockyVersion] = None,
         alma_version: Optional[AlmaVersion] = None,
+        priority: JobPriority = JobPriority.INTERACTIVE,
+        user: str = ANONYMOUS_USER,
     ) -> bool:
         """
         Submit a symbol generation job to the queue.
@@ -1193,6 +1604,8 @@ ls -la "$OUTPUT_DIR"
             kernel_version: Kernel version (e.g., "5.15.0-91-generic" or "5.10.0-28-amd64")
             distro: Linux distribution
             *_version: Version enum for the specific distro
+            priority: Scheduling class; interactive jobs preempt bulk ones
+            user: Submitter, used to share slots fairly between users
             
         Returns:
             True if job was submitted successfully
@@ -1210,6 +1623,8 @@ ls -la "$OUTPUT_DIR"
             oracle_version,
             rocky_version,
             alma_version,
+            priority=priority,
+            user=user,
         )
         
         if started:
@@ -1388,6 +1803,11 @@ ls -la "$OUTPUT_DIR"
             logs = container.logs().decode('utf-8')
             logger.info(f"Container logs for job {job_id}:\n{logs}")
             
+            if exit_code == EXIT_SCRATCH_QUOTA:
+                self._update_status(db, job_id, SymGenStatus.FAILED,
+                                  error=f"Job exceeded its scratch disk quota of {JOB_SCRATCH_QUOTA} bytes")
+                return False
+            
             if exit_code != 0:
                 self._update_status(db, job_id, SymGenStatus.FAILED,
                                   error=f"Container exited with code {exit_code}: {logs[-2000:]}")
@@ -1419,9 +1839,12 @@ ls -la "$OUTPUT_DIR"
                 job.symbol_file_path = final_path
                 job.symbol_file_size = file_size
                 job.completed_at = datetime.utcnow()
+                job.last_accessed_at = job.completed_at
                 db.commit()
                 self._broadcast_job_update(job)  # Broadcast COMPLETED status
             
+            self._enforce_storage_quota(db, final_path)
+            
             logger.info(f"Symbol generation completed: {symbol_filename}")
             return True
             
@@ -1446,25 +1869,23 @@ ls -la "$OUTPUT_DIR"
             db.close()
     
     async def cancel_job_async(self, job_id: int) -> bool:
-        """Cancel a job (async version for queue cancellation)."""
-        # Try to cancel from queue first
+        """
+        Cancel a job: drop it from the queue or stop its generation task (whose
+        cleanup removes the container and scratch directory), then record it.
+        """
         await job_queue.cancel_job(job_id)
         return self.cancel_job(job_id)
     
     def cancel_job(self, job_id: int) -> bool:
-        """Cancel a running symbol generation job."""
+        """Stop a job's container, remove its scratch directory and mark it cancelled."""
         db = SessionLocal()
         try:
             job = db.query(SymbolGeneration).filter(SymbolGeneration.id == job_id).first()
             if not job:
                 return False
             
-            # Try to cancel from queue (sync version - checks if queued)
-            queue_pos = job_queue.get_queue_position(job_id)
-            if queue_pos:
-                # Job is queued, will be removed by async cancel
-                logger.info(f"Job {job_id} is queued at position {queue_pos}, marking as cancelled")
-            
+            # The generation task normally did this already; a job whose task
+            # is gone (e.g. after a restart) may still have a container
             if job.container_id and self.is_available():
                 try:
                     container = self.docker_client.containers.get(job.container_id)
@@ -1473,10 +1894,17 @@ ls -la "$OUTPUT_DIR"
                 except Exception as e:
                     logger.warning(f"Failed to stop container: {e}")
             
-            job.status = SymGenStatus.FAILED
+            temp_dir = os.path.join(SYMBOLS_DIR, f"temp_{job_id}")
+            if os.path.exists(temp_dir):
+                shutil.rmtree(temp_dir, ignore_errors=True)
+            
+            job.status = SymGenStatus.CANCELLED
+            job.status_message = "Cancelled"
             job.error_message = "Cancelled by user"
             job.completed_at = datetime.utcnow()
             db.commit()
+            self._broadcast_job_update(job)
+            logger.info(f"Job {job_id} cancelled")
             return True
             
         finally:
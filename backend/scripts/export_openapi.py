@@ -0,0 +1,57 @@
+"""
+Export the REST API's OpenAPI document without starting the server.
+
+Usage (from backend/):
+    python scripts/export_openapi.py                 # print to stdout
+    python scripts/export_openapi.py -o openapi.json
+    python scripts/export_openapi.py -o openapi.json --check
+
+With --check, nothing is written; the exit status is 1 when the file differs
+from what the code describes, so a committed copy can be verified in CI.
+"""
+
+import argparse
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from app.main import app  # noqa: E402
+
+
+def render() -> str:
+    """The OpenAPI document as stable, diff-friendly JSON."""
+    return json.dumps(app.openapi(), indent=2, sort_keys=True) + "\n"
+
+
+def main() -> int:
+    parser = argparse.ArgumentParser(description="Export the Symgen OpenAPI document")
+    parser.add_argument("-o", "--output", help="File to write (default: stdout)")
+    parser.add_argument("--check", action="store_true", help="Fail if the output file is out of date")
+    args = parser.parse_args()
+
+    document = render()
+    if args.check:
+        if not args.output:
+            parser.error("--check requires --output")
+        try:
+            with open(args.output) as f:
+                current = f.read()
+        except FileNotFoundError:
+            current = ""
+        if current != document:
+            print(f"{args.output} is out of date; run scripts/export_openapi.py -o {args.output}", file=sys.stderr)
+            return 1
+        return 0
+
+    if args.output:
+        with open(args.output, "w") as f:
+            f.write(document)
+    else:
+        sys.stdout.write(document)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -1,4 +1,16 @@
-use clap::{Parser, Subcommand};
+use chrono::NaiveDate;
+use clap::{ArgGroup, Parser, Subcommand};
+use clap_complete::Shell;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::time::Duration;
+use symgen_core::acquisition::DEFAULT_DEBUGINFOD_URL;
+use symgen_core::distros::Arch;
+use symgen_core::docker::{OutputTransfer, RemovePolicy, Runtime};
+use symgen_core::jobs::JobStatus;
+use symgen_core::script::Compression;
+
+use crate::pack;
 
 /// Volatility3 Linux Symbol Generator
 ///
@@ -9,51 +21,547 @@ use clap::{Parser, Subcommand};
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
 pub struct Cli {
-    /// Output in JSON format
+    /// Output in JSON format: the result on stdout, messages on stderr
     #[arg(long, global = true)]
     pub json: bool,
 
+    /// With --json, write messages to stdout too, making it one NDJSON stream
+    #[arg(long, global = true, requires = "json")]
+    pub ndjson: bool,
+
     /// Verbose output
     #[arg(short, long, global = true)]
     pub verbose: bool,
 
+    /// Configuration file (default: ~/.config/symgen/config.toml)
+    #[arg(long, global = true, env = "SYMGEN_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// Append a hash-chained record of this invocation to a JSONL audit log
+    #[arg(long, global = true, env = "SYMGEN_AUDIT_LOG")]
+    pub audit_log: Option<PathBuf>,
+
+    /// Cache directory for symbol files and run history; may be a shared network directory
+    #[arg(long, global = true, env = "SYMGEN_CACHE_DIR")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Move symbol files into a content-addressed store under this directory,
+    /// leaving symlinks in the output directory
+    #[arg(
+        long = "store",
+        global = true,
+        value_name = "DIR",
+        env = "SYMGEN_STORE"
+    )]
+    pub store_dir: Option<PathBuf>,
+
+    /// Upload each finished symbol file and a checksum to s3://bucket/prefix
+    /// (credentials from AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY)
+    #[arg(long, global = true, value_name = "URL", env = "SYMGEN_UPLOAD")]
+    pub upload: Option<String>,
+
+    /// Skip the upload configured by `upload` or SYMGEN_UPLOAD for this run
+    #[arg(long, global = true)]
+    pub no_upload: bool,
+
+    /// Container runtime to use (default: auto-detect Docker, then Podman)
+    #[arg(long, global = true, value_enum, env = "SYMGEN_RUNTIME")]
+    pub runtime: Option<Runtime>,
+
+    /// Set a template variable used as `{{KEY}}` in configured script commands (repeatable)
+    #[arg(long = "set", global = true, value_name = "KEY=VALUE", value_parser = parse_var)]
+    pub vars: Vec<(String, String)>,
+
+    /// PEM CA certificate to trust inside the containers, e.g. a TLS-intercepting
+    /// proxy's root (repeatable)
+    #[arg(long = "ca-cert", global = true, value_name = "PEM")]
+    pub ca_certs: Vec<PathBuf>,
+
+    /// Registry prefix for images named without a registry, e.g.
+    /// registry.corp.local/mirror pulls registry.corp.local/mirror/ubuntu:22.04
+    #[arg(
+        long,
+        global = true,
+        value_name = "PREFIX",
+        env = "SYMGEN_REGISTRY_MIRROR"
+    )]
+    pub registry_mirror: Option<String>,
+
+    /// Registry credentials in Docker config.json format (default:
+    /// ~/.docker/config.json; Podman's auth.json works too)
+    #[arg(long, global = true, value_name = "FILE")]
+    pub registry_auth: Option<PathBuf>,
+
+    /// HTTP(S) proxy for apt, dnf and downloads inside the containers
+    /// (default: the host's HTTP_PROXY/HTTPS_PROXY)
+    #[arg(long, global = true, value_name = "URL")]
+    pub proxy: Option<String>,
+
+    /// Shell script run inside every container before the first step, e.g. to
+    /// register a RHEL subscription or point the package manager at a mirror
+    #[arg(long, global = true, value_name = "FILE")]
+    pub pre_script: Option<PathBuf>,
+
+    /// Shell script run inside the container once the symbol file is written,
+    /// e.g. to copy extra artifacts to `$OUTPUT_DIR`
+    #[arg(long, global = true, value_name = "FILE")]
+    pub post_script: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
 #[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum Commands {
     /// Generate a Volatility3 symbol file for a Linux kernel
-    #[command(alias = "gen", after_help = "EXAMPLES:
+    #[command(
+        alias = "gen",
+        after_help = "EXAMPLES:
     # Generate from kernel banner (auto-detects distro, release, and kernel):
     symgen generate -b \"Linux version 5.15.0-91-generic (buildd@...) (gcc (Ubuntu 11.4.0-1ubuntu1~22.04) ...)\"
+    symgen generate -b @banner.txt
+    vol -f mem.lime banners.Banners | symgen generate -b -
+
+    # Generate for every banner Volatility found:
+    vol -r json -f mem.lime banners.Banners > banners.json
+    symgen generate --from-vol3-banners banners.json
 
     # Generate with explicit parameters:
     symgen generate -k 5.15.0-91-generic -d ubuntu -r 22.04
     symgen generate -k 6.1.0-18-amd64 -d debian -r 12
-    symgen generate -k 5.14.0-427.el9 -d rocky -r 9")]
+    symgen generate -k 5.14.0-427.el9 -d rocky -r 9"
+    )]
     Generate {
-        /// Kernel banner string (from /proc/version or volatility banner output).
+        /// Kernel banner string (from /proc/version or volatility banner output),
+        /// `-` to read it from stdin or `@FILE` to read it from a file.
         /// Auto-detects kernel version, distribution, and version.
         /// Example: "Linux version 5.15.0-91-generic ... (gcc (Ubuntu 11.4.0-1ubuntu1~22.04) ...)"
         #[arg(short, long, conflicts_with_all = ["kernel", "distro", "version"])]
-        banner: Option<String>,
+        banner: Option<OsString>,
 
         /// Kernel version (e.g., 5.15.0-91-generic, 6.1.0-18-amd64)
-        #[arg(short, long, required_unless_present = "banner")]
+        #[arg(short, long, required_unless_present_any = ["banner", "from_vol3_banners", "kernel_snap"])]
         kernel: Option<String>,
 
-        /// Linux distribution (ubuntu, debian, fedora, centos, rhel, oracle, rocky, alma)
-        #[arg(short, long, required_unless_present = "banner")]
+        /// Linux distribution (ubuntu, debian, fedora, centos, rhel, oracle, rocky, alma, kali, wsl;
+        /// derivatives such as mint, lmde, pop and neon map to their base release)
+        #[arg(short, long, required_unless_present_any = ["banner", "from_vol3_banners", "kernel_snap", "try_distros"])]
         distro: Option<String>,
 
         /// Distribution version (e.g., 22.04 for Ubuntu, 12 for Debian, 40 for Fedora)
-        #[arg(short = 'r', long = "release", required_unless_present = "banner")]
+        #[arg(short = 'r', long = "release", required_unless_present_any = ["banner", "from_vol3_banners", "try_distros"])]
         distro_version: Option<String>,
 
+        /// Distributions to try in order when the banner names none, e.g.
+        /// `ubuntu,debian,rhel`; `DISTRO:RELEASE` picks the release, else the newest
+        /// is tried. The one that works is remembered for the kernel.
+        #[arg(
+            long = "try",
+            value_name = "DISTRO[:RELEASE]",
+            value_delimiter = ',',
+            value_parser = parse_try,
+            conflicts_with_all = ["distro", "distro_version", "from_vol3_banners", "kernel_snap"]
+        )]
+        try_distros: Vec<(String, Option<String>)>,
+
         /// Output directory for the symbol file (default: current directory)
         #[arg(short, long)]
         output_dir: Option<String>,
+
+        /// Architecture of the target kernel (default: detected from the banner or
+        /// kernel name, else amd64; non-amd64 builds dwarf2json from source;
+        /// riscv64 and armhf are Ubuntu only)
+        #[arg(long, value_enum)]
+        arch: Option<Arch>,
+
+        /// Skip signature checks on the Ubuntu proposed/ddebs repositories
+        /// (for archived releases or expired signing keys)
+        #[arg(long)]
+        allow_unauthenticated: bool,
+
+        /// Ignore apt Release file dates when the host clock is skewed
+        /// (containers share the host clock, so it cannot be corrected inside them)
+        #[arg(long)]
+        fix_time: bool,
+
+        /// Do not enable the Ubuntu -proposed pocket
+        #[arg(long)]
+        no_proposed: bool,
+
+        /// Do not enable the Ubuntu -updates debug symbol pocket
+        #[arg(long)]
+        no_updates: bool,
+
+        /// Fail instead of retrying other Ubuntu/Debian releases when the
+        /// selected release has no debug symbols for the kernel
+        #[arg(long)]
+        no_fallback: bool,
+
+        /// Extra argument passed through to `dwarf2json linux` (repeatable),
+        /// e.g. --converter-arg=--elf --converter-arg=/usr/lib/debug/module.ko.debug
+        #[arg(long = "converter-arg", value_name = "ARG", allow_hyphen_values = true)]
+        converter_args: Vec<String>,
+
+        /// Where to xz-compress the symbol file
+        #[arg(long, value_enum, default_value_t = Compression::Container)]
+        compress: Compression,
+
+        /// Drop types and enums not reachable from any symbol to shrink the file.
+        /// Plugins that look up such types by name will no longer find them.
+        #[arg(long)]
+        minimal: bool,
+
+        /// Record the symgen version, tag and source package in the symbol
+        /// file's metadata (rewrites the file once more)
+        #[arg(long)]
+        annotate: bool,
+
+        /// Also copy the symbol file into the local Volatility3's `symbols/linux`
+        /// (VOLATILITY3_SYMBOL_PATH, else the installed volatility3 package)
+        #[arg(long)]
+        install_vol3: bool,
+
+        /// Do not copy the symbol file into Volatility3, even with
+        /// `install_vol3 = true` in the config
+        #[arg(long, conflicts_with = "install_vol3")]
+        no_install: bool,
+
+        /// Write `<file>.sha256` and a `<file>.manifest.json` chain-of-custody
+        /// record next to the symbol file
+        #[arg(long)]
+        manifest: bool,
+
+        /// Write a detached GPG signature (`<file>.asc`) of the symbol file,
+        /// and of the manifest with --manifest
+        #[arg(long)]
+        sign: bool,
+
+        /// GPG key ID or fingerprint to sign with (default: gpg's default key)
+        #[arg(long, value_name = "ID", env = "SYMGEN_GPG_KEY", requires = "sign")]
+        gpg_key: Option<String>,
+
+        /// Host command run over the decompressed symbol file before it is
+        /// compressed (repeatable); the JSON path is `$1` and `$SYMGEN_ISF`
+        #[arg(long = "post-process", value_name = "CMD")]
+        post_process: Vec<String>,
+
+        /// Case or engagement label to record with the run (e.g. CASE-2024-118)
+        #[arg(long, value_parser = parse_tag)]
+        tag: Option<String>,
+
+        /// How to retrieve the symbol file from the container
+        #[arg(long, value_enum, default_value_t = OutputTransfer::Bind)]
+        transfer: OutputTransfer,
+
+        /// Container image to run instead of the release's official one, e.g. an
+        /// internal mirror of ubuntu:22.04 (no fallback to other releases then)
+        #[arg(long, value_name = "IMAGE", conflicts_with_all = ["from_vol3_banners", "try_distros"])]
+        image: Option<String>,
+
+        /// Kill the container and fail when a run takes longer than this many
+        /// minutes (hung mirrors, stuck package managers); JSON `"error_code": "timeout"`
+        #[arg(long, value_name = "MINUTES", value_parser = clap::value_parser!(u64).range(1..))]
+        timeout: Option<u64>,
+
+        /// When to remove the container after the run
+        #[arg(long, value_enum, default_value_t = RemovePolicy::Always)]
+        rm_policy: RemovePolicy,
+
+        /// Keep the container when the script fails and print how to get a shell
+        /// in it (same as --rm-policy on-success)
+        #[arg(long, conflicts_with = "rm_policy")]
+        keep_on_failure: bool,
+
+        /// Save the full container output next to the symbol file (<symbol_file>.log)
+        #[arg(long)]
+        log_file: bool,
+
+        /// Do not reuse a symbol file from the cache directory
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Generate the symbol file again, without the cache, when it already
+        /// exists in the output directory, and replace it
+        #[arg(long, conflicts_with = "skip_existing")]
+        force: bool,
+
+        /// Succeed without doing anything when the symbol file already exists
+        /// (exit status 0 rather than 3; JSON `"status": "skipped"`)
+        #[arg(long)]
+        skip_existing: bool,
+
+        /// Use a vmlinux, .ddeb/.deb or .rpm debug package from the host
+        /// instead of the distribution's repositories
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["vmlinux_url", "build_id"])]
+        vmlinux: Option<PathBuf>,
+
+        /// Download the vmlinux or debug package from a URL
+        #[arg(long, value_name = "URL", conflicts_with = "build_id")]
+        vmlinux_url: Option<String>,
+
+        /// Reuse the types of an ISF generated for an earlier upload with the same
+        /// ABI and convert only the new System.map (needs -b for the banner)
+        #[arg(
+            long,
+            value_name = "ISF",
+            requires = "banner",
+            conflicts_with_all = ["vmlinux", "vmlinux_url", "build_id", "converter_args"]
+        )]
+        delta_from: Option<PathBuf>,
+
+        /// Fetch the debuginfo for this kernel build ID from a debuginfod server
+        #[arg(long, value_parser = parse_build_id)]
+        build_id: Option<String>,
+
+        /// debuginfod server used with --build-id
+        #[arg(long, value_name = "URL", default_value = DEFAULT_DEBUGINFOD_URL, env = "DEBUGINFOD_URL")]
+        debuginfod_url: String,
+
+        /// JSON output of `vol -r json banners.Banners`; generates symbols for every
+        /// distinct banner in it that has none yet
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with_all = ["banner", "kernel", "distro", "distro_version", "vmlinux", "vmlinux_url", "build_id", "delta_from"]
+        )]
+        from_vol3_banners: Option<PathBuf>,
+
+        /// Print the generation script instead of running it, or write it to FILE;
+        /// no container runtime is needed (for running it by hand, e.g. offline)
+        #[arg(
+            long,
+            visible_alias = "dry-run",
+            value_name = "FILE",
+            num_args = 0..=1,
+            default_missing_value = "-",
+            conflicts_with_all = ["from_vol3_banners", "delta_from", "kernel_snap"]
+        )]
+        print_script: Option<PathBuf>,
+
+        /// Kernel snap of an Ubuntu Core device (e.g. /var/lib/snapd/snaps/pc-kernel_1606.snap);
+        /// the kernel release, upload and architecture are read from it. Give the
+        /// Core release as -r (Core 22 is 22.04). Needs unsquashfs on the host.
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with_all = ["banner", "kernel", "distro", "from_vol3_banners", "vmlinux", "vmlinux_url", "build_id"]
+        )]
+        kernel_snap: Option<PathBuf>,
+    },
+
+    /// Predict download size, duration and symbol file size from past runs
+    #[command(after_help = "EXAMPLES:
+    symgen estimate -k 5.15.0-91-generic -d ubuntu -r 22.04")]
+    Estimate {
+        /// Kernel version (e.g., 5.15.0-91-generic)
+        #[arg(short, long)]
+        kernel: String,
+
+        /// Linux distribution (ubuntu, debian, fedora, centos, rhel, oracle, rocky, alma, kali, wsl;
+        /// derivatives such as mint, lmde, pop and neon map to their base release)
+        #[arg(short, long)]
+        distro: String,
+
+        /// Distribution version (e.g., 22.04 for Ubuntu, 12 for Debian, 40 for Fedora)
+        #[arg(short = 'r', long = "release")]
+        distro_version: String,
+
+        /// Architecture of the target kernel
+        #[arg(long, value_enum, default_value_t = Arch::Amd64)]
+        arch: Arch,
+    },
+
+    /// Generate symbols for every kernel published for a release
+    #[command(after_help = "EXAMPLES:
+    symgen pack -d ubuntu -r 22.04 --since 2023-01
+    symgen pack -d debian -r 12 --list
+    symgen pack -d ubuntu -r 24.04 --since 2024-06 -j 4")]
+    Pack {
+        /// Linux distribution (ubuntu, debian, fedora, centos, rhel, oracle, rocky, alma, kali, wsl;
+        /// derivatives such as mint, lmde, pop and neon map to their base release)
+        #[arg(short, long)]
+        distro: String,
+
+        /// Distribution version (e.g., 22.04 for Ubuntu, 12 for Debian, 40 for Fedora)
+        #[arg(short = 'r', long = "release")]
+        distro_version: String,
+
+        /// Only kernels published on or after this date (YYYY-MM or YYYY-MM-DD)
+        #[arg(long, value_parser = pack::parse_since)]
+        since: Option<NaiveDate>,
+
+        /// Kernel flavor to collect (default: generic on Ubuntu, the architecture's flavor on Debian)
+        #[arg(long)]
+        flavor: Option<String>,
+
+        /// Architecture of the target kernels
+        #[arg(long, value_enum, default_value_t = Arch::Amd64)]
+        arch: Arch,
+
+        /// Output directory for the symbol files (default: current directory)
+        #[arg(short, long)]
+        output_dir: Option<String>,
+
+        /// Only list the kernels that would be generated
+        #[arg(long)]
+        list: bool,
+
+        /// Number of containers to run at once
+        #[arg(short, long, default_value_t = 1, value_parser = parse_jobs)]
+        jobs: usize,
+    },
+
+    /// Build local images with a release's tools, repositories and dwarf2json
+    /// already set up; generate uses them instead of the official images
+    #[command(after_help = "EXAMPLES:
+    symgen build-images -d ubuntu -r 22.04
+    symgen build-images -d rocky
+    symgen build-images -d ubuntu -r 24.04 --arch arm64")]
+    BuildImages {
+        /// Linux distribution (ubuntu, debian, fedora, centos, rhel, oracle, rocky, alma, kali, wsl)
+        #[arg(short, long)]
+        distro: String,
+
+        /// Distribution version to build (default: every supported release)
+        #[arg(short = 'r', long = "release")]
+        distro_version: Option<String>,
+
+        /// Architecture of the images
+        #[arg(long, value_enum, default_value_t = Arch::Amd64)]
+        arch: Arch,
+
+        /// Skip signature checks on the Ubuntu proposed/ddebs repositories
+        #[arg(long)]
+        allow_unauthenticated: bool,
+
+        /// Ignore apt Release file dates when the host clock is skewed
+        #[arg(long)]
+        fix_time: bool,
+    },
+
+    /// Merge kernel and module symbol files into a single ISF
+    #[command(after_help = "EXAMPLES:
+    symgen merge kernel.json.xz module1.json.xz -o combined.json.xz")]
+    Merge {
+        /// Symbol files to merge; the first one provides the metadata
+        #[arg(required = true, num_args = 2..)]
+        inputs: Vec<String>,
+
+        /// Output file (xz-compressed when the name ends in .xz)
+        #[arg(short, long = "output")]
+        output_file: String,
+    },
+
+    /// Write a Volatility3 remote ISF index over a directory of symbol files
+    #[command(after_help = "EXAMPLES:
+    symgen index /srv/symbols --base-url https://symbols.example.org
+    vol -f memory.lime --remote-isf-url https://symbols.example.org/banners.json linux.pslist")]
+    Index {
+        /// Directory to index, as served by the file host
+        dir: PathBuf,
+
+        /// URL the directory is served under
+        #[arg(long)]
+        base_url: String,
+
+        /// Index file (default: <dir>/banners.json)
+        #[arg(short, long = "output")]
+        output_file: Option<PathBuf>,
+    },
+
+    /// List kernels installed on a host and generate symbols for them
+    #[command(
+        name = "host-kernels",
+        after_help = "EXAMPLES:
+    symgen host-kernels
+    symgen host-kernels --ssh analyst@10.0.0.5 --generate -o ./symbols"
+    )]
+    HostKernels {
+        /// SSH destination (user@host) to inspect instead of the local machine
+        #[arg(long)]
+        ssh: Option<String>,
+
+        /// Generate symbols for every kernel found without asking
+        #[arg(long)]
+        generate: bool,
+
+        /// Output directory for the symbol files (default: current directory)
+        #[arg(short, long)]
+        output_dir: Option<String>,
+
+        /// Number of containers to run at once
+        #[arg(short, long, default_value_t = 1, value_parser = parse_jobs)]
+        jobs: usize,
+    },
+
+    /// Find kernel banners in a memory image and optionally generate symbols for them
+    #[command(after_help = "EXAMPLES:
+    symgen scan memory.lime
+    symgen scan memory.raw --generate -o ./symbols")]
+    Scan {
+        /// Raw memory dump or LiME file
+        image: PathBuf,
+
+        /// Generate symbols for every distinct banner found
+        #[arg(long)]
+        generate: bool,
+
+        /// Output directory for the symbol files (default: current directory)
+        #[arg(short, long)]
+        output_dir: Option<String>,
+    },
+
+    /// Check whether an existing symbol file fits the kernel of a banner
+    #[command(
+        name = "compare-kernel",
+        after_help = "EXAMPLES:
+    symgen compare-kernel -b @banner.txt Ubuntu_jammy_5.15.0-91-generic.json.xz"
+    )]
+    CompareKernel {
+        /// Kernel banner of the memory image (from /proc/version or the banners plugin),
+        /// `-` for stdin or `@FILE`
+        #[arg(short, long)]
+        banner: OsString,
+
+        /// Symbol file to check
+        isf: PathBuf,
+    },
+
+    /// Check that symbol files are complete, well-formed ISF and match their kernel
+    #[command(after_help = "EXAMPLES:
+    symgen verify Ubuntu_jammy_5.15.0-91-generic.json.xz
+    symgen verify -k 5.15.0-91-generic downloaded.json.xz
+    symgen verify /srv/symbols/*.json.xz")]
+    Verify {
+        /// Symbol files to check
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Kernel release the banner must name
+        #[arg(short, long)]
+        kernel: Option<String>,
+    },
+
+    /// Show what a symbol file contains: producer, banner, counts, base types and compression
+    #[command(after_help = "EXAMPLES:
+    symgen info Ubuntu_jammy_5.15.0-91-generic.json.xz
+    symgen --json info /srv/incoming/*.json.xz")]
+    Info {
+        /// Symbol files to inspect
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+
+    /// Package a symbol file with its checksums, provenance, signature and log
+    #[command(name = "export-bundle")]
+    ExportBundle {
+        /// Symbol file to package
+        symbol_file: PathBuf,
+
+        /// Bundle path (default: <symbol_file>.bundle.tar.gz)
+        #[arg(short, long = "output")]
+        output_file: Option<PathBuf>,
     },
 
     /// List supported distributions and versions
@@ -61,4 +569,323 @@ pub enum Commands {
 
     /// Check if Docker is available
     Check,
+
+    /// Check for a newer symgen, dwarf2json and distribution releases without changing anything
+    #[command(name = "upgrade-check")]
+    UpgradeCheck,
+
+    /// List symgen containers with their run labels and resource usage
+    Ps {
+        /// Also list stopped containers
+        #[arg(short, long)]
+        all: bool,
+    },
+
+    /// Remove containers left behind by symgen
+    Clean {
+        /// Also stop and remove containers that are still running
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Cancel a queued or running job on a Symgen web service
+    #[command(after_help = "EXAMPLES:
+    symgen cancel 42
+    symgen cancel 42 --server https://symgen.example.org")]
+    Cancel {
+        /// Job ID shown by the web interface
+        job_id: u64,
+
+        /// Base URL of the web service (default: `server` from the config, else http://localhost)
+        #[arg(long, env = "SYMGEN_SERVER")]
+        server: Option<String>,
+    },
+
+    /// Inspect and retry generation jobs recorded in the state directory
+    #[command(after_help = "EXAMPLES:
+    symgen jobs list --status failed
+    symgen jobs show 3f2a
+    symgen jobs retry 3f2a")]
+    Jobs {
+        #[command(subcommand)]
+        action: JobsAction,
+    },
+
+    /// List or prune the local symbol file cache
+    #[command(after_help = "EXAMPLES:
+    symgen cache list
+    symgen cache prune --older-than 90d
+    symgen cache prune --max-size 20G
+    symgen cache prune --tag CASE-2024-118")]
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    /// Maintain an index of local symbol files by kernel banner
+    #[command(
+        name = "banners-db",
+        after_help = "EXAMPLES:
+    symgen banners-db build /srv/symbols
+    symgen banners-db query -b @banner.txt
+    symgen banners-db export -o banners.json"
+    )]
+    BannersDb {
+        #[command(subcommand)]
+        action: BannersDbAction,
+    },
+
+    /// Print a shell completion script
+    #[command(after_help = "EXAMPLES:
+    symgen completions bash > /usr/share/bash-completion/completions/symgen
+    symgen completions zsh > /usr/share/zsh/site-functions/_symgen
+    symgen completions fish > ~/.config/fish/completions/symgen.fish")]
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+
+    /// Write man pages for symgen and each subcommand into a directory
+    #[command(after_help = "EXAMPLES:
+    symgen manpages /usr/share/man/man1")]
+    Manpages {
+        /// Directory to write the `.1` pages to (created if missing)
+        dir: PathBuf,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum BannersDbAction {
+    /// Index the symbol files under directories, dropping entries whose file is gone
+    Build {
+        /// Directories or symbol files to index (default: the cache and the store)
+        paths: Vec<PathBuf>,
+    },
+
+    /// Look up the symbol files for a kernel banner; fails when there are none
+    Query {
+        /// Kernel banner (from /proc/version or the banners plugin), `-` for stdin or `@FILE`
+        #[arg(short, long)]
+        banner: OsString,
+    },
+
+    /// Write the whole index as JSON
+    Export {
+        /// Output file (default: stdout)
+        #[arg(short, long = "output")]
+        output_file: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheAction {
+    /// List cached symbol files, most recently used first
+    List,
+
+    /// Remove cached symbol files, least recently used first
+    #[command(group(ArgGroup::new("limit").required(true).multiple(true)))]
+    Prune {
+        /// Remove files not generated or reused within this long (e.g. 90d, 12h)
+        #[arg(long, value_name = "AGE", value_parser = parse_age, group = "limit")]
+        older_than: Option<Duration>,
+
+        /// Then remove files until the cache fits in this size (e.g. 20G, 500M)
+        #[arg(long, value_name = "SIZE", value_parser = parse_size, group = "limit")]
+        max_size: Option<u64>,
+
+        /// Remove every cached symbol file
+        #[arg(long, group = "limit", conflicts_with_all = ["older_than", "max_size"])]
+        all: bool,
+
+        /// Only consider files generated by runs with this --tag; alone, removes all of them
+        #[arg(long, group = "limit")]
+        tag: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum JobsAction {
+    /// List recorded jobs, newest first
+    List {
+        /// Only list jobs with this status
+        #[arg(long, value_enum)]
+        status: Option<JobStatus>,
+
+        /// Only list jobs run with this --tag
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Maximum number of jobs to list
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Show a job's details and the end of its log
+    Show {
+        /// Job ID or a unique prefix of it
+        id: String,
+
+        /// Number of log lines to show
+        #[arg(long, default_value_t = 20)]
+        lines: usize,
+    },
+
+    /// Run a job again with the same kernel, release and output directory
+    Retry {
+        /// Job ID or a unique prefix of it
+        id: String,
+    },
+}
+
+impl Commands {
+    /// Subcommand name as typed on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Generate { .. } => "generate",
+            Self::Estimate { .. } => "estimate",
+            Self::Pack { .. } => "pack",
+            Self::BuildImages { .. } => "build-images",
+            Self::Merge { .. } => "merge",
+            Self::Index { .. } => "index",
+            Self::HostKernels { .. } => "host-kernels",
+            Self::Scan { .. } => "scan",
+            Self::CompareKernel { .. } => "compare-kernel",
+            Self::Verify { .. } => "verify",
+            Self::Info { .. } => "info",
+            Self::ExportBundle { .. } => "export-bundle",
+            Self::List => "list",
+            Self::Check => "check",
+            Self::UpgradeCheck => "upgrade-check",
+            Self::Ps { .. } => "ps",
+            Self::Clean { .. } => "clean",
+            Self::Cancel { .. } => "cancel",
+            Self::Jobs { .. } => "jobs",
+            Self::Cache { .. } => "cache",
+            Self::BannersDb { .. } => "banners-db",
+            Self::Completions { .. } => "completions",
+            Self::Manpages { .. } => "manpages",
+        }
+    }
+}
+
+/// Build IDs are hex SHA1 (or longer) digests as printed by `readelf -n`
+fn parse_build_id(id: &str) -> Result<String, String> {
+    if id.len() >= 16 && id.len().is_multiple_of(2) && id.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(id.to_lowercase())
+    } else {
+        Err("expected a hex build ID, e.g. from `readelf -n vmlinux`".to_string())
+    }
+}
+
+/// At least one job; each runs its own container
+fn parse_jobs(value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(jobs) if jobs > 0 => Ok(jobs),
+        _ => Err("expected a number of jobs of at least 1".to_string()),
+    }
+}
+
+/// A number with an `s`, `m`, `h` or `d` suffix
+fn parse_age(value: &str) -> Result<Duration, String> {
+    let (number, unit) = value.split_at(value.len().saturating_sub(1));
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err("expected an age like 90d, 12h or 30m".to_string()),
+    };
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(seconds))
+        .map(Duration::from_secs)
+        .ok_or_else(|| "expected an age like 90d, 12h or 30m".to_string())
+}
+
+/// Bytes, optionally with a `K`, `M`, `G` or `T` suffix (powers of 1024)
+fn parse_size(value: &str) -> Result<u64, String> {
+    let upper = value.to_ascii_uppercase();
+    let trimmed = upper.strip_suffix('B').unwrap_or(&upper);
+    let (number, shift) = match trimmed.chars().last() {
+        Some('K') => (&trimmed[..trimmed.len() - 1], 10),
+        Some('M') => (&trimmed[..trimmed.len() - 1], 20),
+        Some('G') => (&trimmed[..trimmed.len() - 1], 30),
+        Some('T') => (&trimmed[..trimmed.len() - 1], 40),
+        _ => (trimmed, 0),
+    };
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(1 << shift))
+        .ok_or_else(|| "expected a size like 20G, 500M or 1048576".to_string())
+}
+
+/// Tags end up in labels and file names, so keep them to a safe character set
+fn parse_tag(tag: &str) -> Result<String, String> {
+    if !tag.is_empty()
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        Ok(tag.to_string())
+    } else {
+        Err("tags may only contain letters, digits, '-', '_' and '.'".to_string())
+    }
+}
+
+/// `ubuntu` or `ubuntu:22.04`
+fn parse_try(entry: &str) -> Result<(String, Option<String>), String> {
+    match entry.split_once(':') {
+        Some((distro, release)) if !distro.is_empty() && !release.is_empty() => {
+            Ok((distro.to_string(), Some(release.to_string())))
+        }
+        None if !entry.is_empty() => Ok((entry.to_string(), None)),
+        _ => Err(format!(
+            "expected DISTRO or DISTRO:RELEASE, got '{}'",
+            entry
+        )),
+    }
+}
+
+fn parse_var(var: &str) -> Result<(String, String), String> {
+    let (key, value) = var
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", var))?;
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!(
+            "variable names may only contain letters, digits and '_' (got '{}')",
+            key
+        ));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ages() {
+        assert_eq!(parse_age("90d"), Ok(Duration::from_secs(90 * 24 * 60 * 60)));
+        assert_eq!(parse_age("12h"), Ok(Duration::from_secs(12 * 60 * 60)));
+        assert_eq!(parse_age("30m"), Ok(Duration::from_secs(30 * 60)));
+        assert_eq!(parse_age("45s"), Ok(Duration::from_secs(45)));
+        assert!(parse_age("90").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("").is_err());
+        assert!(parse_age("99999999999999999999d").is_err());
+    }
+
+    #[test]
+    fn parses_sizes() {
+        assert_eq!(parse_size("1048576"), Ok(1 << 20));
+        assert_eq!(parse_size("500M"), Ok(500 << 20));
+        assert_eq!(parse_size("20G"), Ok(20 << 30));
+        assert_eq!(parse_size("20gb"), Ok(20 << 30));
+        assert_eq!(parse_size("1T"), Ok(1 << 40));
+        assert!(parse_size("20X").is_err());
+        assert!(parse_size("").is_err());
+        assert!(parse_size("99999999999T").is_err());
+    }
 }
@@ -0,0 +1,145 @@
+use regex::Regex;
+use serde::Serialize;
+
+/// How closely an ISF's kernel matches the kernel of a memory image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    /// Identical banners: Volatility picks the ISF up as is
+    Exact,
+    /// Same release string, different build: types match, addresses usually do not
+    SameRelease,
+    /// Same upstream version and flavor under another ABI: layouts may differ
+    DifferentAbi,
+    /// Different kernel altogether
+    Mismatch,
+}
+
+/// Outcome of comparing a banner with the banner recorded in an ISF
+#[derive(Debug, Serialize)]
+pub struct Comparison {
+    pub verdict: Verdict,
+    /// Whether analysis can use the ISF without regenerating
+    pub usable: bool,
+    pub banner: String,
+    pub isf_banner: String,
+    /// Human-readable differences between the two kernels
+    pub differences: Vec<String>,
+    pub recommendation: String,
+}
+
+/// Parts of a banner that decide compatibility
+#[derive(Debug)]
+struct BannerParts {
+    /// `5.15.0-91-generic`
+    release: String,
+    /// `5.15.0`
+    upstream: String,
+    /// `91` (Ubuntu/Debian) or `513.el8` (RPM), between upstream and flavor
+    abi: String,
+    /// `generic`, `amd64`, `x86_64`, ... (last dash- or dot-separated component)
+    flavor: String,
+    /// `101-Ubuntu`, from `#101-Ubuntu SMP ...`
+    build: String,
+    /// Compiler description, e.g. `gcc (Ubuntu 11.4.0-1ubuntu1~22.04) 11.4.0`
+    compiler: String,
+}
+
+impl BannerParts {
+    fn parse(banner: &str) -> Self {
+        let capture = |pattern: &str| {
+            Regex::new(pattern)
+                .ok()
+                .and_then(|re| re.captures(banner))
+                .map(|c| c[1].to_string())
+                .unwrap_or_default()
+        };
+        let release = capture(r"Linux version (\S+)");
+        let (upstream, rest) = release.split_once('-').unwrap_or((&release, ""));
+        let (abi, flavor) = rest.rsplit_once(['-', '.']).unwrap_or((rest, ""));
+
+        Self {
+            upstream: upstream.to_string(),
+            abi: abi.to_string(),
+            flavor: flavor.to_string(),
+            build: capture(r"#(\S+)"),
+            compiler: balanced(&capture(r"\(((?:gcc|clang)[^,#]*)")),
+            release,
+        }
+    }
+}
+
+/// Compare the banner of a memory image with the banner stored in an ISF
+pub fn compare(banner: &str, isf_banner: &str) -> Comparison {
+    let image = BannerParts::parse(banner);
+    let isf = BannerParts::parse(isf_banner);
+
+    let mut differences = Vec::new();
+    let mut differ = |what: &str, image: &str, isf: &str| {
+        if image != isf {
+            differences.push(format!(
+                "{}: image has {}, ISF has {}",
+                what,
+                or_unknown(image),
+                or_unknown(isf)
+            ));
+        }
+    };
+    differ("kernel release", &image.release, &isf.release);
+    differ("build", &image.build, &isf.build);
+    differ("compiler", &image.compiler, &isf.compiler);
+
+    let verdict = if banner.trim_end() == isf_banner.trim_end() {
+        Verdict::Exact
+    } else if image.release == isf.release && !image.release.is_empty() {
+        Verdict::SameRelease
+    } else if image.upstream == isf.upstream
+        && image.flavor == isf.flavor
+        && !image.upstream.is_empty()
+    {
+        Verdict::DifferentAbi
+    } else {
+        Verdict::Mismatch
+    };
+
+    let recommendation = match verdict {
+        Verdict::Exact => "Use the ISF as is".to_string(),
+        Verdict::SameRelease => "Same ABI, different build: structure layouts match but symbol addresses \
+             usually move and Volatility will not match the banner. Regenerate cheaply with \
+             `symgen generate -b <banner> --delta-from <isf>`"
+            .to_string(),
+        Verdict::DifferentAbi => format!(
+            "ABI {} differs from {}: structure layouts may have changed. Regenerate with `symgen generate -b <banner>`",
+            or_unknown(&image.abi),
+            or_unknown(&isf.abi)
+        ),
+        Verdict::Mismatch => "Different kernel: the ISF cannot be used. Regenerate with `symgen generate -b <banner>`"
+            .to_string(),
+    };
+
+    Comparison {
+        verdict,
+        usable: verdict == Verdict::Exact,
+        banner: banner.trim_end().to_string(),
+        isf_banner: isf_banner.trim_end().to_string(),
+        differences,
+        recommendation,
+    }
+}
+
+/// Drop trailing spaces and the closing parentheses of the enclosing group
+fn balanced(text: &str) -> String {
+    let mut text = text.trim_end();
+    while text.ends_with(')') && text.matches(')').count() > text.matches('(').count() {
+        text = text[..text.len() - 1].trim_end();
+    }
+    text.to_string()
+}
+
+fn or_unknown(value: &str) -> &str {
+    if value.is_empty() {
+        "unknown"
+    } else {
+        value
+    }
+}
@@ -0,0 +1,123 @@
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::process::Command;
+use symgen_core::distros::{find_version, resolve_derivative, Arch, Distro};
+
+/// Shell snippet printing everything needed to enumerate a host's kernels
+const PROBE_SCRIPT: &str = "cat /etc/os-release 2>/dev/null; \
+    echo @@ARCH@@; uname -m; \
+    echo @@MODULES@@; ls -1 /lib/modules 2>/dev/null; \
+    echo @@BOOT@@; ls -1 /boot 2>/dev/null; true";
+
+/// Kernels installed on a host, with the distro they belong to
+#[derive(Debug, Serialize)]
+pub struct HostKernels {
+    pub host: String,
+    pub distro: Option<Distro>,
+    pub distro_version: Option<String>,
+    pub arch: Option<Arch>,
+    /// Raw `uname -m` output
+    pub machine: String,
+    pub kernels: Vec<String>,
+}
+
+/// Enumerate installed kernels locally or, with `ssh` set, on a remote host
+pub fn discover(ssh: Option<&str>) -> Result<HostKernels> {
+    let output = match ssh {
+        Some(target) => Command::new("ssh")
+            .args(["-o", "BatchMode=yes", target, PROBE_SCRIPT])
+            .output()
+            .with_context(|| format!("Failed to run ssh {}", target))?,
+        None => Command::new("sh")
+            .args(["-c", PROBE_SCRIPT])
+            .output()
+            .context("Failed to inspect local kernels")?,
+    };
+
+    if !output.status.success() {
+        bail!(
+            "Kernel probe failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let host = ssh.unwrap_or("localhost").to_string();
+    Ok(parse_probe(host, &String::from_utf8_lossy(&output.stdout)))
+}
+
+fn parse_probe(host: String, probe: &str) -> HostKernels {
+    let mut section = "os-release";
+    let mut os_id = None;
+    let mut os_version = None;
+    let mut machine = String::new();
+    let mut kernels = BTreeSet::new();
+
+    for line in probe.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        match line {
+            "@@ARCH@@" | "@@MODULES@@" | "@@BOOT@@" => {
+                section = line;
+                continue;
+            }
+            _ => {}
+        }
+
+        match section {
+            "os-release" => {
+                if let Some((key, value)) = line.split_once('=') {
+                    let value = value.trim_matches('"').to_string();
+                    match key {
+                        "ID" => os_id = Some(value),
+                        "VERSION_ID" => os_version = Some(value),
+                        _ => {}
+                    }
+                }
+            }
+            "@@ARCH@@" => machine = line.to_string(),
+            "@@MODULES@@" => {
+                kernels.insert(line.to_string());
+            }
+            "@@BOOT@@" => {
+                // Skip the unversioned symlink and dracut rescue images
+                if let Some(kernel) = line.strip_prefix("vmlinuz-") {
+                    if !kernel.contains("-rescue-") {
+                        kernels.insert(kernel.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Derivatives such as Linux Mint are enumerated as their base release
+    let base = os_id
+        .as_deref()
+        .zip(os_version.as_deref())
+        .and_then(|(id, version)| resolve_derivative(id, version));
+    let (distro, os_version) = match base {
+        Some((distro, version)) => (Some(distro), Some(version)),
+        None => (os_id.as_deref().and_then(Distro::from_str), os_version),
+    };
+    // Only Ubuntu versions carry a minor part that matters (22.04 vs 9.3)
+    let distro_version = match (distro, os_version) {
+        (Some(Distro::Ubuntu), Some(version)) => Some(version),
+        // Kali's VERSION_ID names a quarterly image of its single rolling release
+        (Some(Distro::Kali), _) => Some("rolling".to_string()),
+        (Some(_), Some(version)) => version.split('.').next().map(str::to_string),
+        _ => None,
+    };
+    let distro_version = distro
+        .zip(distro_version)
+        .filter(|(d, v)| find_version(*d, v).is_some())
+        .map(|(_, v)| v);
+
+    HostKernels {
+        host,
+        distro,
+        distro_version,
+        arch: Arch::from_str(&machine, true).ok(),
+        machine,
+        kernels: kernels.into_iter().collect(),
+    }
+}
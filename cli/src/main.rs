@@ -1,20 +1,42 @@
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{Context, Result};
+use clap::{CommandFactory, Parser};
+use std::ffi::OsStr;
+use std::io::{BufRead, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+use symgen_core::acquisition::AcquisitionSource;
+use symgen_core::banner_index::BannerIndex;
+use symgen_core::cache::{write_atomic, Cache, ISF_NAMESPACE};
+use symgen_core::config::Config;
+use symgen_core::distros::{Arch, DistroVersion};
+use symgen_core::docker::{DockerClient, RemovePolicy, WORK_DIR};
+use symgen_core::generator::{
+    render_generation_script, GenerateOptions, Generated, IfExists, SymbolGenerator, TimedOut,
+};
+use symgen_core::jobs::JobStore;
+use symgen_core::output::{JsonResult, Output};
+use symgen_core::registry::RegistrySettings;
+use symgen_core::upgrade::{self, CheckStatus};
+use symgen_core::{
+    banner, bundle, distros, docker, estimate, history, info, isf, remote_index, scan, snap, verify,
+};
 use tracing_subscriber::EnvFilter;
 
-mod banner;
+mod audit;
 mod cli;
-mod docker;
-mod distros;
-mod generator;
-mod output;
+mod compare;
+mod host;
+mod pack;
+mod server;
 
-use cli::{Cli, Commands};
-use generator::SymbolGenerator;
-use output::Output;
+use cli::{BannersDbAction, CacheAction, Cli, Commands, JobsAction};
+
+/// Exit status when the requested symbol file already exists and nothing was done
+const EXIT_ALREADY_EXISTS: u8 = 3;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     // Initialize logging
     tracing_subscriber::fmt()
         .with_env_filter(
@@ -24,62 +46,1767 @@ async fn main() -> Result<()> {
         .init();
 
     let cli = Cli::parse();
-    let output = Output::new(cli.json);
+    let output = if cli.ndjson {
+        Output::event_stream()
+    } else {
+        Output::new(cli.json)
+    };
+    let mut config = Config::load(cli.config.as_deref())?;
+    if cli.cache_dir.is_some() {
+        config.cache_dir = cli.cache_dir.clone();
+    }
+    if cli.store_dir.is_some() {
+        config.store_dir = cli.store_dir.clone();
+    }
+    if cli.upload.is_some() {
+        config.upload = cli.upload.clone();
+    }
+    if cli.no_upload {
+        config.upload = None;
+    }
+    if let Some(runtime) = cli.runtime {
+        config.runtime = runtime;
+    }
+    config.vars.extend(cli.vars.iter().cloned());
+    config.ca_certs.extend(cli.ca_certs.iter().cloned());
+    if let Some(mirror) = &cli.registry_mirror {
+        config.registry_mirror = Some(mirror.clone());
+    }
+    if let Some(path) = &cli.registry_auth {
+        config.registry_auth = Some(path.clone());
+    }
+    if let Some(proxy) = &cli.proxy {
+        config.proxy = Some(proxy.clone());
+    }
+    if let Some(path) = &cli.pre_script {
+        config.pre_script = Some(path.clone());
+    }
+    if let Some(path) = &cli.post_script {
+        config.post_script = Some(path.clone());
+    }
+    config.check_vars()?;
+
+    let audit_log = cli.audit_log.clone().or_else(|| config.audit_log.clone());
+    let command_name = cli.command.name();
+    let started_at = chrono::Utc::now();
+
+    let mut exit_code = ExitCode::SUCCESS;
+    let result = run(cli.command, config, &output, &mut exit_code).await;
+    // Pipelines reading stdout get a result for failures too
+    if let Err(e) = &result {
+        output.failure(&format!("{:#}", e), error_code(e));
+    }
+
+    // The command has already run, so its outcome stands even if it cannot be logged
+    if let Some(log_path) = audit_log {
+        if let Err(e) = audit::record(&log_path, command_name, started_at, &result) {
+            output.error(&format!("Failed to write audit log: {:#}", e));
+        }
+    }
+
+    result.map(|_| exit_code)
+}
+
+/// `error_code` of the JSON failure result, for errors scripts handle specially
+fn error_code(error: &anyhow::Error) -> Option<&'static str> {
+    error
+        .chain()
+        .any(|cause| cause.is::<TimedOut>())
+        .then_some("timeout")
+}
+
+/// Execute a command, returning the artifacts it produced.
+///
+/// Commands that succeed without doing anything set `exit_code`.
+async fn run(
+    command: Commands,
+    config: Config,
+    output: &Output,
+    exit_code: &mut ExitCode,
+) -> Result<Vec<PathBuf>> {
+    let mut artifacts = Vec::new();
 
-    match cli.command {
+    match command {
         Commands::Generate {
             banner,
             kernel,
             distro,
             distro_version,
             output_dir,
+            arch,
+            allow_unauthenticated,
+            fix_time,
+            no_proposed,
+            no_updates,
+            no_fallback,
+            converter_args,
+            compress,
+            minimal,
+            annotate,
+            install_vol3,
+            no_install,
+            manifest,
+            sign,
+            gpg_key,
+            post_process,
+            tag,
+            transfer,
+            image,
+            rm_policy,
+            keep_on_failure,
+            log_file,
+            no_cache,
+            force,
+            skip_existing,
+            timeout,
+            vmlinux,
+            vmlinux_url,
+            delta_from,
+            build_id,
+            debuginfod_url,
+            from_vol3_banners,
+            kernel_snap,
+            try_distros,
+            print_script,
         } => {
-            // Determine kernel, distro, and version from banner or explicit args
-            let (kernel_ver, distro_str, version_str) = if let Some(banner_str) = banner {
-                // Parse the banner to extract kernel info
-                match banner::parse_banner(&banner_str) {
-                    Some(result) => {
-                        let k = result.kernel_version;
-                        let d = result.distro.ok_or_else(|| {
-                            anyhow::anyhow!("Could not detect distribution from banner. Please specify -d/--distro manually.")
-                        })?;
-                        let v = result.distro_version.ok_or_else(|| {
-                            anyhow::anyhow!("Could not detect distribution version from banner. Please specify -r/--release manually.")
-                        })?;
-                        output.info(&format!("Parsed banner: {} {} kernel {}", d, v, k));
-                        (k, d, v)
+            let install_vol3 = (install_vol3 || config.install_vol3) && !no_install;
+            // Determine kernel, distro, and version from banners or explicit args
+            let targets = if let Some(path) = from_vol3_banners {
+                let json = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                let json: serde_json::Value = serde_json::from_str(&json).with_context(|| {
+                    format!(
+                        "{} is not JSON from `vol -r json banners.Banners`",
+                        path.display()
+                    )
+                })?;
+                let banners = banner::vol3_banners(&json);
+                if banners.is_empty() {
+                    anyhow::bail!("No kernel banners in {}", path.display());
+                }
+                output.info(&format!(
+                    "Found {} distinct banner(s) in {}",
+                    banners.len(),
+                    path.display()
+                ));
+
+                let mut targets = Vec::new();
+                for text in banners {
+                    match banner::parse_banner_str(&text) {
+                        Some(banner::BannerParseResult {
+                            kernel_version,
+                            distro: Some(distro),
+                            distro_version: Some(version),
+                            arch,
+                            ..
+                        }) => targets.push(Target {
+                            kernel: kernel_version,
+                            distro,
+                            version,
+                            banner: Some(text),
+                            arch,
+                            upload: None,
+                            alternatives: Vec::new(),
+                        }),
+                        _ => output.warning(&format!(
+                            "Skipping banner with no detectable distribution: {}",
+                            text
+                        )),
+                    }
+                }
+                if targets.is_empty() {
+                    anyhow::bail!(
+                        "No banner in {} names a supported distribution",
+                        path.display()
+                    );
+                }
+                targets
+            } else if let Some(path) = kernel_snap {
+                let snap = snap::inspect(&path)?;
+                output.info(&format!(
+                    "Kernel snap {}: kernel {}{}",
+                    snap.name,
+                    snap.kernel,
+                    snap.upload
+                        .as_deref()
+                        .map(|upload| format!(", upload {}", upload))
+                        .unwrap_or_default()
+                ));
+                vec![Target {
+                    kernel: snap.kernel,
+                    distro: "ubuntu".to_string(),
+                    version: distro_version.expect("release is required with --kernel-snap"),
+                    banner: None,
+                    arch: snap.arch,
+                    upload: snap.upload,
+                    alternatives: Vec::new(),
+                }]
+            } else if let Some(banner_raw) = banner {
+                // Parse the banner to extract kernel info (tolerating non-UTF-8 bytes)
+                let matches = banner::parse_banner(&read_banner(&banner_raw)?);
+                if matches.iter().any(|m| m.banner != matches[0].banner) {
+                    output.warning("Input contains several different banners; using the first one");
+                }
+                match matches.into_iter().next() {
+                    Some(found) => {
+                        let result = found.result;
+                        match (result.distro, result.distro_version) {
+                            (Some(d), Some(v)) => {
+                                output.info(&format!(
+                                    "Parsed banner: {} {} kernel {}",
+                                    d, v, result.kernel_version
+                                ));
+                                if let Some(derivative) = &result.derivative {
+                                    output.warning(&format!(
+                                        "{} builds its own kernels, which {}'s debug archive does not carry; \
+                                         if no debug symbols are found, supply the debug kernel with --vmlinux",
+                                        derivative, d
+                                    ));
+                                }
+                                vec![Target {
+                                    kernel: result.kernel_version,
+                                    distro: d,
+                                    version: v,
+                                    banner: Some(found.banner),
+                                    arch: result.arch,
+                                    upload: None,
+                                    alternatives: Vec::new(),
+                                }]
+                            }
+                            (d, _) => {
+                                // Undetectable: fall back to what worked before and the --try list
+                                let mut releases = trial_releases(
+                                    &result.kernel_version,
+                                    &try_distros,
+                                    &config,
+                                    output,
+                                )?
+                                .into_iter();
+                                let Some((distro, version)) = releases.next() else {
+                                    return Err(match d {
+                                        Some(_) => anyhow::anyhow!("Could not detect distribution version from banner. Please specify -r/--release manually."),
+                                        None => anyhow::anyhow!("Could not detect distribution from banner. Please specify -d/--distro manually, or families to try with --try."),
+                                    });
+                                };
+                                output.info(&format!(
+                                    "Banner names no release; trying kernel {} as {} {}",
+                                    result.kernel_version, distro, version
+                                ));
+                                vec![Target {
+                                    kernel: result.kernel_version,
+                                    distro,
+                                    version,
+                                    banner: Some(found.banner),
+                                    arch: result.arch,
+                                    upload: None,
+                                    alternatives: releases.collect(),
+                                }]
+                            }
+                        }
                     }
                     None => {
-                        output.error("Failed to parse kernel banner. Could not extract kernel version.");
+                        output.error(
+                            "Failed to parse kernel banner. Could not extract kernel version.",
+                        );
                         return Err(anyhow::anyhow!("Banner parsing failed"));
                     }
                 }
+            } else if !try_distros.is_empty() {
+                let kernel = kernel.expect("kernel is required when banner is not provided");
+                let mut releases =
+                    trial_releases(&kernel, &try_distros, &config, output)?.into_iter();
+                let (distro, version) = releases
+                    .next()
+                    .expect("--try names at least one distribution");
+                vec![Target {
+                    kernel,
+                    distro,
+                    version,
+                    banner: None,
+                    arch: None,
+                    upload: None,
+                    alternatives: releases.collect(),
+                }]
             } else {
                 // Use explicit arguments (already validated as required by clap)
-                (
-                    kernel.expect("kernel is required when banner is not provided"),
-                    distro.expect("distro is required when banner is not provided"),
-                    distro_version.expect("distro_version is required when banner is not provided"),
+                vec![Target {
+                    kernel: kernel.expect("kernel is required when banner is not provided"),
+                    distro: distro.expect("distro is required when banner is not provided"),
+                    version: distro_version
+                        .expect("distro_version is required when banner is not provided"),
+                    banner: None,
+                    arch: None,
+                    upload: None,
+                    alternatives: Vec::new(),
+                }]
+            };
+
+            let acquisition = match (vmlinux, vmlinux_url, build_id) {
+                (Some(path), _, _) => AcquisitionSource::LocalFile(path),
+                (_, Some(url), _) => AcquisitionSource::DirectUrl(url),
+                (_, _, Some(build_id)) => AcquisitionSource::Debuginfod {
+                    server: debuginfod_url,
+                    build_id,
+                },
+                _ => AcquisitionSource::PackageManager,
+            };
+            let if_exists = if force {
+                IfExists::Overwrite
+            } else if skip_existing {
+                IfExists::Skip
+            } else {
+                IfExists::Report
+            };
+            let batch = targets.len() > 1;
+            let total = targets.len();
+            if batch && print_script.is_some() {
+                anyhow::bail!("--print-script takes a single kernel");
+            }
+            let mut failed = Vec::new();
+            let mut existing = 0;
+
+            // Printing scripts must work without a container runtime
+            let (generator, script_config) = match &print_script {
+                Some(_) => (None, Some(config)),
+                None => (Some(SymbolGenerator::new(config).await?), None),
+            };
+            for target in targets {
+                let releases: Vec<(String, String)> =
+                    std::iter::once((target.distro, target.version))
+                        .chain(target.alternatives)
+                        .collect();
+                let trial = releases.len() > 1;
+                let mut outcome = None;
+                for (i, (distro, version)) in releases.iter().enumerate() {
+                    let (distro_str, version_str) =
+                        base_release(distro.clone(), version.clone(), output);
+
+                    // Ubuntu kernels may be given with their upload (`5.15.0-91.101-generic`)
+                    let (kernel_ver, named_upload) = match distros::Distro::from_str(&distro_str) {
+                        Some(distros::Distro::Ubuntu) => {
+                            distros::split_ubuntu_upload(&target.kernel)
+                        }
+                        _ => (target.kernel.clone(), None),
+                    };
+                    let ubuntu_upload = named_upload
+                        .or_else(|| target.upload.clone())
+                        .or_else(|| target.banner.as_deref().and_then(banner::ubuntu_upload));
+
+                    let arch = match arch {
+                        Some(arch) => arch,
+                        None => {
+                            let detected = target
+                                .arch
+                                .or_else(|| Arch::from_kernel(&kernel_ver))
+                                .unwrap_or_default();
+                            if detected != Arch::Amd64 && i == 0 {
+                                output.info(&format!("Detected architecture: {}", detected.name()));
+                            }
+                            detected
+                        }
+                    };
+
+                    let options = GenerateOptions {
+                        minimal,
+                        annotate,
+                        post_process: post_process.clone(),
+                        tag: tag.clone(),
+                        transfer,
+                        remove: if keep_on_failure {
+                            RemovePolicy::OnSuccess
+                        } else {
+                            rm_policy
+                        },
+                        arch,
+                        allow_unauthenticated,
+                        no_proposed,
+                        no_updates,
+                        no_fallback,
+                        converter_args: converter_args.clone(),
+                        compression: compress,
+                        log_file,
+                        no_cache,
+                        fix_time,
+                        acquisition: acquisition.clone(),
+                        delta_from: delta_from.clone(),
+                        banner: target.banner.clone(),
+                        install_vol3,
+                        manifest,
+                        sign,
+                        gpg_key: gpg_key.clone(),
+                        ubuntu_upload,
+                        if_exists,
+                        timeout: timeout.map(|minutes| Duration::from_secs(minutes * 60)),
+                        image: image.clone(),
+                    };
+
+                    if let (Some(path), Some(config)) = (&print_script, &script_config) {
+                        // Human-readable messages share stdout with a printed script
+                        let render_output = if path.as_os_str() == "-" && !output.is_json() {
+                            Output::quiet()
+                        } else {
+                            output.clone()
+                        };
+                        let (version, script) = render_generation_script(
+                            config,
+                            &kernel_ver,
+                            &distro_str,
+                            &version_str,
+                            &options,
+                            &render_output,
+                        )
+                        .await?;
+                        let registry = RegistrySettings {
+                            mirror: config.registry_mirror.clone(),
+                            ..Default::default()
+                        };
+                        let version = DistroVersion {
+                            docker_image: registry.image(&version.docker_image),
+                            ..version
+                        };
+                        if let Some(path) = write_script(
+                            path,
+                            &kernel_ver,
+                            &version,
+                            &script.text,
+                            &options,
+                            output,
+                        )? {
+                            artifacts.push(path);
+                        }
+                        break;
+                    }
+
+                    let generator = generator
+                        .as_ref()
+                        .expect("a generator runs unless scripts are printed");
+                    let result = generator
+                        .generate(
+                            &kernel_ver,
+                            &distro_str,
+                            &version_str,
+                            output_dir.as_deref(),
+                            &options,
+                            output,
+                        )
+                        .await;
+                    match &result {
+                        Err(e) if i + 1 < releases.len() => {
+                            output.warning(&format!(
+                                "{} {} did not work for kernel {} ({:#}), trying {} {}",
+                                distro,
+                                version,
+                                kernel_ver,
+                                e,
+                                releases[i + 1].0,
+                                releases[i + 1].1
+                            ));
+                            continue;
+                        }
+                        // Recorded in the run history, which the next lookup for this kernel reads
+                        Ok(_) if trial => output.success(&format!(
+                            "Kernel {} worked as {} {} (attempt {} of {})",
+                            kernel_ver,
+                            distro,
+                            version,
+                            i + 1,
+                            releases.len()
+                        )),
+                        _ => {}
+                    }
+                    outcome = Some((kernel_ver, result));
+                    break;
+                }
+
+                // Nothing ran when the script was only printed
+                let Some(outcome) = outcome else {
+                    continue;
+                };
+                match outcome {
+                    (_, Ok(generated)) => {
+                        if let Generated::Existing(_) = generated {
+                            existing += 1;
+                        }
+                        artifacts.push(generated.into_path());
+                    }
+                    (kernel_ver, Err(e)) if batch => {
+                        output.error(&format!("{}: {:#}", kernel_ver, e));
+                        failed.push(kernel_ver);
+                    }
+                    (_, Err(e)) => return Err(e),
+                }
+            }
+
+            if batch {
+                output.info(&format!(
+                    "Generated {} of {} symbol file(s), {} already present",
+                    total - failed.len() - existing,
+                    total,
+                    existing
+                ));
+            }
+            if !failed.is_empty() {
+                anyhow::bail!("Symbol generation failed for: {}", failed.join(", "));
+            }
+            if existing == total && if_exists == IfExists::Report {
+                *exit_code = ExitCode::from(EXIT_ALREADY_EXISTS);
+            }
+        }
+        Commands::Estimate {
+            kernel,
+            distro,
+            distro_version,
+            arch,
+        } => {
+            let (distro, distro_version) = base_release(distro, distro_version, output);
+            let distro = distros::Distro::from_str(&distro)
+                .ok_or_else(|| anyhow::anyhow!("Unknown distribution: {}", distro))?;
+            let version = distros::find_version(distro, &distro_version).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unsupported version {} for {}",
+                    distro_version,
+                    distro.display_name()
                 )
+            })?;
+            let entries = match config.history_path() {
+                Some(path) => history::load(&path)?,
+                None => Vec::new(),
             };
+            let estimate = estimate::estimate(&entries, &version, &kernel, arch);
 
-            let generator = SymbolGenerator::new().await?;
-            generator
-                .generate(&kernel_ver, &distro_str, &version_str, output_dir.as_deref(), &output)
-                .await?;
+            if output.is_json() {
+                output.result(JsonResult {
+                    success: true,
+                    data: Some(&estimate),
+                    error: None,
+                });
+            } else if estimate.samples == 0 {
+                output.warning(
+                    "No previous runs recorded yet; estimates appear after the first generation",
+                );
+            } else {
+                output.info(&format!(
+                    "Based on {} previous run(s), {}",
+                    estimate.samples, estimate.basis
+                ));
+                if let Some(size) = &estimate.debuginfo_bytes {
+                    output.info(&format!(
+                        "Debug packages: {} - {} unpacked (typically {})",
+                        estimate::format_bytes(size.min),
+                        estimate::format_bytes(size.max),
+                        estimate::format_bytes(size.median)
+                    ));
+                }
+                if let Some(duration) = &estimate.duration_secs {
+                    output.info(&format!(
+                        "Duration: {} - {} (typically {})",
+                        estimate::format_duration(duration.min),
+                        estimate::format_duration(duration.max),
+                        estimate::format_duration(duration.median)
+                    ));
+                }
+                if let Some(size) = &estimate.symbol_bytes {
+                    output.info(&format!(
+                        "Symbol file: {} - {}",
+                        estimate::format_bytes(size.min),
+                        estimate::format_bytes(size.max)
+                    ));
+                }
+            }
+        }
+        Commands::Merge {
+            inputs,
+            output_file,
+        } => {
+            let inputs: Vec<PathBuf> = inputs.iter().map(PathBuf::from).collect();
+            output.progress(&format!("Merging {} symbol files...", inputs.len()));
+
+            let stats = isf::merge_files(&inputs, Path::new(&output_file))?;
+            for conflict in &stats.conflicts {
+                output.warning(&format!(
+                    "Conflicting definition kept from first input: {}",
+                    conflict
+                ));
+            }
+            output.success(&format!(
+                "Merged ISF written to {} ({} symbols, {} user types, {} enums, {} base types)",
+                output_file, stats.symbols, stats.user_types, stats.enums, stats.base_types
+            ));
+            artifacts.push(PathBuf::from(output_file));
+        }
+        Commands::Index {
+            dir,
+            base_url,
+            output_file,
+        } => {
+            let index_path = output_file.unwrap_or_else(|| dir.join(remote_index::INDEX_FILENAME));
+            let (index, stats) = remote_index::build(&dir, &base_url, Some(&index_path), output)?;
+            write_atomic(&index_path, |file| {
+                serde_json::to_writer_pretty(file, &index).context("Failed to write the index")
+            })?;
+            if stats.skipped > 0 {
+                output.warning(&format!(
+                    "Skipped {} file(s) without a linux_banner",
+                    stats.skipped
+                ));
+            }
+            output.success(&format!(
+                "Indexed {} symbol file(s) for {} banner(s) in {}",
+                stats.files,
+                stats.banners,
+                index_path.display()
+            ));
+            if output.is_json() {
+                output.result(JsonResult {
+                    success: true,
+                    data: Some(&stats),
+                    error: None,
+                });
+            }
+            artifacts.push(index_path);
+        }
+        Commands::CompareKernel { banner, isf } => {
+            let Some(found) = banner::parse_banner(&read_banner(&banner)?)
+                .into_iter()
+                .next()
+            else {
+                anyhow::bail!("No kernel banner found in the input");
+            };
+            output.progress(&format!("Reading {}...", isf.display()));
+            let isf_banner = isf::banner(&isf::load(&isf)?)
+                .ok_or_else(|| anyhow::anyhow!("{} records no linux_banner", isf.display()))?;
+            let comparison = compare::compare(&found.banner, &isf_banner);
+
+            if output.is_json() {
+                output.result(JsonResult {
+                    success: true,
+                    data: Some(&comparison),
+                    error: None,
+                });
+            } else {
+                output.info(&format!("Image: {}", comparison.banner));
+                output.info(&format!("ISF:   {}", comparison.isf_banner));
+                for difference in &comparison.differences {
+                    output.warning(difference);
+                }
+                if comparison.usable {
+                    output.success(&comparison.recommendation);
+                } else {
+                    output.error(&comparison.recommendation);
+                }
+            }
+        }
+        Commands::Verify { files, kernel } => {
+            let mut reports = Vec::new();
+            for file in &files {
+                output.progress(&format!("Checking {}...", file.display()));
+                let report = verify::verify(file, kernel.as_deref());
+                for warning in &report.warnings {
+                    output.warning(&format!("{}: {}", file.display(), warning));
+                }
+                for error in &report.errors {
+                    output.error(&format!("{}: {}", file.display(), error));
+                }
+                if report.more_errors > 0 {
+                    output.error(&format!(
+                        "{}: {} more error(s)",
+                        file.display(),
+                        report.more_errors
+                    ));
+                }
+                if report.is_valid() {
+                    output.success(&format!(
+                        "{}: {} symbols, {} user types, {} enums, {} base types ({})",
+                        file.display(),
+                        report.symbols,
+                        report.user_types,
+                        report.enums,
+                        report.base_types,
+                        report.kernel.as_deref().unwrap_or("unknown kernel")
+                    ));
+                }
+                reports.push(report);
+            }
+            let valid = reports.iter().all(|report| report.is_valid());
+            if !valid {
+                *exit_code = ExitCode::FAILURE;
+            }
+            if output.is_json() {
+                output.result(JsonResult {
+                    success: valid,
+                    data: Some(&reports),
+                    error: (!valid).then(|| "Some symbol files failed verification".to_string()),
+                });
+            }
+        }
+        Commands::Info { files } => {
+            let mut inspected = Vec::new();
+            for file in &files {
+                output.progress(&format!("Reading {}...", file.display()));
+                let info = info::inspect(file)?;
+                if !output.is_json() {
+                    print_info(output, &info);
+                }
+                inspected.push(info);
+            }
+            if output.is_json() {
+                output.result(JsonResult {
+                    success: true,
+                    data: Some(&inspected),
+                    error: None,
+                });
+            }
+        }
+        Commands::HostKernels {
+            ssh,
+            generate,
+            output_dir,
+            jobs,
+        } => {
+            output.progress(&format!(
+                "Enumerating kernels on {}...",
+                ssh.as_deref().unwrap_or("the local machine")
+            ));
+            let found = host::discover(ssh.as_deref())?;
+
+            let release = found.distro.zip(found.distro_version.clone());
+            match &release {
+                Some((distro, version)) => {
+                    output.info(&format!("Host runs {} {}", distro.display_name(), version))
+                }
+                None => output
+                    .warning("Could not map the host's /etc/os-release to a supported release"),
+            }
+            for kernel in &found.kernels {
+                output.info(&format!("  {}", kernel));
+            }
+            output.success(&format!(
+                "Found {} kernel(s) on {}",
+                found.kernels.len(),
+                found.host
+            ));
+
+            if output.is_json() {
+                output.result(JsonResult {
+                    success: true,
+                    data: Some(&found),
+                    error: None,
+                });
+            }
+
+            let Some((distro, version)) = release else {
+                return Ok(artifacts);
+            };
+            let Some(arch) = found.arch else {
+                output.warning(&format!("Unsupported host architecture {}", found.machine));
+                return Ok(artifacts);
+            };
+
+            let confirmed = generate
+                || (!output.is_json()
+                    && std::io::stdin().is_terminal()
+                    && confirm(&format!(
+                        "Generate symbols for {} kernel(s)?",
+                        found.kernels.len()
+                    ))?);
+            if !confirmed || found.kernels.is_empty() {
+                return Ok(artifacts);
+            }
+
+            let generator = SymbolGenerator::new(config).await?;
+            let options = GenerateOptions {
+                arch,
+                ..Default::default()
+            };
+            let results = generator
+                .generate_batch(
+                    &found.kernels,
+                    distro.name(),
+                    &version,
+                    output_dir.as_deref(),
+                    &options,
+                    jobs,
+                    output,
+                )
+                .await;
+            let mut failed = Vec::new();
+            for (kernel, result) in found.kernels.iter().zip(results) {
+                match result {
+                    Ok(generated) => artifacts.push(generated.into_path()),
+                    Err(e) => {
+                        output.error(&format!("{}: {:#}", kernel, e));
+                        failed.push(kernel.clone());
+                    }
+                }
+            }
+            if !failed.is_empty() {
+                anyhow::bail!("Symbol generation failed for: {}", failed.join(", "));
+            }
+        }
+        Commands::Pack {
+            distro,
+            distro_version,
+            since,
+            flavor,
+            arch,
+            output_dir,
+            list,
+            jobs,
+        } => {
+            let (distro, distro_version) = base_release(distro, distro_version, output);
+            let parsed = distros::Distro::from_str(&distro)
+                .ok_or_else(|| anyhow::anyhow!("Unknown distribution: {}", distro))?;
+            let version = distros::find_version(parsed, &distro_version).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unsupported version {} for {}",
+                    distro_version,
+                    parsed.display_name()
+                )
+            })?;
+            let generator = SymbolGenerator::new(config).await?;
+            let options = GenerateOptions {
+                arch,
+                ..Default::default()
+            };
+            let kernels = pack::enumerate(
+                &generator,
+                &version,
+                flavor.as_deref(),
+                since,
+                &options,
+                output,
+            )
+            .await?;
+
+            for kernel in &kernels {
+                match kernel.published {
+                    Some(published) => output.info(&format!(
+                        "  {} ({})",
+                        kernel.kernel,
+                        published.format("%Y-%m-%d")
+                    )),
+                    None => output.info(&format!("  {}", kernel.kernel)),
+                }
+            }
+            output.success(&format!(
+                "Found {} kernel(s) for {} {}",
+                kernels.len(),
+                version.distro.display_name(),
+                version.version
+            ));
+            if list {
+                if output.is_json() {
+                    output.result(JsonResult {
+                        success: true,
+                        data: Some(&kernels),
+                        error: None,
+                    });
+                }
+                return Ok(artifacts);
+            }
+
+            let names: Vec<String> = kernels.iter().map(|k| k.kernel.clone()).collect();
+            let results = generator
+                .generate_batch(
+                    &names,
+                    &distro,
+                    &distro_version,
+                    output_dir.as_deref(),
+                    &options,
+                    jobs,
+                    output,
+                )
+                .await;
+            let mut failed = Vec::new();
+            let mut existing = 0;
+            for (kernel, result) in names.iter().zip(results) {
+                match result {
+                    Ok(generated) => {
+                        if let Generated::Existing(_) = generated {
+                            existing += 1;
+                        }
+                        artifacts.push(generated.into_path())
+                    }
+                    Err(e) => {
+                        output.error(&format!("{}: {:#}", kernel, e));
+                        failed.push(kernel.clone());
+                    }
+                }
+            }
+            output.info(&format!(
+                "Generated {} of {} symbol file(s), {} already present",
+                kernels.len() - failed.len() - existing,
+                kernels.len(),
+                existing
+            ));
+            if !failed.is_empty() {
+                anyhow::bail!("Symbol generation failed for: {}", failed.join(", "));
+            }
+        }
+        Commands::BuildImages {
+            distro,
+            distro_version,
+            arch,
+            allow_unauthenticated,
+            fix_time,
+        } => {
+            let parsed = distros::Distro::from_str(&distro)
+                .ok_or_else(|| anyhow::anyhow!("Unknown distribution: {}", distro))?;
+            if !parsed.supports_arch(arch) {
+                anyhow::bail!(
+                    "{} is not available for {}",
+                    parsed.display_name(),
+                    arch.name()
+                );
+            }
+            let versions = match distro_version {
+                Some(release) => {
+                    vec![distros::find_version(parsed, &release).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Unsupported version {} for {}",
+                            release,
+                            parsed.display_name()
+                        )
+                    })?]
+                }
+                None => distros::get_versions(parsed),
+            };
+            let generator = SymbolGenerator::new(config).await?;
+            let options = GenerateOptions {
+                arch,
+                allow_unauthenticated,
+                fix_time,
+                ..Default::default()
+            };
+
+            let mut built = Vec::new();
+            let mut failed = Vec::new();
+            for version in &versions {
+                output.progress(&format!(
+                    "Building the image for {} {}...",
+                    parsed.display_name(),
+                    version.version
+                ));
+                match generator.build_image(version, &options, output).await {
+                    Ok(image) => {
+                        output.success(&format!("Built {}", image));
+                        built.push(image);
+                    }
+                    Err(e) => {
+                        output.error(&format!(
+                            "{} {}: {:#}",
+                            parsed.display_name(),
+                            version.version,
+                            e
+                        ));
+                        failed.push(version.version.clone());
+                    }
+                }
+            }
+            if output.is_json() {
+                output.result(JsonResult {
+                    success: failed.is_empty(),
+                    data: Some(serde_json::json!({ "built": built, "failed": failed })),
+                    error: None,
+                });
+            }
+            if !failed.is_empty() {
+                anyhow::bail!(
+                    "Image build failed for {} {}",
+                    parsed.display_name(),
+                    failed.join(", ")
+                );
+            }
+        }
+        Commands::Scan {
+            image,
+            generate,
+            output_dir,
+        } => {
+            let size = std::fs::metadata(&image)
+                .with_context(|| format!("Failed to read {}", image.display()))?
+                .len();
+            output.progress(&format!(
+                "Scanning {} ({}) for kernel banners...",
+                image.display(),
+                estimate::format_bytes(size)
+            ));
+            let banners = scan::scan_image(&image)?;
+
+            for found in &banners {
+                output.info(&format!(
+                    "  0x{:x}  {} ({} copies)",
+                    found.offsets[0], found.banner, found.count
+                ));
+            }
+            if output.is_json() {
+                output.result(JsonResult {
+                    success: true,
+                    data: Some(&banners),
+                    error: None,
+                });
+            }
+            if banners.is_empty() {
+                anyhow::bail!("No kernel banner found in {}", image.display());
+            }
+            output.success(&format!("Found {} distinct banner(s)", banners.len()));
+            if !generate {
+                return Ok(artifacts);
+            }
+
+            let generator = SymbolGenerator::new(config).await?;
+            let mut failed = Vec::new();
+            for found in &banners {
+                let result = &found.result;
+                let (Some(distro), Some(version)) = (&result.distro, &result.distro_version) else {
+                    output.warning(&format!(
+                        "Skipping {}: distribution not detected; run generate with -k/-d/-r",
+                        result.kernel_version
+                    ));
+                    continue;
+                };
+                let options = GenerateOptions {
+                    arch: result.arch.unwrap_or_default(),
+                    banner: Some(found.banner.clone()),
+                    ubuntu_upload: banner::ubuntu_upload(&found.banner),
+                    ..Default::default()
+                };
+                match generator
+                    .generate(
+                        &result.kernel_version,
+                        distro,
+                        version,
+                        output_dir.as_deref(),
+                        &options,
+                        output,
+                    )
+                    .await
+                {
+                    Ok(generated) => artifacts.push(generated.into_path()),
+                    Err(e) => {
+                        output.error(&format!("{}: {:#}", result.kernel_version, e));
+                        failed.push(result.kernel_version.clone());
+                    }
+                }
+            }
+            if !failed.is_empty() {
+                anyhow::bail!("Symbol generation failed for: {}", failed.join(", "));
+            }
+        }
+        Commands::ExportBundle {
+            symbol_file,
+            output_file,
+        } => {
+            let bundle_path =
+                output_file.unwrap_or_else(|| bundle::default_bundle_path(&symbol_file));
+            let (manifest, missing) = bundle::export_bundle(&symbol_file, &bundle_path)?;
+
+            if !missing.is_empty() {
+                output.warning(&format!(
+                    "Not found, left out of bundle: {}",
+                    missing.join(", ")
+                ));
+            }
+            output.success(&format!(
+                "Bundle written to {} ({} files)",
+                bundle_path.display(),
+                manifest.files.len()
+            ));
+            artifacts.push(bundle_path);
         }
         Commands::List => {
             output.info("Listing supported distributions and versions...");
-            distros::list_distros(&output);
+            distros::list_distros(output);
         }
         Commands::Check => {
-            let generator = SymbolGenerator::new().await;
+            let generator = SymbolGenerator::new(config).await;
             match generator {
-                Ok(_) => output.success("Docker is available and connected"),
-                Err(e) => output.error(&format!("Docker check failed: {}", e)),
+                Ok(_) => output.success("Container runtime is available and connected"),
+                Err(e) => output.error(&format!("Container runtime check failed: {:#}", e)),
+            }
+        }
+        Commands::UpgradeCheck => {
+            output.info("Checking for updates...");
+            let checks = upgrade::check(&config).await;
+            for check in &checks {
+                match check.status {
+                    CheckStatus::Current => output.success(&format!(
+                        "{} {} is up to date",
+                        check.component, check.current
+                    )),
+                    CheckStatus::Available => output.warning(&format!(
+                        "{}: {}",
+                        check.component,
+                        check.detail.as_deref().unwrap_or_default()
+                    )),
+                    CheckStatus::Unreachable => output.warning(&format!(
+                        "Could not check {}: {}",
+                        check.component,
+                        check.detail.as_deref().unwrap_or_default()
+                    )),
+                    CheckStatus::Skipped => output.info(&format!(
+                        "Skipped {}: {}",
+                        check.component,
+                        check.detail.as_deref().unwrap_or_default()
+                    )),
+                }
+            }
+            if output.is_json() {
+                output.result(JsonResult {
+                    success: true,
+                    data: Some(&checks),
+                    error: None,
+                });
+            }
+        }
+        Commands::Ps { all } => {
+            #[derive(serde::Serialize)]
+            struct PsEntry {
+                name: String,
+                state: String,
+                created: i64,
+                labels: std::collections::HashMap<String, String>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                cpu_percent: Option<f64>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                memory_bytes: Option<u64>,
+            }
+
+            let docker = DockerClient::new(config.runtime).await?;
+            let mut entries = Vec::new();
+            for container in docker.list_managed().await? {
+                if container.state != "running" && !all {
+                    continue;
+                }
+                let usage = if container.state == "running" {
+                    docker
+                        .usage(&container.id)
+                        .await
+                        .map_err(|e| output.warning(&format!("{:#}", e)))
+                        .ok()
+                } else {
+                    None
+                };
+
+                let label =
+                    |key: &str| container.labels.get(key).map(String::as_str).unwrap_or("-");
+                let resources = match &usage {
+                    Some(usage) => format!(
+                        "{:.1}% CPU, {} / {}",
+                        usage.cpu_percent,
+                        estimate::format_bytes(usage.memory_bytes),
+                        estimate::format_bytes(usage.memory_limit)
+                    ),
+                    None => container.state.clone(),
+                };
+                output.info(&format!(
+                    "{}  job {}  {} {}  tag {}  {}",
+                    container.name,
+                    label(docker::JOB_LABEL),
+                    label(docker::DISTRO_LABEL),
+                    label(docker::KERNEL_LABEL),
+                    label(docker::TAG_LABEL),
+                    resources
+                ));
+
+                entries.push(PsEntry {
+                    name: container.name,
+                    state: container.state,
+                    created: container.created,
+                    labels: container.labels,
+                    cpu_percent: usage.as_ref().map(|u| u.cpu_percent),
+                    memory_bytes: usage.as_ref().map(|u| u.memory_bytes),
+                });
+            }
+
+            if output.is_json() {
+                output.result(JsonResult {
+                    success: true,
+                    data: Some(&entries),
+                    error: None,
+                });
+            } else if entries.is_empty() {
+                output.info("No symgen containers running");
+            }
+        }
+        Commands::Clean { all } => {
+            let docker = DockerClient::new(config.runtime).await?;
+            let containers = docker.list_managed().await?;
+
+            let mut removed = 0;
+            for container in containers {
+                if container.state == "running" && !all {
+                    output.info(&format!("Skipping running container {}", container.name));
+                    continue;
+                }
+                match docker.remove(&container.id, all).await {
+                    Ok(()) => {
+                        output.progress(&format!("Removed {}", container.name));
+                        removed += 1;
+                    }
+                    Err(e) => output.warning(&format!("{:#}", e)),
+                }
+            }
+            output.success(&format!("Removed {} container(s)", removed));
+        }
+        Commands::Cancel { job_id, server } => {
+            let server = server
+                .or_else(|| config.server.clone())
+                .unwrap_or_else(|| server::DEFAULT_SERVER.to_string());
+            output.progress(&format!("Cancelling job {} on {}...", job_id, server));
+            let message = server::cancel_job(&server, job_id).await?;
+            output.success(&message);
+        }
+        Commands::Jobs { action } => {
+            let state_dir = config.state_path().ok_or_else(|| {
+                anyhow::anyhow!("No state directory; set `state_dir` in the config")
+            })?;
+            let store = JobStore::open(&state_dir)?;
+            match action {
+                JobsAction::List { status, tag, limit } => {
+                    let records = store.list(status, tag.as_deref(), limit)?;
+                    for record in &records {
+                        output.info(&format!(
+                            "{}  {:<9}  {} {} {}  {}",
+                            &record.id[..8],
+                            record.status.name(),
+                            record.distro,
+                            record.release,
+                            record.kernel,
+                            record.updated_at
+                        ));
+                    }
+                    if output.is_json() {
+                        output.result(JsonResult {
+                            success: true,
+                            data: Some(&records),
+                            error: None,
+                        });
+                    } else if records.is_empty() {
+                        output.info("No jobs recorded");
+                    }
+                }
+                JobsAction::Show { id, lines } => {
+                    let record = store.find(&id)?;
+                    if output.is_json() {
+                        output.result(JsonResult {
+                            success: true,
+                            data: Some(&record),
+                            error: None,
+                        });
+                        return Ok(artifacts);
+                    }
+                    output.info(&format!("Job:      {}", record.id));
+                    output.info(&format!("Status:   {}", record.status.name()));
+                    output.info(&format!(
+                        "Kernel:   {} ({} {}, {})",
+                        record.kernel, record.distro, record.release, record.arch
+                    ));
+                    if let Some(tag) = &record.tag {
+                        output.info(&format!("Tag:      {}", tag));
+                    }
+                    if let Some(retry_of) = &record.retry_of {
+                        output.info(&format!("Retry of: {}", retry_of));
+                    }
+                    output.info(&format!("Created:  {}", record.created_at));
+                    output.info(&format!("Updated:  {}", record.updated_at));
+                    if let Some(file) = &record.symbol_file {
+                        output.info(&format!("Output:   {}", file));
+                    }
+                    if let Some(error) = &record.error {
+                        output.info(&format!("Error:    {}", error));
+                    }
+
+                    let log_path = store.log_path(&record.id);
+                    if let Ok(log) = std::fs::read_to_string(&log_path) {
+                        let log: Vec<&str> = log.lines().collect();
+                        output.info(&format!("Log ({}):", log_path.display()));
+                        for line in &log[log.len().saturating_sub(lines)..] {
+                            output.info(&format!("  {}", line));
+                        }
+                    }
+                }
+                JobsAction::Retry { id } => {
+                    let record = store.find(&id)?;
+                    let options = match &record.options {
+                        Some(json) => {
+                            serde_json::from_str::<GenerateOptions>(json).with_context(|| {
+                                format!("Failed to read the options of job {}", record.id)
+                            })?
+                        }
+                        // Recorded before options were stored; only arch and tag are known
+                        None => GenerateOptions {
+                            arch: clap::ValueEnum::from_str(&record.arch, true).map_err(|_| {
+                                anyhow::anyhow!(
+                                    "Job {} has an unknown architecture {}",
+                                    record.id,
+                                    record.arch
+                                )
+                            })?,
+                            tag: record.tag.clone(),
+                            ..Default::default()
+                        },
+                    };
+                    let output_dir = record.output_dir.as_deref();
+                    drop(store);
+
+                    let generator = SymbolGenerator::new(config).await?;
+                    let job_id = generator.queue_job(
+                        &record.kernel,
+                        &record.distro,
+                        &record.release,
+                        output_dir,
+                        &options,
+                        Some(&record.id),
+                        output,
+                    );
+                    output.info(&format!(
+                        "Retrying job {} as {}",
+                        &record.id[..8],
+                        &job_id[..8]
+                    ));
+                    let generated = generator
+                        .generate_job(
+                            &job_id,
+                            &record.kernel,
+                            &record.distro,
+                            &record.release,
+                            output_dir,
+                            &options,
+                            output,
+                        )
+                        .await?;
+                    artifacts.push(generated.into_path());
+                }
             }
         }
+        Commands::Cache { action } => {
+            let cache_dir = config.cache_path().ok_or_else(|| {
+                anyhow::anyhow!("No cache directory; set `cache_dir` in the config")
+            })?;
+            let cache = Cache::new(&cache_dir);
+            match action {
+                CacheAction::List => {
+                    let entries = cache.list(ISF_NAMESPACE)?;
+                    for entry in &entries {
+                        output.info(&format!(
+                            "{:>10}  {}  {}",
+                            estimate::format_bytes(entry.size),
+                            entry.last_used.format("%Y-%m-%d %H:%M"),
+                            entry.name
+                        ));
+                    }
+                    if output.is_json() {
+                        output.result(JsonResult {
+                            success: true,
+                            data: Some(&entries),
+                            error: None,
+                        });
+                    } else if entries.is_empty() {
+                        output.info(&format!(
+                            "No cached symbol files in {}",
+                            cache_dir.display()
+                        ));
+                    } else {
+                        let total = entries.iter().map(|entry| entry.size).sum();
+                        output.info(&format!(
+                            "{} file(s), {} in {}",
+                            entries.len(),
+                            estimate::format_bytes(total),
+                            cache_dir.display()
+                        ));
+                    }
+                }
+                CacheAction::Prune {
+                    older_than,
+                    max_size,
+                    all,
+                    tag,
+                } => {
+                    let tagged = match &tag {
+                        Some(tag) => {
+                            let entries = match config.history_path() {
+                                Some(path) => history::load(&path)?,
+                                None => Vec::new(),
+                            };
+                            Some(history::tagged_files(&entries, tag))
+                        }
+                        None => None,
+                    };
+                    let max_size = if all || (tagged.is_some() && older_than.is_none()) {
+                        max_size.or(Some(0))
+                    } else {
+                        max_size
+                    };
+                    let removed =
+                        cache.prune(ISF_NAMESPACE, older_than, max_size, tagged.as_ref())?;
+                    for entry in &removed {
+                        output.progress(&format!("Removed {}", entry.name));
+                    }
+                    if output.is_json() {
+                        output.result(JsonResult {
+                            success: true,
+                            data: Some(&removed),
+                            error: None,
+                        });
+                    } else {
+                        let freed = removed.iter().map(|entry| entry.size).sum();
+                        output.success(&format!(
+                            "Removed {} file(s), freed {}",
+                            removed.len(),
+                            estimate::format_bytes(freed)
+                        ));
+                    }
+                }
+            }
+        }
+        Commands::BannersDb { action } => {
+            let state_dir = config.state_path().ok_or_else(|| {
+                anyhow::anyhow!("No state directory; set `state_dir` in the config")
+            })?;
+            let index = BannerIndex::open(&state_dir)?;
+            match action {
+                BannersDbAction::Build { paths } => {
+                    let paths = if paths.is_empty() {
+                        config
+                            .cache_path()
+                            .map(|dir| dir.join(ISF_NAMESPACE))
+                            .into_iter()
+                            .chain(config.store_dir.clone())
+                            .filter(|dir| dir.is_dir())
+                            .collect()
+                    } else {
+                        paths
+                    };
+                    let stats = index.update(&paths, output)?;
+                    if output.is_json() {
+                        output.result(JsonResult {
+                            success: true,
+                            data: Some(&stats),
+                            error: None,
+                        });
+                    } else {
+                        output.success(&format!(
+                            "Indexed {} new and {} changed file(s), {} unchanged, {} removed, {} skipped",
+                            stats.added, stats.updated, stats.unchanged, stats.removed, stats.skipped
+                        ));
+                    }
+                }
+                BannersDbAction::Query { banner } => {
+                    let banner = String::from_utf8_lossy(&read_banner(&banner)?).to_string();
+                    let symbols = index.lookup(&banner)?;
+                    if symbols.is_empty() {
+                        anyhow::bail!("No indexed symbol file matches this banner");
+                    }
+                    if output.is_json() {
+                        output.result(JsonResult {
+                            success: true,
+                            data: Some(&symbols),
+                            error: None,
+                        });
+                    } else {
+                        for symbol in &symbols {
+                            output.info(&symbol.path);
+                        }
+                    }
+                }
+                BannersDbAction::Export { output_file } => {
+                    let symbols = index.all()?;
+                    let json = serde_json::to_string_pretty(&symbols)?;
+                    match output_file {
+                        Some(path) => {
+                            std::fs::write(&path, json + "\n")
+                                .with_context(|| format!("Failed to write {}", path.display()))?;
+                            output.success(&format!(
+                                "Exported {} entries to {}",
+                                symbols.len(),
+                                path.display()
+                            ));
+                        }
+                        None => println!("{}", json),
+                    }
+                }
+            }
+        }
+        Commands::Completions { shell } => {
+            let mut command = Cli::command();
+            clap_complete::generate(shell, &mut command, "symgen", &mut std::io::stdout());
+        }
+        Commands::Manpages { dir } => {
+            std::fs::create_dir_all(&dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+            // One page for symgen and one per subcommand (symgen-generate.1, symgen-cache-prune.1, ...)
+            clap_mangen::generate_to(Cli::command(), &dir)
+                .with_context(|| format!("Failed to write man pages to {}", dir.display()))?;
+            let mut pages: Vec<PathBuf> = std::fs::read_dir(&dir)?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    let name = path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    name.starts_with("symgen") && name.ends_with(".1")
+                })
+                .collect();
+            pages.sort();
+            output.success(&format!(
+                "Wrote {} man page(s) to {}",
+                pages.len(),
+                dir.display()
+            ));
+            if output.is_json() {
+                output.result(JsonResult {
+                    success: true,
+                    data: Some(&pages),
+                    error: None,
+                });
+            }
+            artifacts.extend(pages);
+        }
+    }
+
+    Ok(artifacts)
+}
+
+/// A kernel to generate, from explicit arguments or a parsed banner
+struct Target {
+    kernel: String,
+    distro: String,
+    version: String,
+    banner: Option<String>,
+    arch: Option<Arch>,
+    /// Ubuntu upload known from elsewhere than the banner or kernel name
+    upload: Option<String>,
+    /// Further `(distro, release)` pairs to try, in order, when generation fails
+    alternatives: Vec<(String, String)>,
+}
+
+/// Releases to try for a kernel whose distribution is unknown: the one it was
+/// generated for before, then each `--try` entry (its newest release unless one is named)
+fn trial_releases(
+    kernel: &str,
+    tries: &[(String, Option<String>)],
+    config: &Config,
+    output: &Output,
+) -> Result<Vec<(String, String)>> {
+    let mut releases = Vec::new();
+    if let Some(path) = config.history_path() {
+        if let Some((distro, version)) = history::known_release(&history::load(&path)?, kernel) {
+            output.info(&format!(
+                "Kernel {} was generated as {} {} before",
+                kernel,
+                distro.display_name(),
+                version
+            ));
+            releases.push((distro.name().to_string(), version));
+        }
+    }
+    for (name, release) in tries {
+        let distro = distros::Distro::from_str(name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown distribution in --try: {}", name))?;
+        let version = match release {
+            Some(release) => release.clone(),
+            None => distros::get_versions(distro)
+                .pop()
+                .map(|v| v.version)
+                .ok_or_else(|| anyhow::anyhow!("No supported {} release", distro.display_name()))?,
+        };
+        let entry = (distro.name().to_string(), version);
+        if !releases.contains(&entry) {
+            releases.push(entry);
+        }
     }
+    Ok(releases)
+}
+
+/// Print a rendered generation script (`-`) or write it to a file with a hint
+/// on running it by hand; returns the file written
+fn write_script(
+    path: &Path,
+    kernel: &str,
+    version: &DistroVersion,
+    script: &str,
+    options: &GenerateOptions,
+    output: &Output,
+) -> Result<Option<PathBuf>> {
+    if path == Path::new("-") {
+        if output.is_json() {
+            output.result(JsonResult {
+                success: true,
+                data: Some(serde_json::json!({
+                    "kernel": kernel,
+                    "distro": version.distro.name(),
+                    "distro_version": version.version,
+                    "image": version.docker_image,
+                    "script": script,
+                })),
+                error: None,
+            });
+        } else {
+            print!("{}", script);
+        }
+        return Ok(None);
+    }
+
+    std::fs::write(path, script).with_context(|| format!("Failed to write {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+            .with_context(|| format!("Failed to make {} executable", path.display()))?;
+    }
+    output.success(&format!(
+        "Generation script for {} written to {}",
+        kernel,
+        path.display()
+    ));
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    output.info(&format!(
+        "Run it in a {} container from the directory holding it, e.g. docker run --rm --platform {} -v \"$PWD:{}\" -w {} {} bash {}/{}",
+        version.docker_image,
+        options.arch.platform(),
+        WORK_DIR,
+        WORK_DIR,
+        version.docker_image,
+        WORK_DIR,
+        name
+    ));
+    for (host, container) in options.acquisition.resolve().inputs() {
+        output.info(&format!(
+            "Mount {} at {} as well",
+            host.display(),
+            container
+        ));
+    }
+    Ok(Some(path.to_path_buf()))
+}
+
+/// Map a derivative's `-d`/`-r` (`mint 21.3`) to the base release whose kernels it runs
+fn base_release(distro: String, version: String, output: &Output) -> (String, String) {
+    match distros::resolve_derivative(&distro, &version) {
+        Some((base, base_version)) => {
+            output.info(&format!(
+                "{} {} uses {} {} kernels",
+                distro,
+                version,
+                base.display_name(),
+                base_version
+            ));
+            (base.name().to_string(), base_version)
+        }
+        None => (distro, version),
+    }
+}
+
+/// Human-readable summary of an inspected symbol file
+fn print_info(output: &Output, info: &info::SymbolInfo) {
+    let unknown = || "unknown".to_string();
+    let compression = &info.compression;
+    output.info(&info.path);
+    output.info(&format!(
+        "  Producer:    {}",
+        info.producer.clone().unwrap_or_else(unknown)
+    ));
+    output.info(&format!(
+        "  Format:      {}",
+        info.format.clone().unwrap_or_else(unknown)
+    ));
+    output.info(&format!(
+        "  Banner:      {}",
+        info.banner.as_deref().unwrap_or("none")
+    ));
+    output.info(&format!(
+        "  Kernel:      {}",
+        info.kernel.clone().unwrap_or_else(unknown)
+    ));
+    output.info(&format!(
+        "  Contents:    {} symbols, {} user types, {} enums, {} base types",
+        info.symbols,
+        info.user_types,
+        info.enums,
+        info.base_types.len()
+    ));
+    let base_types: Vec<String> = info
+        .base_types
+        .iter()
+        .map(|base| match base.size {
+            Some(size) => format!("{} ({})", base.name, size),
+            None => base.name.clone(),
+        })
+        .collect();
+    if !base_types.is_empty() {
+        output.info(&format!("  Base types:  {}", base_types.join(", ")));
+    }
+    let mut storage = compression.format.to_string();
+    if let Some(check) = compression.check {
+        storage.push_str(&format!(", {} check", check));
+    }
+    if compression.format != "none" && compression.file_size > 0 {
+        storage.push_str(&format!(
+            ", {} -> {} ({:.1}x)",
+            estimate::format_bytes(compression.file_size),
+            estimate::format_bytes(compression.json_size),
+            compression.json_size as f64 / compression.file_size as f64
+        ));
+    } else {
+        storage.push_str(&format!(
+            ", {}",
+            estimate::format_bytes(compression.file_size)
+        ));
+    }
+    output.info(&format!("  Compression: {}", storage));
+    for source in &info.sources {
+        output.info(&format!(
+            "  Source:      {} from {} {}",
+            source.section,
+            source.kind.as_deref().unwrap_or("?"),
+            source.name.as_deref().unwrap_or("?")
+        ));
+    }
+    if let Some(symgen) = &info.symgen {
+        let field = |key: &str| {
+            symgen
+                .get(key)
+                .and_then(|value| value.as_str())
+                .unwrap_or("?")
+        };
+        output.info(&format!(
+            "  Generated:   by symgen {} at {} for {} {} {}",
+            field("version"),
+            field("generated_at"),
+            field("distro"),
+            field("distro_version"),
+            field("arch")
+        ));
+    }
+}
+
+/// Banner bytes from a `-b` argument: inline, `-` for stdin or `@path` for a file.
+///
+/// Input may span several lines (e.g. banners plugin output); only `Linux version`
+/// lines are parsed as banners.
+fn read_banner(arg: &OsStr) -> Result<Vec<u8>> {
+    if arg == "-" {
+        let mut input = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut input)
+            .context("Failed to read the banner from stdin")?;
+        return Ok(input);
+    }
+    if let Some(path) = arg.to_str().and_then(|arg| arg.strip_prefix('@')) {
+        return std::fs::read(path).with_context(|| format!("Failed to read banner file {}", path));
+    }
+    Ok(arg.as_encoded_bytes().to_vec())
+}
 
-    Ok(())
+/// Ask a yes/no question on the terminal (default no)
+fn confirm(question: &str) -> Result<bool> {
+    print!("{} [y/N] ", question);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
 }
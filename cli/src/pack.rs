@@ -0,0 +1,160 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use symgen_core::distros::{Arch, Distro, DistroVersion};
+use symgen_core::generator::{GenerateOptions, SymbolGenerator};
+use symgen_core::output::Output;
+
+/// Launchpad web service root
+const LAUNCHPAD_API: &str = "https://api.launchpad.net/1.0";
+/// Largest page Launchpad serves for collection queries
+const LAUNCHPAD_PAGE_SIZE: u32 = 300;
+
+/// A kernel published for a release
+#[derive(Debug, Serialize)]
+pub struct PublishedKernel {
+    pub kernel: String,
+    /// When the kernel first appeared, if the source records it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<DateTime<Utc>>,
+}
+
+/// One page of a Launchpad collection
+#[derive(Deserialize)]
+struct LaunchpadPage {
+    entries: Vec<BinaryPublication>,
+    next_collection_link: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BinaryPublication {
+    binary_package_name: String,
+    date_published: Option<DateTime<Utc>>,
+}
+
+/// Accept `YYYY-MM` (first of the month) or `YYYY-MM-DD`
+pub fn parse_since(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(&format!("{}-01", value), "%Y-%m-%d"))
+        .map_err(|_| "expected a date as YYYY-MM or YYYY-MM-DD".to_string())
+}
+
+/// Enumerate the kernels published for a release, oldest first.
+///
+/// Ubuntu's ddebs indexes only list the newest kernel of each pocket, so its
+/// history comes from Launchpad. Other releases are listed from their own
+/// repositories inside a container; Debian's indexes carry no dates, so
+/// `since` cannot narrow them down.
+pub async fn enumerate(
+    generator: &SymbolGenerator,
+    version: &DistroVersion,
+    flavor: Option<&str>,
+    since: Option<NaiveDate>,
+    options: &GenerateOptions,
+    output: &Output,
+) -> Result<Vec<PublishedKernel>> {
+    let flavor = flavor.unwrap_or_else(|| version.distro.default_flavor(options.arch));
+    let since = since.map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+
+    let mut kernels = match (version.distro, &version.codename) {
+        (Distro::Ubuntu, Some(codename)) => {
+            launchpad_kernels(codename, options.arch, flavor, since, output).await?
+        }
+        _ => {
+            let listed = generator
+                .list_published_kernels(version, flavor, options, output)
+                .await?;
+            if since.is_some() && listed.iter().any(|(_, built)| *built == 0) {
+                output.warning(&format!(
+                    "{} repositories do not record publication dates; --since is ignored for them",
+                    version.distro.display_name()
+                ));
+            }
+            listed
+                .into_iter()
+                .map(|(kernel, built)| PublishedKernel {
+                    kernel,
+                    published: DateTime::from_timestamp(built, 0).filter(|_| built > 0),
+                })
+                .collect()
+        }
+    };
+
+    if let Some(since) = since {
+        kernels.retain(|k| k.published.is_none_or(|published| published >= since));
+    }
+    kernels.sort_by(|a, b| {
+        a.published
+            .cmp(&b.published)
+            .then_with(|| a.kernel.cmp(&b.kernel))
+    });
+    Ok(kernels)
+}
+
+/// Kernel image packages published to the Ubuntu primary archive for a series
+async fn launchpad_kernels(
+    codename: &str,
+    arch: Arch,
+    flavor: &str,
+    since: Option<DateTime<Utc>>,
+    output: &Output,
+) -> Result<Vec<PublishedKernel>> {
+    output.info(&format!(
+        "Querying Launchpad for {} kernels published in {}...",
+        flavor, codename
+    ));
+    let pattern = Regex::new(&format!(
+        r"^linux-image-(?:unsigned-)?(\d+\.\d+\.\d+-\d+-{})$",
+        regex::escape(flavor)
+    ))?;
+
+    let mut url = format!(
+        "{LAUNCHPAD_API}/ubuntu/+archive/primary?ws.op=getPublishedBinaries\
+         &binary_name=linux-image-&exact_match=false\
+         &distro_arch_series={LAUNCHPAD_API}/ubuntu/{codename}/{arch}\
+         &ws.size={LAUNCHPAD_PAGE_SIZE}",
+        arch = arch.deb_arch(),
+    );
+    if let Some(since) = since {
+        url.push_str(&format!("&created_since_date={}", since.format("%Y-%m-%d")));
+    }
+
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("symgen/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    // The same kernel is published once per pocket; keep its earliest date
+    let mut kernels: BTreeMap<String, Option<DateTime<Utc>>> = BTreeMap::new();
+    let mut next = Some(url);
+    while let Some(url) = next {
+        let page: LaunchpadPage = client
+            .get(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Launchpad query failed: {}", url))?
+            .json()
+            .await
+            .context("Unexpected response from Launchpad")?;
+
+        for entry in page.entries {
+            let Some(kernel) = pattern
+                .captures(&entry.binary_package_name)
+                .map(|c| c[1].to_string())
+            else {
+                continue;
+            };
+            let published = kernels.entry(kernel).or_insert(entry.date_published);
+            if let Some(date) = entry.date_published {
+                *published = Some(published.map_or(date, |known| known.min(date)));
+            }
+        }
+        next = page.next_collection_link;
+    }
+
+    Ok(kernels
+        .into_iter()
+        .map(|(kernel, published)| PublishedKernel { kernel, published })
+        .collect())
+}
@@ -0,0 +1,57 @@
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+/// Web service used when none is configured (the docker-compose nginx front end)
+pub const DEFAULT_SERVER: &str = "http://localhost";
+
+/// Body of the web service's job actions
+#[derive(Deserialize)]
+struct ActionResponse {
+    success: bool,
+    message: String,
+}
+
+/// Body of a FastAPI error response
+#[derive(Deserialize)]
+struct ErrorResponse {
+    detail: String,
+}
+
+/// Cancel a queued or running job on a Symgen web service, returning its message.
+///
+/// The server stops the job's container, removes its scratch space and marks
+/// it cancelled.
+pub async fn cancel_job(server: &str, job_id: u64) -> Result<String> {
+    let url = format!(
+        "{}/api/symgen/jobs/{}/cancel",
+        server.trim_end_matches('/'),
+        job_id
+    );
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("symgen/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let response = client
+        .post(&url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {}", server))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let detail = response
+            .json::<ErrorResponse>()
+            .await
+            .map(|error| error.detail)
+            .unwrap_or_else(|_| status.to_string());
+        bail!("Could not cancel job {}: {}", job_id, detail);
+    }
+
+    let body: ActionResponse = response
+        .json()
+        .await
+        .with_context(|| format!("Unexpected response from {}", url))?;
+    if !body.success {
+        bail!("Could not cancel job {}: {}", job_id, body.message);
+    }
+    Ok(body.message)
+}
@@ -0,0 +1,281 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::config::expand_placeholders;
+use crate::distros::DistroVersion;
+use crate::script::{distro_script, shell_quote, Script, ScriptOptions};
+
+/// Default debuginfod server, federating the distributions' own servers
+pub const DEFAULT_DEBUGINFOD_URL: &str = "https://debuginfod.elfutils.org";
+/// Where a host file given with `--vmlinux` appears inside the container
+const LOCAL_INPUT_PATH: &str = "/symgen-input/vmlinux";
+/// Where downloaded debug files are staged before unpacking
+const DOWNLOAD_PATH: &str = "/tmp/symgen-download";
+
+/// A way of getting the debug kernel (`vmlinux` with DWARF) into the container.
+///
+/// Each strategy renders the steps up to and including `find_vmlinux`, which
+/// leaves `$VMLINUX` set; the shared dwarf2json steps follow.
+pub trait Acquisition {
+    /// Short name used in logs and `list --json`
+    fn name(&self) -> &'static str;
+
+    /// Script for the release, from the preamble through `find_vmlinux`
+    fn script(&self, version: &DistroVersion, kernel: &str, options: &ScriptOptions) -> Script;
+
+    /// Host files mounted read-only into the container, as (host path, container path)
+    fn inputs(&self) -> Vec<(PathBuf, String)> {
+        Vec::new()
+    }
+
+    /// Whether another release's repositories may hold the kernel when this one's do not
+    fn searches_repositories(&self) -> bool {
+        false
+    }
+}
+
+/// Names of the acquisition methods every release supports
+pub const METHODS: &[&str] = &["package-manager", "direct-url", "debuginfod", "local-file"];
+
+/// Acquisition selected on the command line
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AcquisitionSource {
+    /// Debug packages from the distribution's repositories
+    #[default]
+    PackageManager,
+    /// A vmlinux, `.ddeb`/`.deb` or `.rpm` downloaded from a URL
+    DirectUrl(String),
+    /// The kernel's debuginfo looked up by build ID on a debuginfod server
+    Debuginfod { server: String, build_id: String },
+    /// A vmlinux or debug package already on the host
+    LocalFile(PathBuf),
+}
+
+impl AcquisitionSource {
+    /// Strategy implementing this source
+    pub fn resolve(&self) -> Box<dyn Acquisition> {
+        match self {
+            Self::PackageManager => Box::new(PackageManager),
+            Self::DirectUrl(url) => Box::new(DirectUrl { url: url.clone() }),
+            Self::Debuginfod { server, build_id } => Box::new(Debuginfod {
+                server: server.trim_end_matches('/').to_string(),
+                build_id: build_id.to_lowercase(),
+            }),
+            Self::LocalFile(path) => Box::new(LocalFile { path: path.clone() }),
+        }
+    }
+}
+
+/// The distribution's debug symbol repositories (the built-in scripts, or
+/// the site's template for the distro when one is configured)
+pub struct PackageManager;
+
+impl Acquisition for PackageManager {
+    fn name(&self) -> &'static str {
+        "package-manager"
+    }
+
+    fn script(&self, version: &DistroVersion, kernel: &str, options: &ScriptOptions) -> Script {
+        match &options.template {
+            Some(template) => template_script(version, kernel, options, template),
+            None => distro_script(version, kernel, options),
+        }
+    }
+
+    fn searches_repositories(&self) -> bool {
+        true
+    }
+}
+
+/// A debug file downloaded from an arbitrary HTTPS (or HTTP) URL
+pub struct DirectUrl {
+    url: String,
+}
+
+impl Acquisition for DirectUrl {
+    fn name(&self) -> &'static str {
+        "direct-url"
+    }
+
+    fn script(&self, version: &DistroVersion, kernel: &str, options: &ScriptOptions) -> Script {
+        let url = shell_quote(&self.url);
+        base_script(version, kernel, options)
+            .step(
+                "fetch_vmlinux",
+                format!(
+                    r#"# Download the debug kernel
+echo ">>> Downloading {url}..."
+wget -q --max-redirect=5 {url} -O {DOWNLOAD_PATH}
+{unpack}"#,
+                    unpack = unpack_download(&self.url, kernel),
+                ),
+            )
+            .step("find_vmlinux", find_vmlinux(kernel))
+    }
+}
+
+/// Debuginfo served by debuginfod for a known build ID
+pub struct Debuginfod {
+    server: String,
+    build_id: String,
+}
+
+impl Acquisition for Debuginfod {
+    fn name(&self) -> &'static str {
+        "debuginfod"
+    }
+
+    fn script(&self, version: &DistroVersion, kernel: &str, options: &ScriptOptions) -> Script {
+        let server = shell_quote(&self.server);
+        let build_id = &self.build_id;
+        base_script(version, kernel, options)
+            .step(
+                "fetch_vmlinux",
+                format!(
+                    r#"# Fetch the kernel's debuginfo by build ID
+echo ">>> Fetching debuginfo for build ID {build_id} from {server}..."
+mkdir -p /usr/lib/debug/boot
+if ! wget -q --max-redirect=5 {server}/buildid/{build_id}/debuginfo -O /usr/lib/debug/boot/vmlinux-{kernel}; then
+    echo "ERROR: {server} has no debuginfo for build ID {build_id}"
+    exit 2
+fi
+"#
+                ),
+            )
+            .step("find_vmlinux", find_vmlinux(kernel))
+    }
+}
+
+/// A vmlinux or debug package supplied from the host
+pub struct LocalFile {
+    path: PathBuf,
+}
+
+impl Acquisition for LocalFile {
+    fn name(&self) -> &'static str {
+        "local-file"
+    }
+
+    fn script(&self, version: &DistroVersion, kernel: &str, options: &ScriptOptions) -> Script {
+        let name = self.path.to_string_lossy();
+        base_script(version, kernel, options)
+            .step(
+                "fetch_vmlinux",
+                format!(
+                    r#"# Use the debug kernel supplied from the host
+echo ">>> Using host file {name}..."
+cp {LOCAL_INPUT_PATH} {DOWNLOAD_PATH}
+{unpack}"#,
+                    name = shell_quote(&name),
+                    unpack = unpack_download(&name, kernel),
+                ),
+            )
+            .step("find_vmlinux", find_vmlinux(kernel))
+    }
+
+    fn inputs(&self) -> Vec<(PathBuf, String)> {
+        vec![(self.path.clone(), LOCAL_INPUT_PATH.to_string())]
+    }
+}
+
+/// The release's preamble and tool installation, without its repository steps
+fn base_script(version: &DistroVersion, kernel: &str, options: &ScriptOptions) -> Script {
+    distro_script(version, kernel, options).until("install_tools")
+}
+
+/// A site template in place of the release's built-in repository steps.
+///
+/// The template runs as a single `template` step and has to leave the debug
+/// kernel under `/usr/lib/debug`, with wget and xz installed for the steps after it.
+fn template_script(
+    version: &DistroVersion,
+    kernel: &str,
+    options: &ScriptOptions,
+    template: &str,
+) -> Script {
+    let vars = HashMap::from([
+        ("KERNEL".to_string(), kernel.to_string()),
+        ("DISTRO".to_string(), version.distro.name().to_string()),
+        ("VERSION".to_string(), version.version.clone()),
+        (
+            "CODENAME".to_string(),
+            version
+                .codename
+                .clone()
+                .unwrap_or_else(|| version.version.clone()),
+        ),
+        ("ARCH".to_string(), options.arch.name().to_string()),
+    ]);
+    let mut body = expand_placeholders(template, &vars);
+    if !body.ends_with('\n') {
+        body.push('\n');
+    }
+    Script::new(format!(
+        r#"#!/bin/bash
+set -e
+
+echo "=== Starting symbol generation for {distro} kernel {kernel} (site template) ==="
+
+# Save output directory (the mounted volume)
+OUTPUT_DIR="$PWD"
+
+# Configure apt for non-interactive mode
+export DEBIAN_FRONTEND=noninteractive
+"#,
+        distro = version.distro.display_name(),
+    ))
+    .step("template", body)
+    .step("find_vmlinux", find_vmlinux(kernel))
+}
+
+/// Unpack the staged download into `/` according to the source's file name
+fn unpack_download(source: &str, kernel: &str) -> String {
+    let source = source.split(['?', '#']).next().unwrap_or(source);
+    if source.ends_with(".ddeb") || source.ends_with(".deb") {
+        format!(
+            r#"dpkg-deb -x {DOWNLOAD_PATH} /
+rm -f {DOWNLOAD_PATH}
+"#
+        )
+    } else if source.ends_with(".rpm") {
+        format!(
+            r#"command -v cpio >/dev/null || dnf -y -q install cpio 2>/dev/null || yum -y -q install cpio
+(cd / && rpm2cpio {DOWNLOAD_PATH} | cpio -idm --quiet)
+rm -f {DOWNLOAD_PATH}
+"#
+        )
+    } else {
+        let decompress = if source.ends_with(".xz") {
+            "xz -dc"
+        } else if source.ends_with(".gz") {
+            "gzip -dc"
+        } else {
+            "cat"
+        };
+        format!(
+            r#"mkdir -p /usr/lib/debug/boot
+{decompress} {DOWNLOAD_PATH} > /usr/lib/debug/boot/vmlinux-{kernel}
+rm -f {DOWNLOAD_PATH}
+"#
+        )
+    }
+}
+
+/// `find_vmlinux` step covering the layouts of both deb and rpm debug packages
+fn find_vmlinux(kernel: &str) -> String {
+    format!(
+        r#"# Find the unpacked vmlinux
+echo ">>> Looking for vmlinux..."
+VMLINUX=$(find /usr/lib/debug -type f \( -name "vmlinux-{kernel}*" -o -path "*{kernel}*/vmlinux" \) 2>/dev/null | head -1)
+if [ -z "$VMLINUX" ]; then
+    echo "ERROR: vmlinux not found in the supplied file"
+    find /usr/lib/debug -name "vmlinux*" -type f 2>/dev/null || true
+    exit 2
+fi
+echo ">>> Found vmlinux: $VMLINUX"
+echo "@@RESULT:vmlinux=$VMLINUX@@"
+"#
+    )
+}
@@ -0,0 +1,82 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+/// Sidecar holding the symbol file's SHA256 in `sha256sum` format
+pub const CHECKSUM_SUFFIX: &str = "sha256";
+/// Sidecar describing how the symbol file was produced
+pub const MANIFEST_SUFFIX: &str = "manifest.json";
+/// Detached signature over the symbol file
+pub const SIGNATURE_SUFFIX: &str = "asc";
+/// Container output captured during generation
+pub const LOG_SUFFIX: &str = "log";
+
+/// Path of a sidecar file next to a symbol file (e.g. `foo.json.xz.sha256`)
+pub fn sidecar_path(symbol_file: &Path, suffix: &str) -> PathBuf {
+    let mut name = symbol_file.as_os_str().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Compute the hex-encoded SHA256 of a file
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut reader = BufReader::new(file);
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+
+    loop {
+        let read = reader
+            .read(&mut buf)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Chain-of-custody record written next to a symbol file with `--manifest`
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolManifest {
+    pub symbol_file: String,
+    pub sha256: String,
+    pub size: u64,
+    pub kernel: String,
+    pub distro: String,
+    pub distro_version: String,
+    pub arch: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// dwarf2json release the file was produced with, when known
+    pub dwarf2json_version: Option<String>,
+    pub dwarf2json_sha256: Option<String>,
+    pub source_package: Option<String>,
+    /// Where the System.map came from; None when the file was made without one
+    pub system_map_source: Option<String>,
+    /// The file was reused from the symbol cache rather than generated by this run
+    pub from_cache: bool,
+    pub symgen_version: String,
+    pub generated_at: String,
+}
+
+/// Write the `.sha256` (in `sha256sum` format) and `.manifest.json` sidecars of a symbol file
+pub fn write_manifest(symbol_file: &Path, manifest: &SymbolManifest) -> Result<()> {
+    let checksum_path = sidecar_path(symbol_file, CHECKSUM_SUFFIX);
+    std::fs::write(
+        &checksum_path,
+        format!("{}  {}\n", manifest.sha256, manifest.symbol_file),
+    )
+    .with_context(|| format!("Failed to write {}", checksum_path.display()))?;
+    let manifest_path = sidecar_path(symbol_file, MANIFEST_SUFFIX);
+    let json = serde_json::to_string_pretty(manifest).context("Failed to serialize manifest")?;
+    std::fs::write(&manifest_path, json + "\n")
+        .with_context(|| format!("Failed to write {}", manifest_path.display()))?;
+    Ok(())
+}
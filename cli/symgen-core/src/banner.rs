@@ -0,0 +1,483 @@
+use regex::bytes::Regex as BytesRegex;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+use crate::distros::{Arch, UBUNTU_FLAVORS};
+
+/// Longest banner accepted when scanning a buffer; real banners are ~200 bytes
+pub(crate) const MAX_BANNER_LEN: usize = 512;
+/// Derivatives whose own kernel builds name their build host in the banner,
+/// detected as their base distro
+const DERIVATIVE_HOSTS: &[(&str, &str)] = &[("pop-os.org", "Pop!_OS")];
+
+/// Result of parsing a kernel banner
+#[derive(Debug, Clone, Serialize)]
+pub struct BannerParseResult {
+    pub kernel_version: String,
+    pub distro: Option<String>,
+    pub distro_version: Option<String>,
+    /// Derivative that built the kernel on top of `distro`, e.g. `Pop!_OS`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub derivative: Option<String>,
+    /// Architecture named by the kernel release, compiler or build host
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arch: Option<Arch>,
+    /// Suggested symgen command to generate the symbol
+    pub suggested_command: Option<String>,
+}
+
+/// A kernel banner found in a byte buffer
+#[derive(Debug, Clone, Serialize)]
+pub struct BannerMatch {
+    /// Byte offset of the banner within the input
+    pub offset: usize,
+    /// Banner text with control characters stripped
+    pub banner: String,
+    #[serde(flatten)]
+    pub result: BannerParseResult,
+}
+
+/// Find and parse every kernel banner in a byte buffer.
+///
+/// The buffer may be a clean `/proc/version` line or a carved memory region;
+/// each `Linux version ...` run up to the next NUL or newline is a candidate,
+/// and invalid UTF-8 is decoded lossily. When no `Linux version` prefix is
+/// present the whole buffer is parsed as a single banner. Matches are returned
+/// in buffer order, including repeated copies of the same banner.
+pub fn parse_banner(buf: &[u8]) -> Vec<BannerMatch> {
+    let candidates: Vec<(usize, &[u8])> = banner_regex()
+        .find_iter(buf)
+        .map(|m| (m.start(), m.as_bytes()))
+        .collect();
+    let candidates = if candidates.is_empty() {
+        vec![(0, buf)]
+    } else {
+        candidates
+    };
+
+    candidates
+        .into_iter()
+        .filter_map(|(offset, bytes)| {
+            let banner = normalize_banner(&String::from_utf8_lossy(bytes));
+            parse_banner_str(&banner).map(|result| BannerMatch {
+                offset,
+                banner,
+                result,
+            })
+        })
+        .collect()
+}
+
+/// Distinct banners in the JSON output of `vol -r json banners.Banners`, in file order.
+///
+/// Rows are objects with a `Banner` column, nested under `__children` in tree
+/// output; every such string anywhere in the document is collected.
+pub fn vol3_banners(json: &serde_json::Value) -> Vec<String> {
+    fn collect(value: &serde_json::Value, banners: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Array(items) => items.iter().for_each(|item| collect(item, banners)),
+            serde_json::Value::Object(row) => {
+                for (key, value) in row {
+                    match value.as_str() {
+                        Some(text) if key == "Banner" => {
+                            let banner = normalize_banner(text);
+                            if !banner.is_empty() && !banners.contains(&banner) {
+                                banners.push(banner);
+                            }
+                        }
+                        _ => collect(value, banners),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut banners = Vec::new();
+    collect(json, &mut banners);
+    banners
+}
+
+/// `Linux version <major>.<minor>...` up to the next NUL or newline
+pub(crate) fn banner_regex() -> &'static BytesRegex {
+    static RE: OnceLock<BytesRegex> = OnceLock::new();
+    RE.get_or_init(|| {
+        BytesRegex::new(&format!(
+            r"(?-u)Linux version \d+\.\d+[^\x00\n]{{0,{}}}",
+            MAX_BANNER_LEN
+        ))
+        .expect("valid banner regex")
+    })
+}
+
+/// Parse a single kernel banner string to extract kernel version and distro information.
+///
+/// Supports various banner formats:
+/// - Ubuntu: "Linux version 5.15.0-91-generic (buildd@...) (gcc (Ubuntu 11.4.0-1ubuntu1~22.04)..."
+/// - Debian: "Linux version 5.10.0-28-amd64 (debian-kernel@...) (gcc-10 (Debian 10.2.1-6)..."
+/// - Fedora: "Linux version 6.5.6-300.fc39.x86_64 (mockbuild@...) (gcc (GCC) 13.2.1..."
+/// - RHEL/CentOS: "Linux version 4.18.0-513.el8.x86_64 (mockbuild@...) (gcc (GCC) 8.5.0..."
+/// - Kali: "Linux version 6.6.9-amd64 (devel@kali.org) (x86_64-linux-gnu-gcc-13 (Debian 13.2.0-9)..."
+/// - WSL2: "Linux version 5.15.153.1-microsoft-standard-WSL2 (root@...) (gcc (GCC) 11.2.0..."
+///
+/// Linux Mint, LMDE and KDE neon run their base release's kernels unchanged,
+/// so their banners are detected as Ubuntu or Debian.
+pub fn parse_banner_str(banner: &str) -> Option<BannerParseResult> {
+    let banner = normalize_banner(banner);
+    let banner = banner.as_str();
+    if banner.is_empty() {
+        return None;
+    }
+
+    let banner_lower = banner.to_lowercase();
+    let arch = Arch::detect(banner);
+
+    // WSL2 kernels name no distribution, only Microsoft's own suffix
+    if banner_lower.contains("-microsoft-standard") {
+        let kernel_version = cached_regex!(r"Linux version (\S+-microsoft-standard\S*)")
+            .captures(banner)?[1]
+            .to_string();
+        return Some(BannerParseResult {
+            suggested_command: Some(format!(
+                "symgen generate -k {} -d wsl -r 2{}",
+                kernel_version,
+                arch_option(arch, &kernel_version)
+            )),
+            kernel_version,
+            distro: Some("WSL2".to_string()),
+            distro_version: Some("2".to_string()),
+            derivative: None,
+            arch,
+        });
+    }
+
+    // Detect distribution; Kali kernels are built with Debian's compiler
+    let is_kali = banner_lower.contains("kali");
+    let is_ubuntu = banner_lower.contains("ubuntu");
+    let is_debian = banner_lower.contains("debian") && !is_kali;
+    let is_fedora = banner_lower.contains("fedora") || banner_lower.contains(".fc");
+    let is_rhel = banner_lower.contains("red hat") || banner_lower.contains(".el");
+    let is_centos = banner_lower.contains("centos");
+    let is_rocky = banner_lower.contains("rocky");
+    let is_alma = banner_lower.contains("alma");
+    let is_oracle = banner_lower.contains("oracle") || banner_lower.contains(".ol");
+
+    // Extract kernel version based on detected distro
+    let kernel_version = extract_kernel_version(
+        banner,
+        &banner_lower,
+        is_ubuntu,
+        is_debian,
+        is_fedora,
+        is_rhel,
+        is_centos,
+        is_rocky,
+        is_alma,
+        is_oracle,
+    )?;
+
+    // Determine distro and version
+    let (distro, distro_version) = if is_kali {
+        (Some("Kali".to_string()), Some("rolling".to_string()))
+    } else {
+        determine_distro_version(
+            banner,
+            &banner_lower,
+            &kernel_version,
+            is_ubuntu,
+            is_debian,
+            is_fedora,
+            is_rhel,
+            is_centos,
+            is_rocky,
+            is_alma,
+            is_oracle,
+        )
+    };
+    let derivative = DERIVATIVE_HOSTS
+        .iter()
+        .find(|(host, _)| banner_lower.contains(host))
+        .map(|(_, name)| name.to_string());
+
+    // Generate suggested command
+    let suggested_command = if let (Some(ref d), Some(ref v)) = (&distro, &distro_version) {
+        Some(format!(
+            "symgen generate -k {} -d {} -V {}{}",
+            kernel_version,
+            d.to_lowercase(),
+            v,
+            arch_option(arch, &kernel_version)
+        ))
+    } else {
+        None
+    };
+
+    Some(BannerParseResult {
+        kernel_version,
+        distro,
+        distro_version,
+        derivative,
+        arch,
+        suggested_command,
+    })
+}
+
+/// `--arch` option for a suggested command, needed unless the kernel is
+/// amd64 or its name carries the architecture
+fn arch_option(arch: Option<Arch>, kernel: &str) -> String {
+    match arch {
+        Some(arch) if arch != Arch::Amd64 && !arch.is_named_in(kernel) => {
+            format!(" --arch {}", arch.name())
+        }
+        _ => String::new(),
+    }
+}
+
+/// Ubuntu upload of a kernel build, from the `#101-Ubuntu` (or HWE
+/// `#101~22.04.1-Ubuntu`) build tag of its banner
+pub fn ubuntu_upload(banner: &str) -> Option<String> {
+    cached_regex!(r"#(\d+(?:~[\d.]+)?)-Ubuntu")
+        .captures(banner)
+        .map(|cap| cap[1].to_string())
+}
+
+/// Strip NUL padding and control characters and collapse whitespace runs
+pub(crate) fn normalize_banner(banner: &str) -> String {
+    banner
+        .split(|c: char| c.is_whitespace() || c.is_control())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[allow(clippy::too_many_arguments)]
+fn extract_kernel_version(
+    banner: &str,
+    _banner_lower: &str,
+    is_ubuntu: bool,
+    is_debian: bool,
+    is_fedora: bool,
+    is_rhel: bool,
+    is_centos: bool,
+    is_rocky: bool,
+    is_alma: bool,
+    is_oracle: bool,
+) -> Option<String> {
+    if is_debian {
+        // Debian pattern: 5.10.0-28-amd64, 6.1.0-18-amd64
+        let re = cached_regex!(r"Linux version (\d+\.\d+\.\d+-\d+-amd64)");
+        if let Some(cap) = re.captures(banner) {
+            return Some(cap[1].to_string());
+        }
+        let re = cached_regex!(r"(\d+\.\d+\.\d+-\d+-amd64)");
+        if let Some(cap) = re.captures(banner) {
+            return Some(cap[1].to_string());
+        }
+    } else if is_ubuntu {
+        // Ubuntu pattern: 5.15.0-91-generic, 5.15.0-1050-aws, 5.15.0-1045-intel-iotg
+        let re = cached_regex!(r"Linux version (\d+\.\d+\.\d+-\d+-[a-z][a-z0-9-]*)");
+        if let Some(cap) = re.captures(banner) {
+            return Some(cap[1].to_string());
+        }
+        let re = cached_regex!(&{
+            // Longest first, so `generic-64k` is not cut short at `generic`
+            let mut flavors = UBUNTU_FLAVORS.to_vec();
+            flavors.sort_by_key(|flavor| std::cmp::Reverse(flavor.len()));
+            format!(r"(\d+\.\d+\.\d+-\d+-(?:{}))\b", flavors.join("|"))
+        });
+        if let Some(cap) = re.captures(banner) {
+            return Some(cap[1].to_string());
+        }
+    } else if is_fedora {
+        // Fedora pattern: 6.5.6-300.fc39.x86_64
+        let re =
+            cached_regex!(r"Linux version (\d+\.\d+\.\d+-\d+\.fc\d+\.[a-z0-9_]+(?:\+[a-z0-9-]+)?)");
+        if let Some(cap) = re.captures(banner) {
+            return Some(cap[1].to_string());
+        }
+        let re = cached_regex!(r"(\d+\.\d+\.\d+-\d+\.fc\d+\.[a-z0-9_]+)");
+        if let Some(cap) = re.captures(banner) {
+            return Some(cap[1].to_string());
+        }
+    } else if is_rhel || is_centos || is_rocky || is_alma || is_oracle {
+        // RHEL-based pattern: 4.18.0-513.el8.x86_64, 5.14.0-362.el9.x86_64
+        let re = cached_regex!(
+            r"Linux version (\d+\.\d+\.\d+-[\d.]+\.el\d+[a-z0-9_.]*(?:\+[a-z0-9-]+)?)"
+        );
+        if let Some(cap) = re.captures(banner) {
+            return Some(cap[1].to_string());
+        }
+        let re = cached_regex!(r"(\d+\.\d+\.\d+-[\d.]+\.el\d+[a-z0-9_.]*)");
+        if let Some(cap) = re.captures(banner) {
+            return Some(cap[1].to_string());
+        }
+        // Oracle UEK pattern: 5.15.0-100.96.32.el8uek.x86_64
+        let re = cached_regex!(r"(\d+\.\d+\.\d+-[\d.]+\.el\d+uek[a-z0-9_.]*)");
+        if let Some(cap) = re.captures(banner) {
+            return Some(cap[1].to_string());
+        }
+    }
+
+    // Generic fallback
+    let re = cached_regex!(r"Linux version (\d+\.\d+\.\d+[^\s]*)");
+    if let Some(cap) = re.captures(banner) {
+        return Some(cap[1].to_string());
+    }
+    let re = cached_regex!(r"(\d+\.\d+\.\d+-\d+-[a-z]+)");
+    if let Some(cap) = re.captures(banner) {
+        return Some(cap[1].to_string());
+    }
+
+    None
+}
+
+#[allow(clippy::too_many_arguments)]
+fn determine_distro_version(
+    banner: &str,
+    banner_lower: &str,
+    kernel_version: &str,
+    is_ubuntu: bool,
+    is_debian: bool,
+    is_fedora: bool,
+    is_rhel: bool,
+    is_centos: bool,
+    is_rocky: bool,
+    is_alma: bool,
+    is_oracle: bool,
+) -> (Option<String>, Option<String>) {
+    if is_ubuntu {
+        let version = if banner.contains("~24.04") || banner_lower.contains("noble") {
+            Some("24.04".to_string())
+        } else if banner.contains("~22.04") || banner_lower.contains("jammy") {
+            Some("22.04".to_string())
+        } else if banner.contains("~20.04") || banner_lower.contains("focal") {
+            Some("20.04".to_string())
+        } else {
+            // Guess from kernel version
+            let major_minor = kernel_version.split('-').next().unwrap_or("");
+            if major_minor.starts_with("5.4.") {
+                Some("20.04".to_string())
+            } else if major_minor.starts_with("5.15.") || major_minor.starts_with("5.19.") {
+                Some("22.04".to_string())
+            } else if major_minor.starts_with("6.") {
+                Some("24.04".to_string())
+            } else {
+                None
+            }
+        };
+        return (Some("Ubuntu".to_string()), version);
+    }
+
+    if is_debian {
+        let version = if banner_lower.contains("buster") || banner_lower.contains("debian 10") {
+            Some("10".to_string())
+        } else if banner_lower.contains("bullseye") || banner_lower.contains("debian 11") {
+            Some("11".to_string())
+        } else if banner_lower.contains("bookworm") || banner_lower.contains("debian 12") {
+            Some("12".to_string())
+        } else {
+            // Guess from kernel version
+            let major_minor = kernel_version.split('-').next().unwrap_or("");
+            if major_minor.starts_with("4.19.") {
+                Some("10".to_string())
+            } else if major_minor.starts_with("5.10.") {
+                Some("11".to_string())
+            } else if major_minor.starts_with("6.1.") {
+                Some("12".to_string())
+            } else {
+                None
+            }
+        };
+        return (Some("Debian".to_string()), version);
+    }
+
+    if is_fedora {
+        // Extract Fedora version from kernel (e.g., fc39 -> 39)
+        let version = cached_regex!(r"\.fc(\d+)\.")
+            .captures(kernel_version)
+            .map(|cap| cap[1].to_string());
+        return (Some("Fedora".to_string()), version);
+    }
+
+    // RHEL-based distros - extract version from .el suffix
+    let el_version = cached_regex!(r"\.el(\d+)")
+        .captures(kernel_version)
+        .map(|cap| cap[1].to_string());
+
+    if is_centos {
+        return (Some("CentOS".to_string()), el_version);
+    }
+
+    if is_rocky {
+        return (Some("Rocky".to_string()), el_version);
+    }
+
+    if is_alma {
+        return (Some("Alma".to_string()), el_version);
+    }
+
+    if is_oracle {
+        return (Some("Oracle".to_string()), el_version);
+    }
+
+    if is_rhel {
+        return (Some("RHEL".to_string()), el_version);
+    }
+
+    (None, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UBUNTU: &str = "Linux version 5.15.0-91-generic (buildd@lcy02-amd64-045) \
+        (gcc (Ubuntu 11.4.0-1ubuntu1~22.04) 11.4.0, GNU ld (GNU Binutils for Ubuntu) 2.38) \
+        #101-Ubuntu SMP Tue Nov 14 13:30:08 UTC 2023";
+
+    #[test]
+    fn parses_ubuntu_banner() {
+        let matches = parse_banner(UBUNTU.as_bytes());
+        assert_eq!(matches.len(), 1);
+        let result = &matches[0].result;
+        assert_eq!(result.kernel_version, "5.15.0-91-generic");
+        assert_eq!(result.distro.as_deref(), Some("Ubuntu"));
+        assert_eq!(result.distro_version.as_deref(), Some("22.04"));
+    }
+
+    #[test]
+    fn finds_every_copy_in_a_buffer() {
+        let mut buf = b"\x00\xffgarbage".to_vec();
+        buf.extend_from_slice(UBUNTU.as_bytes());
+        buf.extend_from_slice(b"\x00more\n");
+        let second = buf.len();
+        buf.extend_from_slice(UBUNTU.as_bytes());
+        buf.push(0);
+
+        let matches = parse_banner(&buf);
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].offset, 9);
+        assert_eq!(matches[1].offset, second);
+        assert_eq!(matches[0].banner, matches[1].banner);
+    }
+
+    #[test]
+    fn parses_rpm_banner() {
+        let matches = parse_banner(
+            b"Linux version 6.5.6-300.fc39.x86_64 (mockbuild@bkernel01.iad2.fedoraproject.org) \
+              (gcc (GCC) 13.2.1 20230918 (Red Hat 13.2.1-3), GNU ld version 2.40-13.fc39) \
+              #1 SMP PREEMPT_DYNAMIC Fri Oct  6 19:57:21 UTC 2023",
+        );
+        assert_eq!(matches.len(), 1);
+        let result = &matches[0].result;
+        assert_eq!(result.kernel_version, "6.5.6-300.fc39.x86_64");
+        assert_eq!(result.distro.as_deref(), Some("Fedora"));
+        assert_eq!(result.distro_version.as_deref(), Some("39"));
+    }
+
+    #[test]
+    fn rejects_non_banners() {
+        assert!(parse_banner(b"").is_empty());
+        assert!(parse_banner(b"not a kernel banner").is_empty());
+    }
+}
@@ -0,0 +1,270 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::banner::{normalize_banner, parse_banner_str};
+use crate::isf;
+use crate::output::Output;
+
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS symbols (
+    path TEXT PRIMARY KEY,
+    banner TEXT NOT NULL,
+    kernel TEXT,
+    distro TEXT,
+    distro_version TEXT,
+    arch TEXT,
+    size INTEGER NOT NULL,
+    modified TEXT NOT NULL,
+    generated_at TEXT,
+    indexed_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS symbols_banner ON symbols (banner);";
+
+const COLUMNS: &str =
+    "path, banner, kernel, distro, distro_version, arch, size, modified, generated_at, indexed_at";
+
+/// A symbol file in the index
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexedSymbol {
+    pub path: String,
+    /// Normalized `linux_banner` of the file
+    pub banner: String,
+    pub kernel: Option<String>,
+    pub distro: Option<String>,
+    pub distro_version: Option<String>,
+    pub arch: Option<String>,
+    pub size: u64,
+    /// File modification time when indexed; a changed file is read again
+    pub modified: String,
+    /// From the `--annotate` metadata, else the dwarf2json producer record
+    pub generated_at: Option<String>,
+    pub indexed_at: String,
+}
+
+impl IndexedSymbol {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            path: row.get(0)?,
+            banner: row.get(1)?,
+            kernel: row.get(2)?,
+            distro: row.get(3)?,
+            distro_version: row.get(4)?,
+            arch: row.get(5)?,
+            size: row.get::<_, i64>(6)? as u64,
+            modified: row.get(7)?,
+            generated_at: row.get(8)?,
+            indexed_at: row.get(9)?,
+        })
+    }
+}
+
+/// What an index update did
+#[derive(Debug, Default, Serialize)]
+pub struct IndexStats {
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    /// Entries whose file is gone
+    pub removed: usize,
+    /// Files that are not symbol files or record no banner
+    pub skipped: usize,
+}
+
+/// Index of local symbol files by kernel banner, kept in `banners.db`
+pub struct BannerIndex {
+    conn: Connection,
+}
+
+impl BannerIndex {
+    /// Open or create the index in `dir`
+    pub fn open(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = dir.join("banners.db");
+        let conn = Connection::open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        conn.busy_timeout(Duration::from_secs(10))?;
+        conn.execute_batch(SCHEMA)
+            .context("Failed to create banner index")?;
+        Ok(Self { conn })
+    }
+
+    /// Index the symbol files under `roots` and drop entries whose file is gone.
+    ///
+    /// Files unchanged since they were indexed are not read again, so
+    /// rebuilding a large collection is quick.
+    pub fn update(&self, roots: &[PathBuf], output: &Output) -> Result<IndexStats> {
+        let mut stats = IndexStats::default();
+        let mut files = Vec::new();
+        for root in roots {
+            collect_symbol_files(root, &mut files)?;
+        }
+
+        for path in files {
+            // Keyed by absolute path so builds from different directories agree
+            let path = std::env::current_dir()
+                .map(|cwd| cwd.join(&path))
+                .unwrap_or(path);
+            let key = path.to_string_lossy().to_string();
+            let metadata = std::fs::metadata(&path)?;
+            let modified = chrono::DateTime::<chrono::Utc>::from(metadata.modified()?).to_rfc3339();
+
+            let indexed: Option<(i64, String)> = self
+                .conn
+                .query_row(
+                    "SELECT size, modified FROM symbols WHERE path = ?1",
+                    params![key],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+            if indexed
+                .as_ref()
+                .is_some_and(|(size, time)| *size as u64 == metadata.len() && *time == modified)
+            {
+                stats.unchanged += 1;
+                continue;
+            }
+
+            output.progress(&format!("Indexing {}", path.display()));
+            let Some(symbol) = read_symbol_file(&path, key, metadata.len(), modified) else {
+                stats.skipped += 1;
+                continue;
+            };
+            self.conn
+                .execute(
+                    &format!(
+                        "INSERT OR REPLACE INTO symbols ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                        COLUMNS
+                    ),
+                    params![
+                        symbol.path,
+                        symbol.banner,
+                        symbol.kernel,
+                        symbol.distro,
+                        symbol.distro_version,
+                        symbol.arch,
+                        symbol.size as i64,
+                        symbol.modified,
+                        symbol.generated_at,
+                        symbol.indexed_at
+                    ],
+                )
+                .context("Failed to update banner index")?;
+            if indexed.is_some() {
+                stats.updated += 1;
+            } else {
+                stats.added += 1;
+            }
+        }
+
+        for symbol in self.all()? {
+            if !Path::new(&symbol.path).is_file() {
+                self.conn
+                    .execute("DELETE FROM symbols WHERE path = ?1", params![symbol.path])?;
+                stats.removed += 1;
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Symbol files matching a kernel banner; whitespace and NUL padding are ignored
+    pub fn lookup(&self, banner: &str) -> Result<Vec<IndexedSymbol>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM symbols WHERE banner = ?1 ORDER BY path",
+            COLUMNS
+        ))?;
+        let symbols = stmt
+            .query_map(params![normalize_banner(banner)], IndexedSymbol::from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Failed to read banner index")?;
+        Ok(symbols)
+    }
+
+    /// Every indexed symbol file, ordered by banner
+    pub fn all(&self) -> Result<Vec<IndexedSymbol>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM symbols ORDER BY banner, path",
+            COLUMNS
+        ))?;
+        let symbols = stmt
+            .query_map([], IndexedSymbol::from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Failed to read banner index")?;
+        Ok(symbols)
+    }
+}
+
+/// `.json` and `.json.xz` files under `root`, following symlinks into the store
+pub(crate) fn collect_symbol_files(root: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if root.is_file() {
+        files.push(root.to_path_buf());
+        return Ok(());
+    }
+    let entries =
+        std::fs::read_dir(root).with_context(|| format!("Failed to read {}", root.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if path.is_dir() {
+            collect_symbol_files(&path, files)?;
+        } else if name.ends_with(".json") || name.ends_with(".json.xz") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Index entry for a symbol file, or None if it is not one or records no banner
+fn read_symbol_file(
+    path: &Path,
+    key: String,
+    size: u64,
+    modified: String,
+) -> Option<IndexedSymbol> {
+    let isf = isf::load(path).ok()?;
+    let banner = normalize_banner(&isf::banner(&isf)?);
+    let text = |pointer: &str| {
+        isf.pointer(pointer)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+
+    // Annotated files say how they were made; otherwise go by the banner
+    let (kernel, distro, distro_version, arch) = match isf.pointer("/metadata/symgen") {
+        Some(_) => (
+            text("/metadata/symgen/kernel"),
+            text("/metadata/symgen/distro"),
+            text("/metadata/symgen/distro_version"),
+            text("/metadata/symgen/arch"),
+        ),
+        None => match parse_banner_str(&banner) {
+            Some(parsed) => (
+                Some(parsed.kernel_version),
+                parsed.distro,
+                parsed.distro_version,
+                parsed.arch.map(|arch| arch.name().to_string()),
+            ),
+            None => (None, None, None, None),
+        },
+    };
+
+    Some(IndexedSymbol {
+        path: key,
+        banner,
+        kernel,
+        distro,
+        distro_version,
+        arch,
+        size,
+        modified,
+        generated_at: text("/metadata/symgen/generated_at")
+            .or_else(|| text("/metadata/producer/datetime")),
+        indexed_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
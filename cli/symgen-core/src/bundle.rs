@@ -0,0 +1,128 @@
+use anyhow::{bail, Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use crate::artifacts::{
+    sha256_file, sidecar_path, CHECKSUM_SUFFIX, LOG_SUFFIX, MANIFEST_SUFFIX, SIGNATURE_SUFFIX,
+};
+
+/// Top-level manifest written into every evidence bundle
+#[derive(Debug, Serialize)]
+pub struct BundleManifest {
+    pub symgen_version: String,
+    pub created_at: String,
+    pub symbol_file: String,
+    pub files: Vec<BundleEntry>,
+}
+
+/// A file packaged into the bundle
+#[derive(Debug, Serialize)]
+pub struct BundleEntry {
+    pub name: String,
+    pub role: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// Name of the manifest inside the bundle
+const BUNDLE_MANIFEST: &str = "MANIFEST.json";
+
+/// Files looked for next to the symbol file, with the role recorded in the manifest
+const SIDECARS: &[(&str, &str)] = &[
+    (CHECKSUM_SUFFIX, "checksum"),
+    (MANIFEST_SUFFIX, "provenance"),
+    (SIGNATURE_SUFFIX, "signature"),
+    ("manifest.json.asc", "provenance-signature"),
+    (LOG_SUFFIX, "generation-log"),
+];
+
+/// Default bundle path for a symbol file (`<symbol>.bundle.tar.gz`)
+pub fn default_bundle_path(symbol_file: &Path) -> PathBuf {
+    sidecar_path(symbol_file, "bundle.tar.gz")
+}
+
+/// Package a symbol file and its sidecars into a gzipped tarball.
+///
+/// Returns the manifest together with the sidecar roles that were not found.
+pub fn export_bundle(
+    symbol_file: &Path,
+    bundle_path: &Path,
+) -> Result<(BundleManifest, Vec<&'static str>)> {
+    if !symbol_file.is_file() {
+        bail!("Symbol file not found: {}", symbol_file.display());
+    }
+
+    let symbol_name = file_name(symbol_file)?;
+
+    let mut members = vec![(symbol_file.to_path_buf(), "symbols")];
+    let mut missing = Vec::new();
+    for (suffix, role) in SIDECARS {
+        let path = sidecar_path(symbol_file, suffix);
+        if path.is_file() {
+            members.push((path, *role));
+        } else {
+            missing.push(*role);
+        }
+    }
+
+    let mut files = Vec::new();
+    for (path, role) in &members {
+        files.push(BundleEntry {
+            name: file_name(path)?,
+            role: role.to_string(),
+            sha256: sha256_file(path)?,
+            size: std::fs::metadata(path)?.len(),
+        });
+    }
+
+    let manifest = BundleManifest {
+        symgen_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        symbol_file: symbol_name.clone(),
+        files,
+    };
+
+    // Everything lives under a single top-level directory named after the symbol file
+    let root = symbol_name.trim_end_matches(".json.xz").to_string();
+
+    let file = File::create(bundle_path)
+        .with_context(|| format!("Failed to create bundle {}", bundle_path.display()))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(chrono::Utc::now().timestamp() as u64);
+    header.set_cksum();
+    archive
+        .append_data(
+            &mut header,
+            format!("{}/{}", root, BUNDLE_MANIFEST),
+            manifest_json.as_slice(),
+        )
+        .context("Failed to write bundle manifest")?;
+
+    for (path, _) in &members {
+        archive
+            .append_path_with_name(path, format!("{}/{}", root, file_name(path)?))
+            .with_context(|| format!("Failed to add {} to bundle", path.display()))?;
+    }
+
+    archive
+        .into_inner()
+        .context("Failed to finish bundle")?
+        .finish()
+        .context("Failed to compress bundle")?;
+
+    Ok((manifest, missing))
+}
+
+fn file_name(path: &Path) -> Result<String> {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .with_context(|| format!("Invalid file path {}", path.display()))
+}
@@ -0,0 +1,248 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Cache subdirectory holding generated symbol files
+pub const ISF_NAMESPACE: &str = "isf";
+/// How long to wait for another machine to release a lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(600);
+/// Locks older than this are assumed to belong to a crashed process
+const STALE_LOCK_AGE: Duration = Duration::from_secs(2 * 60 * 60);
+/// Delay between attempts to take a held lock
+const LOCK_RETRY: Duration = Duration::from_millis(250);
+
+/// A file in the cache
+#[derive(Debug, Serialize)]
+pub struct CacheEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub size: u64,
+    /// When the entry was last stored or reused
+    pub last_used: DateTime<Utc>,
+}
+
+/// Cache directory, possibly shared with other machines over a network filesystem
+///
+/// Entries are written to a temporary file and renamed into place, so readers
+/// never see partial files; writers serialize on `<entry>.lock` files created
+/// with `O_EXCL`, which unlike `flock` behaves on NFS and SMB mounts.
+#[derive(Clone)]
+pub struct Cache {
+    root: PathBuf,
+}
+
+impl Cache {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Path of an entry, whether or not it exists
+    pub fn entry_path(&self, namespace: &str, name: &str) -> PathBuf {
+        self.root.join(namespace).join(name)
+    }
+
+    /// Copy a cached entry to `dest`; returns false when the entry is missing.
+    ///
+    /// The entry's modification time is bumped so pruning keeps what is in use.
+    pub fn fetch(&self, namespace: &str, name: &str, dest: &Path) -> Result<bool> {
+        let path = self.entry_path(namespace, name);
+        if !path.is_file() {
+            return Ok(false);
+        }
+        copy_atomic(&path, dest)?;
+        // A read-only shared cache still serves entries, it just cannot track use
+        if let Ok(file) = OpenOptions::new().write(true).open(&path) {
+            file.set_modified(SystemTime::now()).ok();
+        }
+        Ok(true)
+    }
+
+    /// Entries of a namespace, most recently used first
+    pub fn list(&self, namespace: &str) -> Result<Vec<CacheEntry>> {
+        let dir = self.root.join(namespace);
+        let read_dir = match std::fs::read_dir(&dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", dir.display())),
+        };
+
+        let mut entries = Vec::new();
+        for item in read_dir {
+            let item = item.with_context(|| format!("Failed to read {}", dir.display()))?;
+            let name = item.file_name().to_string_lossy().to_string();
+            // Locks and in-flight writes are not entries
+            if name.ends_with(".lock") || name.contains(".tmp-") {
+                continue;
+            }
+            let metadata = item.metadata()?;
+            if !metadata.is_file() {
+                continue;
+            }
+            entries.push(CacheEntry {
+                name,
+                path: item.path(),
+                size: metadata.len(),
+                last_used: metadata.modified()?.into(),
+            });
+        }
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.last_used));
+        Ok(entries)
+    }
+
+    /// Remove entries unused for longer than `max_age`, then the least recently
+    /// used ones until the namespace fits in `max_bytes`. Returns the removed entries.
+    ///
+    /// With `only`, other entries are left alone and not counted.
+    pub fn prune(
+        &self,
+        namespace: &str,
+        max_age: Option<Duration>,
+        max_bytes: Option<u64>,
+        only: Option<&HashSet<String>>,
+    ) -> Result<Vec<CacheEntry>> {
+        let now = Utc::now();
+        let mut kept_bytes = 0;
+        let mut full = false;
+        let mut removed = Vec::new();
+
+        for entry in self.list(namespace)? {
+            if only.is_some_and(|names| !names.contains(&entry.name)) {
+                continue;
+            }
+            let expired = max_age.is_some_and(|age| {
+                (now - entry.last_used)
+                    .to_std()
+                    .is_ok_and(|unused| unused > age)
+            });
+            full = full || max_bytes.is_some_and(|max| kept_bytes + entry.size > max);
+            if !expired && !full {
+                kept_bytes += entry.size;
+                continue;
+            }
+
+            let _lock = FileLock::acquire(&entry.path)?;
+            std::fs::remove_file(&entry.path)
+                .with_context(|| format!("Failed to remove {}", entry.path.display()))?;
+            removed.push(entry);
+        }
+        Ok(removed)
+    }
+
+    /// Store a copy of `src` as an entry, replacing any previous one
+    pub fn store(&self, namespace: &str, name: &str, src: &Path) -> Result<()> {
+        let path = self.entry_path(namespace, name);
+        let dir = path
+            .parent()
+            .context("Cache entry has no parent directory")?;
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create cache directory {}", dir.display()))?;
+
+        let _lock = FileLock::acquire(&path)?;
+        copy_atomic(src, &path)
+    }
+}
+
+/// Exclusive lock on a path, held through a `<path>.lock` file
+pub struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    /// Wait for and take the lock guarding `target`
+    pub fn acquire(target: &Path) -> Result<Self> {
+        let path = sibling(target, "lock");
+        let started = Instant::now();
+
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    // Record the owner to make stuck locks easy to trace
+                    writeln!(file, "{} {}", hostname(), std::process::id()).ok();
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    if is_stale(&path) {
+                        tracing::warn!("Removing stale lock {}", path.display());
+                        std::fs::remove_file(&path).ok();
+                        continue;
+                    }
+                    if started.elapsed() > LOCK_TIMEOUT {
+                        bail!("Timed out waiting for lock {}", path.display());
+                    }
+                    std::thread::sleep(LOCK_RETRY);
+                }
+                Err(e) => {
+                    return Err(e)
+                        .with_context(|| format!("Failed to create lock {}", path.display()))
+                }
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            tracing::warn!("Failed to release lock {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Replace `dest` with a copy of `src` without exposing a partial file
+pub fn copy_atomic(src: &Path, dest: &Path) -> Result<()> {
+    write_atomic(dest, |file| {
+        let mut input =
+            File::open(src).with_context(|| format!("Failed to open {}", src.display()))?;
+        std::io::copy(&mut input, file)
+            .with_context(|| format!("Failed to copy {}", src.display()))?;
+        Ok(())
+    })
+}
+
+/// Write `dest` through a temporary file in the same directory, then rename it into place
+pub fn write_atomic(dest: &Path, write: impl FnOnce(&mut File) -> Result<()>) -> Result<()> {
+    let temp = sibling(dest, &format!("tmp-{}", uuid::Uuid::new_v4()));
+    let result = File::create(&temp)
+        .with_context(|| format!("Failed to create {}", temp.display()))
+        .and_then(|mut file| {
+            write(&mut file)?;
+            file.sync_all()
+                .with_context(|| format!("Failed to flush {}", temp.display()))
+        })
+        .and_then(|_| {
+            std::fs::rename(&temp, dest)
+                .with_context(|| format!("Failed to move {} into place", dest.display()))
+        });
+
+    if result.is_err() {
+        std::fs::remove_file(&temp).ok();
+    }
+    result
+}
+
+/// `<path>.<suffix>` next to `path`
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn is_stale(lock: &Path) -> bool {
+    std::fs::metadata(lock)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age > STALE_LOCK_AGE)
+}
+
+fn hostname() -> String {
+    std::fs::read_to_string("/etc/hostname")
+        .map(|name| name.trim().to_string())
+        .unwrap_or_else(|_| "unknown".to_string())
+}
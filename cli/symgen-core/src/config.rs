@@ -0,0 +1,378 @@
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::distros::Distro;
+use crate::docker::Runtime;
+use crate::failures::FailurePattern;
+use crate::script::TEMPLATE_VARS;
+use crate::upload::S3Destination;
+
+/// User configuration, loaded from `~/.config/symgen/config.toml` by default
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Script customizations keyed by distro name (e.g. `ubuntu`, `rocky`)
+    pub distros: HashMap<String, ScriptOverrides>,
+    /// Append-only audit log recording every invocation
+    pub audit_log: Option<PathBuf>,
+    /// Record of past runs used by `symgen estimate`
+    pub history_file: Option<PathBuf>,
+    /// Cache of generated symbol files and run history, may be shared between machines
+    pub cache_dir: Option<PathBuf>,
+    /// Container runtime (`auto`, `docker` or `podman`)
+    pub runtime: Runtime,
+    /// Host commands run over every decompressed symbol file before it is compressed
+    pub post_process: Vec<String>,
+    /// Base URL of the Symgen web service used by `symgen cancel`
+    pub server: Option<String>,
+    /// Template variables substituted for `{{KEY}}` in script overrides; `--set` adds to them
+    pub vars: HashMap<String, String>,
+    /// PEM CA certificates installed into every container's trust store; `--ca-cert` adds to them
+    pub ca_certs: Vec<PathBuf>,
+    /// HTTP(S) proxy for the containers; the host's `HTTP_PROXY`/`HTTPS_PROXY`
+    /// are passed through when unset. `--proxy` overrides it
+    pub proxy: Option<String>,
+    /// Prefix for images named without a registry, e.g. `registry.corp.local/mirror`;
+    /// `--registry-mirror` overrides it
+    pub registry_mirror: Option<String>,
+    /// Docker `config.json`-format file with registry credentials (default:
+    /// `~/.docker/config.json`); `--registry-auth` overrides it
+    pub registry_auth: Option<PathBuf>,
+    /// Shell script run in every container before the first step; `--pre-script` overrides it
+    pub pre_script: Option<PathBuf>,
+    /// Shell script run in every generation container once the symbol file is written;
+    /// `--post-script` overrides it
+    pub post_script: Option<PathBuf>,
+    /// Directory holding the job database and job logs
+    pub state_dir: Option<PathBuf>,
+    /// Content-addressed store symbol files are moved into, leaving symlinks behind
+    pub store_dir: Option<PathBuf>,
+    /// Site-specific log patterns explaining known failures, checked before the built-in ones
+    pub failures: Vec<FailurePattern>,
+    /// dwarf2json release tag to download (e.g. `v0.9.0`), or `latest`; a
+    /// distro's `dwarf2json_url` still takes precedence
+    pub dwarf2json_version: Option<String>,
+    /// Copy every symbol file `generate` makes into Volatility3's `symbols/linux`,
+    /// as with `--install-vol3`; `--no-install` skips it for one run
+    pub install_vol3: bool,
+    /// `s3://bucket/prefix` every finished symbol file is uploaded to, with its
+    /// checksum; `--no-upload` skips it for one run
+    pub upload: Option<String>,
+    /// S3-compatible endpoint for uploads (MinIO, Ceph, ...); AWS when unset
+    pub upload_endpoint: Option<String>,
+    /// Region uploads are signed for; `AWS_REGION`, else us-east-1, when unset
+    pub upload_region: Option<String>,
+    /// Where `symgen upgrade-check` looks for new releases
+    pub upgrade_check: UpgradeEndpoints,
+    /// Directory of per-distro script templates (`ubuntu.sh`, `rocky.sh`, ...),
+    /// `~/.config/symgen/templates` unless configured
+    pub templates_dir: Option<PathBuf>,
+}
+
+/// Release feeds asked by `symgen upgrade-check`; point them at internal
+/// mirrors, or set one to an empty string to skip that check
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UpgradeEndpoints {
+    /// GitHub `releases/latest` API endpoint for symgen
+    pub symgen: String,
+    /// GitHub `releases/latest` API endpoint for dwarf2json
+    pub dwarf2json: String,
+    /// Ubuntu meta-release file listing LTS releases
+    pub ubuntu: String,
+    /// Release file of Debian's `stable` suite
+    pub debian: String,
+    /// Bodhi API listing current Fedora releases
+    pub fedora: String,
+}
+
+impl Default for UpgradeEndpoints {
+    fn default() -> Self {
+        Self {
+            symgen: "https://api.github.com/repos/nxb1t/Symgen/releases/latest".to_string(),
+            dwarf2json: crate::dwarf2json::LATEST_RELEASE_API.to_string(),
+            ubuntu: "https://changelogs.ubuntu.com/meta-release-lts".to_string(),
+            debian: "https://deb.debian.org/debian/dists/stable/Release".to_string(),
+            fedora: "https://bodhi.fedoraproject.org/releases/?state=current&rows_per_page=100"
+                .to_string(),
+        }
+    }
+}
+
+/// Site-specific tweaks applied to the steps of a generated script
+///
+/// Step names: `update`, `install_tools`, `setup_keyring` (Ubuntu only),
+/// `add_repos`, `install_debuginfo`, `find_vmlinux`, `setup_dwarf2json`,
+/// `find_system_map`, `generate`, `compress`, `finish`. With `--vmlinux`,
+/// `--vmlinux-url` or `--build-id`, `fetch_vmlinux` replaces `setup_keyring`
+/// through `install_debuginfo`; with `--delta-from`, `install_system_map`
+/// replaces `setup_keyring` through `find_vmlinux`.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScriptOverrides {
+    /// Commands to run before a step
+    pub before: HashMap<String, Vec<String>>,
+    /// Commands to run after a step
+    pub after: HashMap<String, Vec<String>>,
+    /// Shell snippets replacing a step entirely
+    pub replace: HashMap<String, String>,
+    /// Alternative dwarf2json download URL (must be HTTPS)
+    pub dwarf2json_url: Option<String>,
+    /// Expected SHA256 of the downloaded dwarf2json binary
+    pub dwarf2json_sha256: Option<String>,
+}
+
+impl Config {
+    /// Load the configuration file.
+    ///
+    /// An explicitly given path must exist; the default location is optional.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match Self::default_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Self::default()),
+            },
+        };
+
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        let config: Self = toml::from_str(&contents)
+            .with_context(|| format!("Invalid config file {}", path.display()))?;
+
+        for (name, overrides) in &config.distros {
+            if Distro::from_str(name).is_none() {
+                bail!(
+                    "Unknown distribution '{}' in config file {}",
+                    name,
+                    path.display()
+                );
+            }
+            if let Some(url) = &overrides.dwarf2json_url {
+                if !url.starts_with("https://") {
+                    bail!(
+                        "dwarf2json_url for '{}' must use https:// (got {})",
+                        name,
+                        url
+                    );
+                }
+            }
+            if let Some(hash) = &overrides.dwarf2json_sha256 {
+                if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                    bail!(
+                        "dwarf2json_sha256 for '{}' is not a SHA256 hex digest",
+                        name
+                    );
+                }
+            }
+        }
+
+        if let Some(version) = &config.dwarf2json_version {
+            if version.is_empty() || version.contains('/') || version.contains("..") {
+                bail!(
+                    "dwarf2json_version must be a release tag or 'latest' (got '{}')",
+                    version
+                );
+            }
+        }
+
+        if let Some(upload) = &config.upload {
+            S3Destination::parse(upload)
+                .with_context(|| format!("Invalid upload in {}", path.display()))?;
+        }
+
+        for failure in &config.failures {
+            Regex::new(&failure.pattern).with_context(|| {
+                format!(
+                    "Invalid failure pattern '{}' in {}",
+                    failure.pattern,
+                    path.display()
+                )
+            })?;
+        }
+
+        Ok(config)
+    }
+
+    /// Default configuration file location
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("symgen").join("config.toml"))
+    }
+
+    /// History file location: `history_file`, else inside a configured cache
+    /// directory, else `~/.local/share/symgen/history.jsonl`
+    pub fn history_path(&self) -> Option<PathBuf> {
+        self.history_file
+            .clone()
+            .or_else(|| self.cache_dir.as_ref().map(|dir| dir.join("history.jsonl")))
+            .or_else(|| dirs::data_local_dir().map(|dir| dir.join("symgen").join("history.jsonl")))
+    }
+
+    /// Cache directory, `~/.cache/symgen` unless configured
+    pub fn cache_path(&self) -> Option<PathBuf> {
+        self.cache_dir
+            .clone()
+            .or_else(|| dirs::cache_dir().map(|dir| dir.join("symgen")))
+    }
+
+    /// Job state directory, `~/.local/state/symgen` unless configured
+    pub fn state_path(&self) -> Option<PathBuf> {
+        self.state_dir.clone().or_else(|| {
+            dirs::state_dir()
+                .or_else(dirs::data_local_dir)
+                .map(|dir| dir.join("symgen"))
+        })
+    }
+
+    /// Contents of the configured CA certificates
+    pub fn ca_cert_pems(&self) -> Result<Vec<String>> {
+        self.ca_certs
+            .iter()
+            .map(|path| {
+                let pem = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read CA certificate {}", path.display()))?;
+                if !pem.contains("-----BEGIN CERTIFICATE-----") {
+                    bail!("{} is not a PEM certificate", path.display());
+                }
+                Ok(pem)
+            })
+            .collect()
+    }
+
+    /// Contents of the configured pre- and post-scripts
+    pub fn hook_scripts(&self) -> Result<(Option<String>, Option<String>)> {
+        let read = |path: &Option<PathBuf>| -> Result<Option<String>> {
+            path.as_ref()
+                .map(|path| {
+                    std::fs::read_to_string(path).with_context(|| {
+                        format!("Failed to read container script {}", path.display())
+                    })
+                })
+                .transpose()
+        };
+        Ok((read(&self.pre_script)?, read(&self.post_script)?))
+    }
+
+    /// Script overrides configured for a distro (empty when none are set),
+    /// with template variables substituted
+    pub fn script_overrides(&self, distro: Distro) -> ScriptOverrides {
+        self.distros
+            .iter()
+            .find(|(name, _)| Distro::from_str(name) == Some(distro))
+            .map(|(_, overrides)| overrides.expand(&self.vars))
+            .unwrap_or_default()
+    }
+
+    /// Template directory, `~/.config/symgen/templates` unless configured
+    pub fn templates_path(&self) -> Option<PathBuf> {
+        self.templates_dir
+            .clone()
+            .or_else(|| dirs::config_dir().map(|dir| dir.join("symgen").join("templates")))
+    }
+
+    /// The site's template for a distro (`<templates dir>/<distro>.sh`), if there is one,
+    /// with configured variables substituted.
+    ///
+    /// The template replaces the distro's built-in repository steps. `{{KERNEL}}`,
+    /// `{{DISTRO}}`, `{{VERSION}}`, `{{CODENAME}}` and `{{ARCH}}` are filled in per
+    /// run; any other placeholder has to be a configured variable.
+    pub fn script_template(&self, distro: Distro) -> Result<Option<String>> {
+        let Some(path) = self
+            .templates_path()
+            .map(|dir| dir.join(format!("{}.sh", distro.name())))
+        else {
+            return Ok(None);
+        };
+        if !path.exists() {
+            return Ok(None);
+        }
+        let template = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read script template {}", path.display()))?;
+        let template = expand_placeholders(&template, &self.vars);
+        for captures in placeholder_regex().captures_iter(&template) {
+            if !TEMPLATE_VARS.contains(&&captures[1]) {
+                bail!(
+                    "Script template {} uses undefined variable '{}' (set it with --set {}=... or under [vars])",
+                    path.display(),
+                    &captures[1],
+                    &captures[1]
+                );
+            }
+        }
+        Ok(Some(template))
+    }
+
+    /// Fail on `{{KEY}}` placeholders in script overrides that no variable defines,
+    /// rather than sending a half-filled command into the container
+    pub fn check_vars(&self) -> Result<()> {
+        let mut names = self.distros.keys().collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            for text in self.distros[name].templates() {
+                for captures in placeholder_regex().captures_iter(text) {
+                    if !self.vars.contains_key(&captures[1]) {
+                        bail!(
+                            "Script override for '{}' uses undefined variable '{}' (set it with --set {}=... or under [vars])",
+                            name,
+                            &captures[1],
+                            &captures[1]
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ScriptOverrides {
+    /// Every text that may contain template placeholders
+    fn templates(&self) -> impl Iterator<Item = &String> {
+        self.before
+            .values()
+            .chain(self.after.values())
+            .flatten()
+            .chain(self.replace.values())
+            .chain(self.dwarf2json_url.iter())
+    }
+
+    /// Copy with `{{KEY}}` placeholders replaced by their values; unknown ones are kept
+    fn expand(&self, vars: &HashMap<String, String>) -> Self {
+        let expand = |text: &String| expand_placeholders(text, vars);
+        let expand_all = |map: &HashMap<String, Vec<String>>| -> HashMap<String, Vec<String>> {
+            map.iter()
+                .map(|(step, commands)| (step.clone(), commands.iter().map(expand).collect()))
+                .collect()
+        };
+        Self {
+            before: expand_all(&self.before),
+            after: expand_all(&self.after),
+            replace: self
+                .replace
+                .iter()
+                .map(|(step, snippet)| (step.clone(), expand(snippet)))
+                .collect(),
+            dwarf2json_url: self.dwarf2json_url.as_ref().map(expand),
+            dwarf2json_sha256: self.dwarf2json_sha256.clone(),
+        }
+    }
+}
+
+/// `text` with `{{KEY}}` placeholders replaced by their values; unknown ones are kept
+pub(crate) fn expand_placeholders(text: &str, vars: &HashMap<String, String>) -> String {
+    placeholder_regex()
+        .replace_all(text, |captures: &regex::Captures| {
+            vars.get(&captures[1])
+                .cloned()
+                .unwrap_or_else(|| captures[0].to_string())
+        })
+        .into_owned()
+}
+
+/// `{{KEY}}`, optionally with spaces inside the braces
+fn placeholder_regex() -> Regex {
+    Regex::new(r"\{\{\s*([A-Za-z0-9_]+)\s*\}\}").unwrap()
+}
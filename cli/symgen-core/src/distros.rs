@@ -0,0 +1,778 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::acquisition;
+use crate::output::Output;
+
+/// Supported Linux distributions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Distro {
+    Ubuntu,
+    Debian,
+    Fedora,
+    CentOS,
+    RHEL,
+    Oracle,
+    Rocky,
+    Alma,
+    Kali,
+    /// Microsoft's WSL2 kernel, built from its published source and config
+    Wsl,
+}
+
+/// Ubuntu kernel flavors (the `aws` in `5.15.0-1050-aws`)
+pub const UBUNTU_FLAVORS: &[&str] = &[
+    "generic",
+    "generic-64k",
+    "generic-lpae",
+    "lowlatency",
+    "lowlatency-64k",
+    "aws",
+    "azure",
+    "azure-fde",
+    "gcp",
+    "gke",
+    "gkeop",
+    "oracle",
+    "ibm",
+    "kvm",
+    "oem",
+    "nvidia",
+    "intel-iotg",
+    "intel-iot-realtime",
+    "realtime",
+    "raspi",
+    "raspi-realtime",
+    "xilinx-zynqmp",
+    "nvidia-tegra",
+    "mtk",
+];
+
+/// Ubuntu flavors built from their own source packages (`linux-aws`,
+/// `linux-azure`, ...), whose ABI numbers start at 1000 and whose updates are
+/// only published to the `-updates` and `-security` pockets
+pub fn ubuntu_cloud_flavor(kernel: &str) -> Option<&'static str> {
+    let re = regex::Regex::new(r"^\d+\.\d+\.\d+-(\d+)-(.+)$").ok()?;
+    let cap = re.captures(kernel)?;
+    let abi: u32 = cap[1].parse().ok()?;
+    UBUNTU_FLAVORS
+        .iter()
+        .copied()
+        .find(|flavor| *flavor == &cap[2])
+        .filter(|_| abi >= 1000)
+}
+
+/// Release of a WSL2 kernel (`5.15.153.1` for `5.15.153.1-microsoft-standard-WSL2`);
+/// a trailing `+` marks a kernel built from a modified tree
+pub fn wsl_release(kernel: &str) -> Option<String> {
+    regex::Regex::new(r"^(\d+\.\d+\.\d+(?:\.\d+)?)-microsoft-standard(?:-WSL2)?\+?$")
+        .ok()?
+        .captures(kernel)
+        .map(|cap| cap[1].to_string())
+}
+
+/// Releases (or release series) of a derivative and the base release whose kernels they run
+type DerivativeReleases = &'static [(&'static str, Distro, &'static str)];
+
+/// Distributions running an Ubuntu or Debian release's kernels unchanged, by
+/// the names accepted for them. LMDE reports itself as `linuxmint` in os-release.
+const DERIVATIVES: &[(&[&str], DerivativeReleases)] = &[
+    (
+        &["mint", "linuxmint"],
+        &[
+            ("20", Distro::Ubuntu, "20.04"),
+            ("21", Distro::Ubuntu, "22.04"),
+            ("22", Distro::Ubuntu, "24.04"),
+            ("5", Distro::Debian, "11"),
+            ("6", Distro::Debian, "12"),
+        ],
+    ),
+    (
+        &["lmde"],
+        &[("5", Distro::Debian, "11"), ("6", Distro::Debian, "12")],
+    ),
+    (
+        &[
+            "pop", "pop-os", "pop_os", "popos", "neon", "kde-neon", "kdeneon",
+        ],
+        &[
+            ("20.04", Distro::Ubuntu, "20.04"),
+            ("22.04", Distro::Ubuntu, "22.04"),
+            ("24.04", Distro::Ubuntu, "24.04"),
+        ],
+    ),
+];
+
+/// Base release of a derivative's release (`mint` `21.3` gives Ubuntu `22.04`)
+pub fn resolve_derivative(name: &str, version: &str) -> Option<(Distro, String)> {
+    let name = name.to_lowercase();
+    let (_, releases) = DERIVATIVES
+        .iter()
+        .find(|(names, _)| names.contains(&name.as_str()))?;
+    releases
+        .iter()
+        .find(|(release, _, _)| {
+            version == *release || version.starts_with(&format!("{}.", release))
+        })
+        .map(|(_, base, base_version)| (*base, base_version.to_string()))
+}
+
+impl Distro {
+    /// Parse distro from string (case-insensitive)
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "ubuntu" => Some(Self::Ubuntu),
+            "debian" => Some(Self::Debian),
+            "fedora" => Some(Self::Fedora),
+            "centos" => Some(Self::CentOS),
+            "rhel" | "redhat" => Some(Self::RHEL),
+            "oracle" | "oraclelinux" | "ol" => Some(Self::Oracle),
+            "rocky" | "rockylinux" => Some(Self::Rocky),
+            "alma" | "almalinux" => Some(Self::Alma),
+            "kali" | "kali-linux" => Some(Self::Kali),
+            "wsl" | "wsl2" => Some(Self::Wsl),
+            _ => None,
+        }
+    }
+
+    /// Canonical name accepted by `-d/--distro`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ubuntu => "ubuntu",
+            Self::Debian => "debian",
+            Self::Fedora => "fedora",
+            Self::CentOS => "centos",
+            Self::RHEL => "rhel",
+            Self::Oracle => "oracle",
+            Self::Rocky => "rocky",
+            Self::Alma => "alma",
+            Self::Kali => "kali",
+            Self::Wsl => "wsl",
+        }
+    }
+
+    /// Get the display name for this distro
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Ubuntu => "Ubuntu",
+            Self::Debian => "Debian",
+            Self::Fedora => "Fedora",
+            Self::CentOS => "CentOS",
+            Self::RHEL => "RHEL",
+            Self::Oracle => "Oracle Linux",
+            Self::Rocky => "Rocky Linux",
+            Self::Alma => "AlmaLinux",
+            Self::Kali => "Kali",
+            Self::Wsl => "WSL2",
+        }
+    }
+
+    /// Whether images and debuginfo repositories exist for an architecture
+    pub fn supports_arch(&self, arch: Arch) -> bool {
+        match self {
+            // Neither Oracle Linux, Kali nor WSL2 ships POWER or Z builds
+            Self::Oracle | Self::Kali | Self::Wsl => matches!(arch, Arch::Amd64 | Arch::Arm64),
+            // Embedded targets are only handled for Ubuntu's ports archive
+            _ if matches!(arch, Arch::Riscv64 | Arch::Armhf) => *self == Self::Ubuntu,
+            _ => true,
+        }
+    }
+
+    /// Whether packages come from apt repositories (Ubuntu, Debian and Kali,
+    /// and the Ubuntu container WSL2 kernels are built in)
+    pub fn uses_apt(&self) -> bool {
+        matches!(self, Self::Ubuntu | Self::Debian | Self::Kali | Self::Wsl)
+    }
+
+    /// Whether a kernel release lacks the flavor suffix its packages are named with
+    /// (`5.15.0-91` instead of `5.15.0-91-generic`)
+    pub fn lacks_flavor(&self, kernel: &str) -> bool {
+        matches!(self, Self::Ubuntu | Self::Debian)
+            && regex::Regex::new(r"^\d+\.\d+\.\d+-\d+$")
+                .map(|re| re.is_match(kernel))
+                .unwrap_or(false)
+    }
+
+    /// Flavor picked when a flavorless kernel is published with several
+    pub fn default_flavor(&self, arch: Arch) -> &'static str {
+        match self {
+            Self::Debian | Self::Kali => arch.debian_flavor(),
+            _ => "generic",
+        }
+    }
+
+    /// Get all supported distros
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::Ubuntu,
+            Self::Debian,
+            Self::Fedora,
+            Self::CentOS,
+            Self::RHEL,
+            Self::Oracle,
+            Self::Rocky,
+            Self::Alma,
+            Self::Kali,
+            Self::Wsl,
+        ]
+    }
+}
+
+/// CPU architecture of the kernel being analyzed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Arch {
+    #[default]
+    #[value(alias = "x86_64")]
+    Amd64,
+    #[value(alias = "aarch64")]
+    Arm64,
+    #[value(alias = "ppc64el", alias = "powerpc64le")]
+    Ppc64le,
+    S390x,
+    Riscv64,
+    #[value(alias = "armv7", alias = "armv7l")]
+    Armhf,
+}
+
+impl Arch {
+    /// Canonical name (as used in `--arch`)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Amd64 => "amd64",
+            Self::Arm64 => "arm64",
+            Self::Ppc64le => "ppc64le",
+            Self::S390x => "s390x",
+            Self::Riscv64 => "riscv64",
+            Self::Armhf => "armhf",
+        }
+    }
+
+    /// Docker image platform
+    pub fn platform(&self) -> &'static str {
+        match self {
+            Self::Amd64 => "linux/amd64",
+            Self::Arm64 => "linux/arm64",
+            Self::Ppc64le => "linux/ppc64le",
+            Self::S390x => "linux/s390x",
+            Self::Riscv64 => "linux/riscv64",
+            Self::Armhf => "linux/arm/v7",
+        }
+    }
+
+    /// Architecture suffix of RPM package and kernel names
+    pub fn rpm_arch(&self) -> &'static str {
+        match self {
+            Self::Amd64 => "x86_64",
+            Self::Arm64 => "aarch64",
+            Self::Ppc64le => "ppc64le",
+            Self::S390x => "s390x",
+            Self::Riscv64 => "riscv64",
+            Self::Armhf => "armv7hl",
+        }
+    }
+
+    /// Debian/Ubuntu package architecture
+    pub fn deb_arch(&self) -> &'static str {
+        match self {
+            Self::Amd64 => "amd64",
+            Self::Arm64 => "arm64",
+            Self::Ppc64le => "ppc64el",
+            Self::S390x => "s390x",
+            Self::Riscv64 => "riscv64",
+            Self::Armhf => "armhf",
+        }
+    }
+
+    /// Debian kernel flavour (e.g. the `amd64` in `6.1.0-18-amd64`)
+    pub fn debian_flavor(&self) -> &'static str {
+        match self {
+            Self::Amd64 => "amd64",
+            Self::Arm64 => "arm64",
+            Self::Ppc64le => "powerpc64le",
+            Self::S390x => "s390x",
+            Self::Riscv64 => "riscv64",
+            Self::Armhf => "armmp",
+        }
+    }
+
+    /// Architecture name used by upstream Go toolchain downloads
+    pub fn go_arch(&self) -> &'static str {
+        match self {
+            Self::Amd64 => "amd64",
+            Self::Arm64 => "arm64",
+            Self::Ppc64le => "ppc64le",
+            Self::S390x => "s390x",
+            Self::Riscv64 => "riscv64",
+            Self::Armhf => "armv6l",
+        }
+    }
+
+    /// Whether dwarf2json publishes a prebuilt binary for this architecture
+    pub fn has_dwarf2json_release(&self) -> bool {
+        matches!(self, Self::Amd64)
+    }
+
+    /// Whether a kernel release string already names this architecture
+    pub fn is_named_in(&self, kernel: &str) -> bool {
+        kernel.ends_with(self.rpm_arch()) || kernel.ends_with(self.debian_flavor())
+    }
+
+    /// Architecture named by an RPM kernel's suffix or a Debian kernel's flavor
+    /// (`.aarch64`, `-arm64`); Ubuntu kernel names do not carry one
+    pub fn from_kernel(kernel: &str) -> Option<Self> {
+        let (base, _) = rpm_kernel_variant(kernel);
+        Self::value_variants()
+            .iter()
+            .copied()
+            .find(|arch| arch.is_named_in(base))
+    }
+
+    /// GNU target triple prefix of cross compilers (`aarch64` in `aarch64-linux-gnu-gcc`)
+    pub fn gnu_target(&self) -> &'static str {
+        match self {
+            Self::Amd64 => "x86_64-linux-gnu",
+            Self::Arm64 => "aarch64-linux-gnu",
+            Self::Ppc64le => "powerpc64le-linux-gnu",
+            Self::S390x => "s390x-linux-gnu",
+            Self::Riscv64 => "riscv64-linux-gnu",
+            Self::Armhf => "arm-linux-gnueabihf",
+        }
+    }
+
+    /// Architecture of the kernel a banner describes: from the release string,
+    /// else from the compiler's target triple (`aarch64-linux-gnu-gcc-12`),
+    /// else from Ubuntu's build host name (`buildd@bos02-arm64-077`)
+    pub fn detect(banner: &str) -> Option<Self> {
+        let caps = regex::Regex::new(r"Linux version (\S+) \(([^)]*)\)")
+            .ok()?
+            .captures(banner)?;
+        Self::from_kernel(&caps[1])
+            .or_else(|| {
+                Self::value_variants()
+                    .iter()
+                    .copied()
+                    .find(|arch| banner.contains(arch.gnu_target()))
+            })
+            .or_else(|| {
+                Self::value_variants()
+                    .iter()
+                    .copied()
+                    .find(|arch| caps[2].contains(&format!("-{}-", arch.deb_arch())))
+            })
+    }
+}
+
+/// Split an RPM kernel release into its base and `+variant` suffix
+/// (`6.5.6-300.fc39.x86_64+debug` gives `6.5.6-300.fc39.x86_64` and `debug`)
+pub fn rpm_kernel_variant(kernel: &str) -> (&str, Option<&str>) {
+    match kernel.split_once('+') {
+        Some((base, variant)) => (base, Some(variant)),
+        None => (kernel, None),
+    }
+}
+
+/// Split the upload out of an Ubuntu kernel written like its package version
+/// (`5.15.0-91.101-generic` gives `5.15.0-91-generic` and `101`)
+pub fn split_ubuntu_upload(kernel: &str) -> (String, Option<String>) {
+    let re = regex::Regex::new(r"^(\d+\.\d+\.\d+-\d+)\.(\d+(?:~[\d.]+)?)(-.+)?$")
+        .expect("valid upload regex");
+    match re.captures(kernel) {
+        Some(cap) => (
+            format!(
+                "{}{}",
+                &cap[1],
+                cap.get(3).map_or("", |flavor| flavor.as_str())
+            ),
+            Some(cap[2].to_string()),
+        ),
+        None => (kernel.to_string(), None),
+    }
+}
+
+/// apt version of an Ubuntu kernel's packages for one upload
+/// (`5.15.0-91-generic` and `101` give `5.15.0-91.101`)
+pub fn ubuntu_package_version(kernel: &str, upload: &str) -> Option<String> {
+    let re = regex::Regex::new(r"^\d+\.\d+\.\d+-\d+").ok()?;
+    re.find(kernel)
+        .map(|abi| format!("{}.{}", abi.as_str(), upload))
+}
+
+/// Reject RPM kernel names that cannot match the selected architecture's packages
+pub fn check_rpm_kernel(kernel: &str, arch: Arch) -> Result<(), String> {
+    const RPM_ARCHES: &[&str] = &[
+        "x86_64", "aarch64", "ppc64le", "s390x", "i686", "armv7hl", "riscv64",
+    ];
+
+    let (base, variant) = rpm_kernel_variant(kernel);
+    if let Some(kernel_arch) = RPM_ARCHES
+        .iter()
+        .find(|a| base.ends_with(&format!(".{}", a)))
+    {
+        if *kernel_arch != arch.rpm_arch() {
+            return Err(format!(
+                "Kernel {} is built for {}, but the target architecture is {} (see --arch)",
+                kernel,
+                kernel_arch,
+                arch.name()
+            ));
+        }
+    }
+    match variant {
+        Some(v) if v.starts_with("PAE") => Err(format!(
+            "Kernel {} is a 32-bit PAE build; i686 kernels are not supported",
+            kernel
+        )),
+        Some(v) if (v.starts_with("16k") || v.starts_with("64k")) && arch != Arch::Arm64 => {
+            Err(format!(
+                "Kernel {} is an aarch64 {} page-size build (use --arch arm64)",
+                kernel,
+                &v[..3]
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Distro version information
+#[derive(Debug, Clone, Serialize)]
+pub struct DistroVersion {
+    pub distro: Distro,
+    pub version: String,
+    pub codename: Option<String>,
+    pub docker_image: String,
+}
+
+/// What symgen can do for a distro release
+#[derive(Debug, Serialize)]
+pub struct Capabilities {
+    pub architectures: Vec<Arch>,
+    /// Kernel flavor suffixes (Ubuntu, Debian) or kernel families (Oracle) handled;
+    /// empty when kernel names carry no flavor
+    pub kernel_flavors: Vec<String>,
+    pub acquisition_methods: Vec<&'static str>,
+    /// Whether the debug repositories need a subscription or other credentials
+    pub requires_credentials: bool,
+}
+
+impl DistroVersion {
+    /// Capabilities of this release
+    pub fn capabilities(&self) -> Capabilities {
+        let architectures: Vec<Arch> = Arch::value_variants()
+            .iter()
+            .copied()
+            .filter(|arch| self.distro.supports_arch(*arch))
+            .collect();
+
+        let kernel_flavors = match self.distro {
+            Distro::Ubuntu => UBUNTU_FLAVORS.iter().map(|f| f.to_string()).collect(),
+            Distro::Kali => architectures
+                .iter()
+                .map(|a| a.debian_flavor().to_string())
+                .collect(),
+            Distro::Debian => {
+                let mut flavors: Vec<String> = architectures
+                    .iter()
+                    .map(|a| a.debian_flavor().to_string())
+                    .collect();
+                flavors.extend(["cloud-amd64", "rt-amd64"].map(String::from));
+                flavors
+            }
+            Distro::Oracle => vec!["uek".to_string(), "rhck".to_string()],
+            _ => Vec::new(),
+        };
+
+        Capabilities {
+            architectures,
+            kernel_flavors,
+            acquisition_methods: acquisition::METHODS.to_vec(),
+            // UBI images only reach the UBI subset; kernel debuginfo needs a subscription
+            requires_credentials: self.distro == Distro::RHEL,
+        }
+    }
+}
+
+/// Get supported versions for a distro
+pub fn get_versions(distro: Distro) -> Vec<DistroVersion> {
+    match distro {
+        Distro::Ubuntu => vec![
+            DistroVersion {
+                distro,
+                version: "20.04".to_string(),
+                codename: Some("focal".to_string()),
+                docker_image: "ubuntu:20.04".to_string(),
+            },
+            DistroVersion {
+                distro,
+                version: "22.04".to_string(),
+                codename: Some("jammy".to_string()),
+                docker_image: "ubuntu:22.04".to_string(),
+            },
+            DistroVersion {
+                distro,
+                version: "24.04".to_string(),
+                codename: Some("noble".to_string()),
+                docker_image: "ubuntu:24.04".to_string(),
+            },
+        ],
+        Distro::Debian => vec![
+            DistroVersion {
+                distro,
+                version: "10".to_string(),
+                codename: Some("buster".to_string()),
+                docker_image: "debian:10".to_string(),
+            },
+            DistroVersion {
+                distro,
+                version: "11".to_string(),
+                codename: Some("bullseye".to_string()),
+                docker_image: "debian:11".to_string(),
+            },
+            DistroVersion {
+                distro,
+                version: "12".to_string(),
+                codename: Some("bookworm".to_string()),
+                docker_image: "debian:12".to_string(),
+            },
+        ],
+        Distro::Fedora => vec![
+            DistroVersion {
+                distro,
+                version: "38".to_string(),
+                codename: None,
+                docker_image: "fedora:38".to_string(),
+            },
+            DistroVersion {
+                distro,
+                version: "39".to_string(),
+                codename: None,
+                docker_image: "fedora:39".to_string(),
+            },
+            DistroVersion {
+                distro,
+                version: "40".to_string(),
+                codename: None,
+                docker_image: "fedora:40".to_string(),
+            },
+        ],
+        Distro::CentOS => vec![
+            DistroVersion {
+                distro,
+                version: "7".to_string(),
+                codename: None,
+                docker_image: "centos:7".to_string(),
+            },
+            DistroVersion {
+                distro,
+                version: "8".to_string(),
+                codename: Some("Stream 8".to_string()),
+                docker_image: "quay.io/centos/centos:stream8".to_string(),
+            },
+            DistroVersion {
+                distro,
+                version: "9".to_string(),
+                codename: Some("Stream 9".to_string()),
+                docker_image: "quay.io/centos/centos:stream9".to_string(),
+            },
+        ],
+        Distro::RHEL => vec![
+            DistroVersion {
+                distro,
+                version: "8".to_string(),
+                codename: None,
+                docker_image: "redhat/ubi8:latest".to_string(),
+            },
+            DistroVersion {
+                distro,
+                version: "9".to_string(),
+                codename: None,
+                docker_image: "redhat/ubi9:latest".to_string(),
+            },
+        ],
+        Distro::Oracle => vec![
+            DistroVersion {
+                distro,
+                version: "8".to_string(),
+                codename: None,
+                docker_image: "oraclelinux:8".to_string(),
+            },
+            DistroVersion {
+                distro,
+                version: "9".to_string(),
+                codename: None,
+                docker_image: "oraclelinux:9".to_string(),
+            },
+        ],
+        Distro::Rocky => vec![
+            DistroVersion {
+                distro,
+                version: "8".to_string(),
+                codename: None,
+                docker_image: "rockylinux:8".to_string(),
+            },
+            DistroVersion {
+                distro,
+                version: "9".to_string(),
+                codename: None,
+                docker_image: "rockylinux:9".to_string(),
+            },
+        ],
+        Distro::Alma => vec![
+            DistroVersion {
+                distro,
+                version: "8".to_string(),
+                codename: None,
+                docker_image: "almalinux:8".to_string(),
+            },
+            DistroVersion {
+                distro,
+                version: "9".to_string(),
+                codename: None,
+                docker_image: "almalinux:9".to_string(),
+            },
+        ],
+        // Rolling release; kali-last-snapshot covers the latest quarterly image
+        Distro::Kali => vec![DistroVersion {
+            distro,
+            version: "rolling".to_string(),
+            codename: Some("kali-rolling".to_string()),
+            docker_image: "kalilinux/kali-rolling".to_string(),
+        }],
+        // Microsoft publishes no debug kernels; the release's source is built
+        // with its own config in an Ubuntu container
+        Distro::Wsl => vec![DistroVersion {
+            distro,
+            version: "2".to_string(),
+            codename: None,
+            docker_image: "ubuntu:22.04".to_string(),
+        }],
+    }
+}
+
+/// Find distro version by version string
+pub fn find_version(distro: Distro, version: &str) -> Option<DistroVersion> {
+    get_versions(distro)
+        .into_iter()
+        .find(|v| v.version == version)
+}
+
+/// Other releases worth retrying when a kernel is missing from `version`'s repos.
+///
+/// Only Ubuntu and Debian qualify: HWE and backport kernels show up in a
+/// different release than the banner suggests, while the RPM-based distros
+/// encode the release in the kernel name. Nearest releases come first, older
+/// before newer since kernels are backported rather than forward-ported.
+pub fn fallback_versions(version: &DistroVersion) -> Vec<DistroVersion> {
+    if !matches!(version.distro, Distro::Ubuntu | Distro::Debian) {
+        return Vec::new();
+    }
+
+    let versions = get_versions(version.distro);
+    let Some(index) = versions.iter().position(|v| v.version == version.version) else {
+        return Vec::new();
+    };
+
+    let mut others: Vec<(usize, DistroVersion)> = versions
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| *i != index)
+        .collect();
+    others.sort_by_key(|(i, _)| (i.abs_diff(index), *i > index));
+    others.into_iter().map(|(_, v)| v).collect()
+}
+
+/// List all supported distros and versions
+pub fn list_distros(output: &Output) {
+    if output.is_json() {
+        #[derive(Serialize)]
+        struct DistroList {
+            distros: Vec<DistroInfo>,
+        }
+
+        #[derive(Serialize)]
+        struct DistroInfo {
+            name: String,
+            versions: Vec<VersionInfo>,
+        }
+
+        #[derive(Serialize)]
+        struct VersionInfo {
+            version: String,
+            codename: Option<String>,
+            docker_image: String,
+            capabilities: Capabilities,
+        }
+
+        let distros: Vec<DistroInfo> = Distro::all()
+            .iter()
+            .map(|d| DistroInfo {
+                name: d.display_name().to_string(),
+                versions: get_versions(*d)
+                    .into_iter()
+                    .map(|v| VersionInfo {
+                        capabilities: v.capabilities(),
+                        version: v.version,
+                        codename: v.codename,
+                        docker_image: v.docker_image,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        let list = DistroList { distros };
+        println!("{}", serde_json::to_string_pretty(&list).unwrap());
+    } else {
+        println!("\nSupported Distributions and Versions:\n");
+
+        for distro in Distro::all() {
+            println!("  {}:", distro.display_name());
+            for version in get_versions(*distro) {
+                if let Some(codename) = &version.codename {
+                    println!("    - {} ({})", version.version, codename);
+                } else {
+                    println!("    - {}", version.version);
+                }
+            }
+            println!();
+        }
+
+        println!("Example usage:");
+        println!("  symgen generate -k 5.15.0-91-generic -d ubuntu -r 22.04");
+        println!("  symgen generate -k 6.1.0-18-amd64 -d debian -r 12");
+        println!("  symgen generate -k 6.5.6-300.fc39.x86_64 -d fedora -r 39");
+        println!("  symgen generate -k 6.6.9-amd64 -d kali -r rolling");
+        println!("  symgen generate -k 5.15.153.1-microsoft-standard-WSL2 -d wsl -r 2");
+        println!();
+        println!(
+            "Derivatives are mapped to their base release, e.g. -d mint -r 21.3 (Ubuntu 22.04),"
+        );
+        println!("-d lmde -r 6 (Debian 12), -d pop -r 22.04, -d neon -r 22.04.");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions(distro: Distro, version: &str) -> Vec<String> {
+        fallback_versions(&find_version(distro, version).unwrap())
+            .into_iter()
+            .map(|v| v.version)
+            .collect()
+    }
+
+    #[test]
+    fn fallback_prefers_nearest_older_release() {
+        assert_eq!(versions(Distro::Ubuntu, "22.04"), ["20.04", "24.04"]);
+        assert_eq!(versions(Distro::Ubuntu, "24.04"), ["22.04", "20.04"]);
+        assert_eq!(versions(Distro::Debian, "10"), ["11", "12"]);
+    }
+
+    #[test]
+    fn no_fallback_for_rpm_distros() {
+        assert!(versions(Distro::Fedora, "39").is_empty());
+        assert!(versions(Distro::Rocky, "9").is_empty());
+    }
+}
@@ -0,0 +1,938 @@
+use anyhow::{Context, Result};
+use bollard::container::{
+    Config, CreateContainerOptions, DownloadFromContainerOptions, ListContainersOptions,
+    LogsOptions, RemoveContainerOptions, StatsOptions, UploadToContainerOptions,
+    WaitContainerOptions,
+};
+use bollard::image::{CommitContainerOptions, CreateImageOptions};
+use bollard::models::{HostConfig, Mount, MountTypeEnum};
+use bollard::{Docker, API_DEFAULT_VERSION};
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::{HashMap, VecDeque};
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+use crate::registry::RegistrySettings;
+use crate::steps::{parse_marker, Marker};
+
+/// Directory inside the container the generation script is uploaded to
+pub(crate) const SCRIPT_DIR: &str = "/tmp";
+/// File name of the uploaded generation script
+pub(crate) const SCRIPT_NAME: &str = "symgen-generate.sh";
+/// Working directory the script writes its output to
+pub const WORK_DIR: &str = "/work";
+/// Label attached to every container symgen creates
+pub const MANAGED_LABEL: &str = "symgen.managed";
+/// Label grouping the containers of one generation run (fallbacks, flavor probe)
+pub const JOB_LABEL: &str = "symgen.job";
+/// Label naming the kernel a container works on
+pub const KERNEL_LABEL: &str = "symgen.kernel";
+/// Label naming the distro release a container runs
+pub const DISTRO_LABEL: &str = "symgen.distro";
+/// Label carrying the run's case tag, when one was given
+pub const TAG_LABEL: &str = "symgen.tag";
+/// Label on images made by `symgen build-images`, naming the release baked in
+pub const PREBAKED_LABEL: &str = "symgen.prebaked";
+/// Label on prebaked images naming where their dwarf2json came from
+pub const DWARF2JSON_LABEL: &str = "symgen.dwarf2json";
+/// Directory the script's failure trap fills with diagnostics
+pub const DIAGNOSTICS_DIR: &str = "/tmp/symgen-failure";
+/// Log lines kept for the failure bundle
+const FAILURE_LOG_LINES: usize = 200;
+/// Consecutive attempts at reopening a dropped log stream before giving up on it
+const LOG_RECONNECT_ATTEMPTS: u32 = 5;
+/// Longest wait between log stream reconnection attempts, in seconds
+const LOG_RECONNECT_MAX_DELAY: u64 = 16;
+/// Docker daemon socket
+const DOCKER_SOCKET: &str = "/var/run/docker.sock";
+/// Rootful Podman API socket
+const PODMAN_ROOT_SOCKET: &str = "/run/podman/podman.sock";
+/// Seconds to wait for the container API before giving up on a request
+const API_TIMEOUT: u64 = 120;
+
+/// Container engine serving the API symgen talks to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Runtime {
+    /// `DOCKER_HOST` if set, else the Docker socket, else a Podman socket
+    #[default]
+    Auto,
+    /// The Docker daemon (`DOCKER_HOST` or the local socket)
+    Docker,
+    /// Podman's Docker-compatible API socket, rootless first
+    Podman,
+}
+
+impl Runtime {
+    /// Connect to the runtime's API endpoint
+    fn connect(self) -> Result<(Docker, &'static str)> {
+        let podman = podman_socket();
+        let use_podman = match self {
+            Self::Docker => false,
+            Self::Podman => true,
+            Self::Auto => {
+                std::env::var_os("DOCKER_HOST").is_none()
+                    && !Path::new(DOCKER_SOCKET).exists()
+                    && podman.is_some()
+            }
+        };
+
+        if !use_podman {
+            let client = Docker::connect_with_local_defaults()
+                .context("Failed to connect to Docker. Is Docker running?")?;
+            return Ok((client, "Docker"));
+        }
+
+        let socket = podman.context(
+            "No Podman socket found. Start it with `systemctl --user start podman.socket`",
+        )?;
+        let client = Docker::connect_with_socket(
+            socket.to_str().context("Invalid Podman socket path")?,
+            API_TIMEOUT,
+            API_DEFAULT_VERSION,
+        )
+        .with_context(|| format!("Failed to connect to Podman at {}", socket.display()))?;
+        Ok((client, "Podman"))
+    }
+}
+
+/// Podman API socket: the user's rootless one, else the system one
+fn podman_socket() -> Option<PathBuf> {
+    let rootless = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(|dir| PathBuf::from(dir).join("podman").join("podman.sock"));
+    rootless
+        .into_iter()
+        .chain(std::iter::once(PathBuf::from(PODMAN_ROOT_SOCKET)))
+        .find(|socket| socket.exists())
+}
+/// Longest log line passed on; the rest of the line is dropped (progress bars, RPM dumps)
+const MAX_LOG_LINE: usize = 16 * 1024;
+
+/// How generated files get from the container to the output directory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputTransfer {
+    /// Bind-mount the output directory into the container
+    #[default]
+    Bind,
+    /// Copy results out through the Docker archive API after the run
+    /// (works with remote daemons and avoids bind-mount permission/SELinux issues)
+    Copy,
+}
+
+/// When to remove the generation container after it exits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum RemovePolicy {
+    /// Always remove the container
+    #[default]
+    Always,
+    /// Keep the container when the script fails
+    OnSuccess,
+    /// Never remove the container
+    Never,
+}
+
+/// Per-run container settings
+#[derive(Debug)]
+pub struct RunOptions {
+    /// Image platform, e.g. `linux/amd64`
+    pub platform: &'static str,
+    pub transfer: OutputTransfer,
+    pub remove: RemovePolicy,
+    /// File the raw container output is appended to
+    pub log_file: Option<PathBuf>,
+    /// `symgen.*` labels describing the run, added to the managed label
+    pub labels: HashMap<String, String>,
+    /// Host files bind-mounted read-only, as (host path, container path)
+    pub inputs: Vec<(PathBuf, String)>,
+    /// Kill the container once it has run this long
+    pub timeout: Option<Duration>,
+    /// Environment variables (`KEY=VALUE`) set in the container
+    pub env: Vec<String>,
+    /// Directory failure bundles are saved to when the output directory is a
+    /// scratch directory that goes away with the run
+    pub diagnostics_dir: Option<PathBuf>,
+}
+
+/// Result of a finished container run
+#[derive(Debug)]
+pub struct RunOutcome {
+    pub exit_code: i64,
+    pub container_name: String,
+    pub container_id: String,
+    /// Whether the container was killed for running past the timeout
+    pub timed_out: bool,
+    /// Whether the container was left in place after the run
+    pub kept: bool,
+    /// Error raised while removing the container, if any
+    pub cleanup_error: Option<String>,
+    /// Diagnostics collected after a failed run
+    pub failure_dir: Option<PathBuf>,
+}
+
+/// A symgen-managed container found on the daemon
+#[derive(Debug)]
+pub struct ManagedContainer {
+    pub id: String,
+    pub name: String,
+    pub state: String,
+    /// Unix timestamp of container creation
+    pub created: i64,
+    pub labels: HashMap<String, String>,
+}
+
+/// Point-in-time resource usage of a container
+#[derive(Debug)]
+pub struct ContainerUsage {
+    /// Share of one CPU, in percent (200.0 means two full CPUs)
+    pub cpu_percent: f64,
+    pub memory_bytes: u64,
+    pub memory_limit: u64,
+}
+
+/// Docker client wrapper for symbol generation
+pub struct DockerClient {
+    client: Docker,
+    /// `docker` or `podman`, for commands suggested to the user
+    command: &'static str,
+    /// Mirror and credentials images are pulled with
+    registry: RegistrySettings,
+}
+
+impl DockerClient {
+    /// Connect to the container runtime's Docker-compatible API
+    pub async fn new(runtime: Runtime) -> Result<Self> {
+        let (client, name) = runtime.connect()?;
+
+        // Verify connection
+        client
+            .ping()
+            .await
+            .with_context(|| format!("Failed to ping the {} API", name))?;
+        tracing::debug!("Connected to {}", name);
+
+        Ok(Self {
+            client,
+            command: if name == "Podman" { "podman" } else { "docker" },
+            registry: RegistrySettings::default(),
+        })
+    }
+
+    /// Pull and run images through a registry mirror, with stored credentials
+    pub fn with_registry(mut self, registry: RegistrySettings) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// CLI of the connected runtime (`docker` or `podman`)
+    pub fn command(&self) -> &'static str {
+        self.command
+    }
+
+    /// Pull a Docker image if not present
+    pub async fn pull_image(&self, image: &str, platform: &str) -> Result<()> {
+        // Check if image exists locally
+        if self.client.inspect_image(image).await.is_ok() {
+            return Ok(());
+        }
+        let image = &self.registry.image(image);
+        if self.client.inspect_image(image).await.is_ok() {
+            return Ok(());
+        }
+
+        let options = CreateImageOptions {
+            from_image: image.as_str(),
+            platform,
+            ..Default::default()
+        };
+        let credentials = self.registry.credentials(image)?;
+
+        let mut stream = self.client.create_image(Some(options), None, credentials);
+
+        while let Some(result) = stream.next().await {
+            result.with_context(|| format!("Failed to pull image {} for {}", image, platform))?;
+        }
+
+        Ok(())
+    }
+
+    /// Labels of a local image, or `None` when the image is not present
+    pub async fn image_labels(&self, image: &str) -> Option<HashMap<String, String>> {
+        let inspect = self.client.inspect_image(image).await.ok()?;
+        Some(
+            inspect
+                .config
+                .and_then(|config| config.labels)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Save a stopped container's filesystem as the local image `image` (`repo:tag`)
+    pub async fn commit(
+        &self,
+        container_id: &str,
+        image: &str,
+        labels: HashMap<String, String>,
+    ) -> Result<()> {
+        let (repo, tag) = image.rsplit_once(':').unwrap_or((image, "latest"));
+        let options = CommitContainerOptions {
+            container: container_id,
+            repo,
+            tag,
+            comment: "symgen build-images",
+            ..Default::default()
+        };
+        let config = Config {
+            cmd: Some(vec!["bash".to_string()]),
+            labels: Some(labels),
+            ..Default::default()
+        };
+        self.client
+            .commit_container(options, config)
+            .await
+            .with_context(|| format!("Failed to commit container {} as {}", container_id, image))?;
+        Ok(())
+    }
+
+    /// Run a container with the given script, passing each log line and the
+    /// time the container wrote it to `on_log`.
+    ///
+    /// The log file and failure bundle get every line prefixed with its
+    /// timestamp and the step it belongs to.
+    pub async fn run_container(
+        &self,
+        image: &str,
+        script: &str,
+        output_dir: &Path,
+        options: &RunOptions,
+        on_log: impl Fn(&str, Option<DateTime<Utc>>),
+    ) -> Result<RunOutcome> {
+        let container_name = format!("symgen-{}", uuid::Uuid::new_v4());
+
+        let mut mounts = Vec::new();
+        if options.transfer == OutputTransfer::Bind {
+            let output_dir_str = output_dir
+                .to_str()
+                .context("Invalid output directory path")?;
+            mounts.push(Mount {
+                target: Some(WORK_DIR.to_string()),
+                source: Some(output_dir_str.to_string()),
+                typ: Some(MountTypeEnum::BIND),
+                read_only: Some(false),
+                ..Default::default()
+            });
+        }
+        for (source, target) in &options.inputs {
+            let source = std::fs::canonicalize(source)
+                .with_context(|| format!("Input file not found: {}", source.display()))?;
+            mounts.push(Mount {
+                target: Some(target.clone()),
+                source: Some(
+                    source
+                        .to_str()
+                        .context("Invalid input file path")?
+                        .to_string(),
+                ),
+                typ: Some(MountTypeEnum::BIND),
+                read_only: Some(true),
+                ..Default::default()
+            });
+        }
+        let mounts = (!mounts.is_empty()).then_some(mounts);
+
+        // A local image of that name wins over the mirrored one, as when pulling
+        let image = match self.client.inspect_image(image).await {
+            Ok(_) => image.to_string(),
+            Err(_) => self.registry.image(image),
+        };
+
+        // Container configuration
+        let config = Config {
+            image: Some(image),
+            cmd: Some(vec![
+                "bash".to_string(),
+                format!("{}/{}", SCRIPT_DIR, SCRIPT_NAME),
+            ]),
+            working_dir: Some(WORK_DIR.to_string()),
+            env: (!options.env.is_empty()).then(|| options.env.clone()),
+            labels: Some({
+                let mut labels = options.labels.clone();
+                labels.insert(MANAGED_LABEL.to_string(), "true".to_string());
+                labels
+            }),
+            host_config: Some(HostConfig {
+                mounts,
+                memory: Some(8 * 1024 * 1024 * 1024), // 8GB
+                cpu_period: Some(100000),
+                cpu_quota: Some(200000), // 2 CPUs
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let platform = options.platform.to_string();
+        let create_options = CreateContainerOptions {
+            name: &container_name,
+            platform: Some(&platform),
+        };
+
+        // Create container
+        let container = self
+            .client
+            .create_container(Some(create_options), config)
+            .await
+            .context("Failed to create container")?;
+
+        // Stream the script into the container rather than sharing it through the host
+        let upload_options = UploadToContainerOptions {
+            path: SCRIPT_DIR,
+            ..Default::default()
+        };
+        let uploaded = match script_archive(script) {
+            Ok(archive) => self
+                .client
+                .upload_to_container(&container.id, Some(upload_options), archive.into())
+                .await
+                .context("Failed to upload script to container"),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = uploaded {
+            self.remove(&container.id, true).await.ok();
+            return Err(e);
+        }
+
+        // Start container
+        self.client
+            .start_container::<String>(&container.id, None)
+            .await
+            .context("Failed to start container")?;
+
+        // Stream logs
+        let log_options = |since: i64| LogsOptions::<String> {
+            follow: true,
+            stdout: true,
+            stderr: true,
+            timestamps: true,
+            since,
+            ..Default::default()
+        };
+
+        let mut log_stream = self.client.logs(&container.id, Some(log_options(0)));
+        // Time of the last line seen, and lines up to which a reopened stream repeats
+        let last_seen = Cell::new(None::<DateTime<Utc>>);
+        let resumed_after = Cell::new(None::<DateTime<Utc>>);
+        let mut log_tail = VecDeque::with_capacity(FAILURE_LOG_LINES);
+        // Streams until the container exits; dropped when the timeout cuts it short
+        let run = async {
+            let mut lines = LineSplitter::default();
+            let mut log_writer = match &options.log_file {
+                Some(path) => match OpenOptions::new().create(true).append(true).open(path) {
+                    Ok(file) => Some(BufWriter::new(file)),
+                    Err(e) => {
+                        tracing::warn!("Failed to open log file {}: {}", path.display(), e);
+                        None
+                    }
+                },
+                None => None,
+            };
+
+            let mut step = String::new();
+            let mut handle_line = |raw: &str| {
+                let (time, line) = split_timestamp(raw);
+                if let (Some(time), Some(cutoff)) = (time, resumed_after.get()) {
+                    if time <= cutoff {
+                        return;
+                    }
+                }
+                if time.is_some() {
+                    last_seen.set(time);
+                }
+                if let Some(Marker::Step(name)) = parse_marker(line) {
+                    step = name.to_string();
+                }
+                on_log(line, time);
+
+                let stamped = format!(
+                    "{} [{}] {}",
+                    time.map(|t| t.to_rfc3339_opts(SecondsFormat::Millis, true))
+                        .unwrap_or_else(|| "-".to_string()),
+                    if step.is_empty() { "-" } else { &step },
+                    line
+                );
+                if let Some(writer) = &mut log_writer {
+                    if let Err(e) = writeln!(writer, "{}", stamped) {
+                        tracing::warn!("Failed to write log file: {}", e);
+                        log_writer = None;
+                    }
+                }
+                if log_tail.len() == FAILURE_LOG_LINES {
+                    log_tail.pop_front();
+                }
+                log_tail.push_back(stamped);
+            };
+
+            // A daemon restart or a proxy timing out the idle connection drops the
+            // stream while the container keeps running; reopen it where it left off
+            let mut reconnects = 0;
+            loop {
+                let mut dropped = false;
+                while let Some(result) = log_stream.next().await {
+                    match result {
+                        Ok(output) => {
+                            reconnects = 0;
+                            lines.push(&output.into_bytes(), &mut handle_line);
+                        }
+                        Err(e) => {
+                            tracing::warn!("Log stream error: {}", e);
+                            dropped = true;
+                            break;
+                        }
+                    }
+                }
+                if !dropped && !self.is_running(&container.id).await {
+                    break;
+                }
+
+                reconnects += 1;
+                if reconnects > LOG_RECONNECT_ATTEMPTS {
+                    tracing::warn!(
+                        "Giving up on the log stream; waiting for the container to exit"
+                    );
+                    break;
+                }
+                let delay = (1u64 << (reconnects - 1)).min(LOG_RECONNECT_MAX_DELAY);
+                tracing::warn!("Log stream lost; reconnecting in {}s", delay);
+                tokio::time::sleep(Duration::from_secs(delay)).await;
+
+                // The partial line comes again in full; `since` has whole-second
+                // resolution, so lines already seen are skipped by timestamp
+                lines.discard();
+                resumed_after.set(last_seen.get());
+                let since = last_seen.get().map_or(0, |time| time.timestamp());
+                log_stream = self.client.logs(&container.id, Some(log_options(since)));
+            }
+            lines.finish(&mut handle_line);
+            if let Some(mut writer) = log_writer {
+                if let Err(e) = writer.flush() {
+                    tracing::warn!("Failed to write log file: {}", e);
+                }
+            }
+
+            // Wait for container to finish
+            let mut wait_stream = self
+                .client
+                .wait_container(&container.id, None::<WaitContainerOptions<String>>);
+
+            let exit_code = if let Some(result) = wait_stream.next().await {
+                result.context("Failed to wait for container")?.status_code
+            } else {
+                -1
+            };
+            Ok::<_, anyhow::Error>(exit_code)
+        };
+
+        let finished = match options.timeout {
+            Some(limit) => tokio::time::timeout(limit, run).await.ok(),
+            None => Some(run.await),
+        };
+        let timed_out = finished.is_none();
+        let exit_code = match finished {
+            Some(exit_code) => exit_code?,
+            None => {
+                tracing::warn!(
+                    "Container {} ran past the timeout; killing it",
+                    container_name
+                );
+                if let Err(e) = self
+                    .client
+                    .kill_container::<String>(&container.id, None)
+                    .await
+                {
+                    tracing::warn!("Failed to kill container: {}", e);
+                }
+                -1
+            }
+        };
+
+        // Copy results out before the container goes away
+        if options.transfer == OutputTransfer::Copy {
+            if let Err(e) = self.copy_out(&container.id, output_dir).await {
+                tracing::warn!("Failed to copy results from container: {:#}", e);
+            }
+        }
+
+        let failure_dir = if exit_code != 0 {
+            match self
+                .collect_diagnostics(
+                    &container.id,
+                    options.diagnostics_dir.as_deref().unwrap_or(output_dir),
+                    &log_tail,
+                )
+                .await
+            {
+                Ok(dir) => Some(dir),
+                Err(e) => {
+                    tracing::warn!("Failed to collect failure diagnostics: {:#}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // Remove container according to the cleanup policy
+        let remove = match options.remove {
+            RemovePolicy::Always => true,
+            RemovePolicy::OnSuccess => exit_code == 0,
+            RemovePolicy::Never => false,
+        };
+
+        let cleanup_error = if remove {
+            self.remove(&container.id, true)
+                .await
+                .err()
+                .map(|e| format!("{:#}", e))
+        } else {
+            None
+        };
+
+        Ok(RunOutcome {
+            exit_code,
+            container_name,
+            container_id: container.id,
+            timed_out,
+            kept: !remove,
+            cleanup_error,
+            failure_dir,
+        })
+    }
+
+    /// Save the log tail and the script's diagnostics into `<output_dir>/failure-<timestamp>/`
+    async fn collect_diagnostics(
+        &self,
+        container_id: &str,
+        output_dir: &Path,
+        log_tail: &VecDeque<String>,
+    ) -> Result<PathBuf> {
+        let dir = output_dir.join(format!(
+            "failure-{}",
+            chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ")
+        ));
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        let log: String = log_tail.iter().map(|line| format!("{}\n", line)).collect();
+        std::fs::write(dir.join("container.log"), log).context("Failed to write container log")?;
+
+        // The trap may not have run (e.g. the container was killed); the log alone still helps
+        let options = DownloadFromContainerOptions {
+            path: DIAGNOSTICS_DIR,
+        };
+        let mut stream = self
+            .client
+            .download_from_container(container_id, Some(options));
+        let mut data = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            match chunk {
+                Ok(chunk) => data.extend_from_slice(&chunk),
+                Err(e) => {
+                    tracing::debug!("No diagnostics in container: {}", e);
+                    return Ok(dir);
+                }
+            }
+        }
+
+        let mut archive = tar::Archive::new(data.as_slice());
+        for entry in archive.entries().context("Invalid diagnostics archive")? {
+            let mut entry = entry.context("Invalid diagnostics archive")?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            // Drop the top-level directory and refuse anything that escapes it
+            let path = entry.path()?.into_owned();
+            let relative: PathBuf = path.components().skip(1).collect();
+            if relative.as_os_str().is_empty()
+                || !relative
+                    .components()
+                    .all(|c| matches!(c, Component::Normal(_)))
+            {
+                continue;
+            }
+            let target = dir.join(&relative);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            entry
+                .unpack(&target)
+                .with_context(|| format!("Failed to extract {}", path.display()))?;
+        }
+
+        Ok(dir)
+    }
+
+    /// Whether a container is still running; assumed so while the daemon is unreachable
+    async fn is_running(&self, container_id: &str) -> bool {
+        match self.client.inspect_container(container_id, None).await {
+            Ok(info) => info.state.and_then(|state| state.running).unwrap_or(false),
+            Err(_) => true,
+        }
+    }
+
+    /// Remove a container
+    pub async fn remove(&self, container_id: &str, force: bool) -> Result<()> {
+        let remove_options = RemoveContainerOptions {
+            force,
+            ..Default::default()
+        };
+
+        self.client
+            .remove_container(container_id, Some(remove_options))
+            .await
+            .with_context(|| format!("Failed to remove container {}", container_id))
+    }
+
+    /// List all containers created by symgen, including stopped ones
+    pub async fn list_managed(&self) -> Result<Vec<ManagedContainer>> {
+        let options = ListContainersOptions::<String> {
+            all: true,
+            filters: HashMap::from([(
+                "label".to_string(),
+                vec![format!("{}=true", MANAGED_LABEL)],
+            )]),
+            ..Default::default()
+        };
+
+        let containers = self
+            .client
+            .list_containers(Some(options))
+            .await
+            .context("Failed to list containers")?;
+
+        Ok(containers
+            .into_iter()
+            .map(|c| ManagedContainer {
+                id: c.id.unwrap_or_default(),
+                name: c
+                    .names
+                    .and_then(|names| names.into_iter().next())
+                    .map(|name| name.trim_start_matches('/').to_string())
+                    .unwrap_or_default(),
+                state: c.state.unwrap_or_default(),
+                created: c.created.unwrap_or_default(),
+                labels: c.labels.unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// Sample a running container's CPU and memory usage
+    pub async fn usage(&self, container_id: &str) -> Result<ContainerUsage> {
+        // A non-streaming request waits for a second sample, so precpu_stats is filled in
+        let options = StatsOptions {
+            stream: false,
+            one_shot: false,
+        };
+        let stats = self
+            .client
+            .stats(container_id, Some(options))
+            .next()
+            .await
+            .context("No statistics returned")?
+            .with_context(|| format!("Failed to read statistics of {}", container_id))?;
+
+        let cpu_delta = stats
+            .cpu_stats
+            .cpu_usage
+            .total_usage
+            .saturating_sub(stats.precpu_stats.cpu_usage.total_usage);
+        let system_delta = stats
+            .cpu_stats
+            .system_cpu_usage
+            .unwrap_or_default()
+            .saturating_sub(stats.precpu_stats.system_cpu_usage.unwrap_or_default());
+        let cpus = stats.cpu_stats.online_cpus.unwrap_or(1);
+        let cpu_percent = if system_delta > 0 {
+            cpu_delta as f64 / system_delta as f64 * cpus as f64 * 100.0
+        } else {
+            0.0
+        };
+
+        Ok(ContainerUsage {
+            cpu_percent,
+            memory_bytes: stats.memory_stats.usage.unwrap_or_default(),
+            memory_limit: stats.memory_stats.limit.unwrap_or_default(),
+        })
+    }
+
+    /// Download the container's work directory and unpack its files into `output_dir`
+    async fn copy_out(&self, container_id: &str, output_dir: &Path) -> Result<()> {
+        let options = DownloadFromContainerOptions { path: WORK_DIR };
+        let mut stream = self
+            .client
+            .download_from_container(container_id, Some(options));
+
+        let mut data = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            data.extend_from_slice(&chunk.context("Failed to download results")?);
+        }
+
+        // The archive holds a single top-level directory named after WORK_DIR
+        let mut archive = tar::Archive::new(data.as_slice());
+        for entry in archive.entries().context("Invalid results archive")? {
+            let mut entry = entry.context("Invalid results archive")?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let path = entry.path()?.into_owned();
+            if path.components().count() != 2 {
+                continue;
+            }
+            if let Some(name) = path.file_name() {
+                entry
+                    .unpack(output_dir.join(name))
+                    .with_context(|| format!("Failed to extract {}", path.display()))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Pack the generation script into an in-memory tar archive for upload
+fn script_archive(script: &str) -> Result<Vec<u8>> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(script.len() as u64);
+    header.set_mode(0o755);
+    header.set_cksum();
+
+    let mut archive = tar::Builder::new(Vec::new());
+    archive
+        .append_data(&mut header, SCRIPT_NAME, script.as_bytes())
+        .context("Failed to package script")?;
+    archive.into_inner().context("Failed to package script")
+}
+
+/// Split the RFC 3339 timestamp Docker puts in front of each log line
+fn split_timestamp(line: &str) -> (Option<DateTime<Utc>>, &str) {
+    line.split_once(' ')
+        .and_then(|(stamp, rest)| {
+            let time = DateTime::parse_from_rfc3339(stamp).ok()?;
+            Some((Some(time.with_timezone(&Utc)), rest))
+        })
+        .unwrap_or((None, line))
+}
+
+/// Splits streamed log chunks into lines without buffering more than one line
+#[derive(Default)]
+struct LineSplitter {
+    pending: Vec<u8>,
+    /// Set while discarding the remainder of an overlong line
+    truncated: bool,
+}
+
+impl LineSplitter {
+    /// Feed a chunk, calling `emit` for every line it completes
+    fn push(&mut self, mut chunk: &[u8], emit: &mut impl FnMut(&str)) {
+        while let Some(end) = chunk.iter().position(|&b| b == b'\n') {
+            self.append(&chunk[..end]);
+            self.flush(emit);
+            self.truncated = false;
+            chunk = &chunk[end + 1..];
+        }
+        self.append(chunk);
+        if self.pending.len() >= MAX_LOG_LINE && !self.truncated {
+            self.flush(emit);
+            self.truncated = true;
+        }
+    }
+
+    /// Drop a partial line, e.g. when the stream it came from broke off
+    fn discard(&mut self) {
+        self.pending.clear();
+        self.truncated = false;
+    }
+
+    /// Emit whatever is left once the stream ends
+    fn finish(&mut self, emit: &mut impl FnMut(&str)) {
+        if !self.pending.is_empty() {
+            self.flush(emit);
+        }
+    }
+
+    fn append(&mut self, bytes: &[u8]) {
+        if self.truncated {
+            return;
+        }
+        let room = MAX_LOG_LINE.saturating_sub(self.pending.len());
+        self.pending
+            .extend_from_slice(&bytes[..bytes.len().min(room)]);
+    }
+
+    fn flush(&mut self, emit: &mut impl FnMut(&str)) {
+        if self.truncated {
+            return;
+        }
+        let line = String::from_utf8_lossy(&self.pending);
+        emit(line.trim_end_matches('\r'));
+        self.pending.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(chunks: &[&[u8]]) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut splitter = LineSplitter::default();
+        for chunk in chunks {
+            splitter.push(chunk, &mut |line| lines.push(line.to_string()));
+        }
+        splitter.finish(&mut |line| lines.push(line.to_string()));
+        lines
+    }
+
+    #[test]
+    fn joins_lines_across_chunks() {
+        assert_eq!(
+            split(&[b"first\r\nsec", b"ond\nthi", b"rd"]),
+            ["first", "second", "third"]
+        );
+        assert_eq!(split(&[b"a\n\nb\n"]), ["a", "", "b"]);
+    }
+
+    #[test]
+    fn truncates_overlong_lines() {
+        let long = vec![b'x'; MAX_LOG_LINE + 100];
+        let lines = split(&[&long[..10], &long[10..], b"\nnext\n"]);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), MAX_LOG_LINE);
+        assert_eq!(lines[1], "next");
+    }
+
+    #[test]
+    fn discards_partial_lines() {
+        let mut lines = Vec::new();
+        let mut splitter = LineSplitter::default();
+        splitter.push(b"broken", &mut |line| lines.push(line.to_string()));
+        splitter.discard();
+        splitter.push(b"whole\n", &mut |line| lines.push(line.to_string()));
+        assert_eq!(lines, ["whole"]);
+    }
+
+    #[test]
+    fn splits_docker_timestamps() {
+        let (time, rest) = split_timestamp("2024-01-02T03:04:05.123456789Z hello world");
+        assert_eq!(rest, "hello world");
+        assert_eq!(
+            time.map(|t| t.to_rfc3339()),
+            Some("2024-01-02T03:04:05.123456789+00:00".to_string())
+        );
+        assert_eq!(split_timestamp("no timestamp"), (None, "no timestamp"));
+    }
+}
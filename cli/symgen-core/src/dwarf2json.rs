@@ -0,0 +1,234 @@
+use anyhow::{bail, Context, Result};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use crate::artifacts::{sha256_file, sidecar_path, CHECKSUM_SUFFIX};
+use crate::cache::{write_atomic, Cache, FileLock};
+use crate::distros::Arch;
+use crate::output::Output;
+
+/// Cache subdirectory holding dwarf2json binaries, as `<version>/<arch>/dwarf2json`
+pub const DWARF2JSON_NAMESPACE: &str = "dwarf2json";
+/// Release used by default and whenever `latest` cannot be resolved
+pub const PINNED_VERSION: &str = "v0.8.0";
+/// GitHub API endpoint naming the newest dwarf2json release
+pub const LATEST_RELEASE_API: &str =
+    "https://api.github.com/repos/volatilityfoundation/dwarf2json/releases/latest";
+/// Cache entry remembering the resolved `latest` tag
+const LATEST_ENTRY: &str = "latest";
+/// How long a resolved `latest` tag is used before asking GitHub again; anonymous
+/// API calls are limited to 60 an hour per address, which CI runners share
+const LATEST_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Published SHA256 digests of upstream release assets, as `(tag, arch, digest)`.
+///
+/// Downloads of a listed asset are verified against its digest even when the
+/// configuration pins none. Add the assets of a release before pointing
+/// `PINNED_VERSION` at it.
+const RELEASE_SHA256: &[(&str, Arch, &str)] = &[];
+
+/// Release asset URL of a dwarf2json version
+pub fn release_url(tag: &str, arch: Arch) -> String {
+    format!(
+        "https://github.com/volatilityfoundation/dwarf2json/releases/download/{}/dwarf2json-linux-{}",
+        tag,
+        arch.go_arch()
+    )
+}
+
+/// Published digest of a release asset URL, for releases symgen knows
+pub fn release_sha256(url: &str) -> Option<&'static str> {
+    RELEASE_SHA256
+        .iter()
+        .find(|(tag, arch, _)| release_url(tag, *arch) == url)
+        .map(|(_, _, digest)| *digest)
+}
+
+/// Tag for a configured dwarf2json version, resolving `latest` through the GitHub API.
+///
+/// The API is called with `GITHUB_TOKEN` (or `GH_TOKEN`) when set, and the answer
+/// is cached for a few hours. When GitHub cannot be asked, an older cached answer
+/// is used, else the pinned release, so a rate limit never fails a run.
+pub async fn resolve_version(cache: Option<&Cache>, version: &str, output: &Output) -> String {
+    if version != "latest" {
+        return version.to_string();
+    }
+
+    let entry = cache.map(|cache| cache.entry_path(DWARF2JSON_NAMESPACE, LATEST_ENTRY));
+    let cached = entry.as_ref().and_then(|path| {
+        let tag = std::fs::read_to_string(path).ok()?.trim().to_string();
+        let age = std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .unwrap_or(Duration::MAX);
+        (!tag.is_empty()).then_some((tag, age))
+    });
+    if let Some((tag, age)) = &cached {
+        if *age < LATEST_TTL {
+            return tag.clone();
+        }
+    }
+
+    match latest_release(LATEST_RELEASE_API).await {
+        Ok(tag) => {
+            if let Some(path) = &entry {
+                let written = path
+                    .parent()
+                    .map_or(Ok(()), std::fs::create_dir_all)
+                    .context("Failed to create cache directory")
+                    .and_then(|_| {
+                        write_atomic(path, |file| {
+                            writeln!(file, "{}", tag).context("Failed to write release tag")
+                        })
+                    });
+                if let Err(e) = written {
+                    tracing::warn!("Failed to cache the latest dwarf2json release: {:#}", e);
+                }
+            }
+            tag
+        }
+        Err(e) => {
+            let fallback = cached
+                .map(|(tag, _)| tag)
+                .unwrap_or_else(|| PINNED_VERSION.to_string());
+            output.warning(&format!(
+                "Could not look up the latest dwarf2json release, using {}: {:#}",
+                fallback, e
+            ));
+            fallback
+        }
+    }
+}
+
+/// Tag of the newest release named by a GitHub `releases/latest` API endpoint
+pub(crate) async fn latest_release(api_url: &str) -> Result<String> {
+    #[derive(serde::Deserialize)]
+    struct Release {
+        tag_name: String,
+    }
+
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("symgen/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(15))
+        .build()?;
+    let mut request = client
+        .get(api_url)
+        .header("Accept", "application/vnd.github+json");
+    let token = std::env::var("GITHUB_TOKEN")
+        .or_else(|_| std::env::var("GH_TOKEN"))
+        .ok()
+        .filter(|token| !token.is_empty());
+    if let Some(token) = &token {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request.send().await.context("GitHub API request failed")?;
+    let status = response.status();
+    let exhausted = response
+        .headers()
+        .get("x-ratelimit-remaining")
+        .is_some_and(|remaining| remaining == "0");
+    if status.as_u16() == 429 || (status.as_u16() == 403 && exhausted) {
+        if token.is_none() {
+            bail!("GitHub API rate limit exceeded; set GITHUB_TOKEN to raise it");
+        }
+        bail!("GitHub API rate limit exceeded");
+    }
+    let release: Release = response
+        .error_for_status()
+        .context("GitHub API request failed")?
+        .json()
+        .await
+        .context("Unexpected GitHub API response")?;
+    // The tag becomes part of a URL and a cache path
+    if release.tag_name.is_empty()
+        || release.tag_name.contains('/')
+        || release.tag_name.contains("..")
+    {
+        bail!("Unexpected release tag '{}'", release.tag_name);
+    }
+    Ok(release.tag_name)
+}
+
+/// Host copy of the dwarf2json binary at `url`, downloaded on first use.
+///
+/// The digest recorded at download time is rechecked on every use, so a
+/// truncated or modified cache entry is fetched again; a digest pinned in the
+/// configuration, else the published one of a known release, is enforced on
+/// the download itself.
+pub async fn cached_binary(
+    cache: &Cache,
+    url: &str,
+    arch: Arch,
+    expected_sha256: Option<&str>,
+) -> Result<PathBuf> {
+    let expected_sha256 = expected_sha256.or_else(|| release_sha256(url));
+    let name = format!("{}/{}/dwarf2json", version_key(url), arch.go_arch());
+    let path = cache.entry_path(DWARF2JSON_NAMESPACE, &name);
+    let digest_path = sidecar_path(&path, CHECKSUM_SUFFIX);
+    let matches_pin =
+        |digest: &str| expected_sha256.is_none_or(|pin| pin.eq_ignore_ascii_case(digest));
+
+    if path.is_file() {
+        let actual = sha256_file(&path)?;
+        let recorded = std::fs::read_to_string(&digest_path).unwrap_or_default();
+        if recorded.trim() == actual && matches_pin(&actual) {
+            return Ok(path);
+        }
+        tracing::warn!(
+            "Cached dwarf2json {} failed verification, downloading it again",
+            path.display()
+        );
+    }
+
+    let bytes = reqwest::get(url)
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to download {}", url))?
+        .bytes()
+        .await
+        .with_context(|| format!("Failed to download {}", url))?;
+    let actual = format!("{:x}", Sha256::digest(&bytes));
+    if !matches_pin(&actual) {
+        bail!(
+            "dwarf2json checksum mismatch for {}: expected {}, got {}",
+            url,
+            expected_sha256.unwrap_or_default(),
+            actual
+        );
+    }
+
+    let dir = path
+        .parent()
+        .context("Cache entry has no parent directory")?;
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create cache directory {}", dir.display()))?;
+    let _lock = FileLock::acquire(&path)?;
+    write_atomic(&path, |file| {
+        file.write_all(&bytes).context("Failed to write dwarf2json")
+    })?;
+    write_atomic(&digest_path, |file| {
+        writeln!(file, "{}", actual).context("Failed to write dwarf2json digest")
+    })?;
+    tracing::info!("Cached dwarf2json {} as {}", url, path.display());
+    Ok(path)
+}
+
+/// Release tag of a GitHub release asset URL
+pub fn release_tag(url: &str) -> Option<&str> {
+    url.split_once("/releases/download/")
+        .and_then(|(_, rest)| rest.split('/').next())
+        .filter(|tag| !tag.is_empty() && !tag.contains(".."))
+}
+
+/// Directory name for a download URL: the release tag for GitHub release
+/// assets, otherwise a digest of the URL so different mirrors never collide
+fn version_key(url: &str) -> String {
+    match release_tag(url) {
+        Some(tag) => tag.to_string(),
+        None => format!("url-{:.16x}", Sha256::digest(url.as_bytes())),
+    }
+}
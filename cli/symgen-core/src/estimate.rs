@@ -0,0 +1,122 @@
+use serde::Serialize;
+
+use crate::distros::{Arch, DistroVersion};
+use crate::history::{kernel_series, HistoryEntry};
+
+/// Smallest, median and largest observed value
+#[derive(Debug, Serialize)]
+pub struct Spread {
+    pub min: u64,
+    pub median: u64,
+    pub max: u64,
+}
+
+impl Spread {
+    fn of(mut values: Vec<u64>) -> Option<Self> {
+        values.sort_unstable();
+        Some(Self {
+            min: *values.first()?,
+            median: values[values.len() / 2],
+            max: *values.last()?,
+        })
+    }
+}
+
+/// Expected cost of a generation run, derived from past runs
+#[derive(Debug, Serialize)]
+pub struct Estimate {
+    pub kernel: String,
+    pub distro: String,
+    pub distro_version: String,
+    pub arch: Arch,
+    /// Which past runs the figures come from
+    pub basis: &'static str,
+    pub samples: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debuginfo_bytes: Option<Spread>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<Spread>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_bytes: Option<Spread>,
+}
+
+/// Estimate a run from the most similar past runs available.
+///
+/// Debug package and ISF sizes track the kernel series closely, so matches
+/// are widened step by step only when nothing closer exists.
+pub fn estimate(
+    history: &[HistoryEntry],
+    version: &DistroVersion,
+    kernel: &str,
+    arch: Arch,
+) -> Estimate {
+    let distro = version.distro.display_name();
+    let series = kernel_series(kernel);
+    const BASES: [&str; 4] = [
+        "same release and kernel series",
+        "same distribution and kernel series",
+        "same distribution",
+        "any distribution",
+    ];
+    let matches = |tier: usize, e: &HistoryEntry| {
+        let same_distro = e.distro == distro && e.arch == arch.name();
+        match tier {
+            0 => same_distro && e.distro_version == version.version && e.kernel_series() == series,
+            1 => same_distro && e.kernel_series() == series,
+            2 => same_distro,
+            _ => e.arch == arch.name(),
+        }
+    };
+
+    let (basis, similar) = BASES
+        .iter()
+        .enumerate()
+        .map(|(tier, basis)| {
+            (
+                *basis,
+                history
+                    .iter()
+                    .filter(|e| matches(tier, e))
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .find(|(_, similar)| !similar.is_empty())
+        .unwrap_or(("no previous runs", Vec::new()));
+
+    Estimate {
+        kernel: kernel.to_string(),
+        distro: distro.to_string(),
+        distro_version: version.version.clone(),
+        arch,
+        basis,
+        samples: similar.len(),
+        debuginfo_bytes: Spread::of(similar.iter().filter_map(|e| e.debuginfo_bytes).collect()),
+        duration_secs: Spread::of(similar.iter().map(|e| e.duration_secs).collect()),
+        symbol_bytes: Spread::of(similar.iter().map(|e| e.symbol_bytes).collect()),
+    }
+}
+
+/// Human-readable byte count (`812.4 MiB`)
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Human-readable duration (`14m 05s`)
+pub fn format_duration(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
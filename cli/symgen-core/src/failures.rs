@@ -0,0 +1,117 @@
+use anyhow::{Context, Result};
+use regex::RegexSet;
+use serde::{Deserialize, Serialize};
+
+/// Failures the community keeps running into: (log line regex, cause, hint)
+const KNOWN_FAILURES: &[(&str, &str, &str)] = &[
+    (
+        r"(NO_PUBKEY|EXPKEYSIG) C8CAB6595FDFF622",
+        "The Ubuntu debug symbol archive signing key is missing or expired in this image",
+        "Pass --allow-unauthenticated, or install a current ubuntu-dbgsym-keyring with a `before` command on add_repos",
+    ),
+    (
+        r"mirrorlist\.centos\.org",
+        "mirrorlist.centos.org is shut down, so the image's default repositories no longer resolve",
+        "Point the repositories at vault.centos.org with a `replace` override on the update step",
+    ),
+    (
+        r"(vault\.centos\.org|dl\.rockylinux\.org/vault|repo\.almalinux\.org/vault).*(404|Not Found)",
+        "The release was moved within the vault, so the repository URL is gone",
+        "Check the vault's current layout and fix the URL with a `replace` override on add_repos",
+    ),
+    (
+        r"does not have a Release file",
+        "The release's repositories have left the main mirrors (end of life)",
+        "Switch the sources to archive.debian.org or old-releases.ubuntu.com with a `before` command on update",
+    ),
+    (
+        r"Hash Sum mismatch",
+        "The mirror served files from the middle of a sync",
+        "Retry in a few minutes or use a different mirror",
+    ),
+    (
+        r"No space left on device",
+        "The container ran out of disk; debug packages unpack to several GB",
+        "Free space in the container runtime's storage, e.g. `docker system prune`",
+    ),
+    (
+        r"Killed\s+/usr/local/bin/dwarf2json",
+        "dwarf2json was killed, most likely for running out of memory",
+        "Give the container runtime more memory; recent kernels need around 8 GB",
+    ),
+    (
+        r"(certificate verify failed|server certificate verification failed|unable to get local issuer certificate)",
+        "TLS certificates were rejected, which usually means a TLS-intercepting proxy",
+        "Pass the proxy's root certificate with --ca-cert",
+    ),
+    (
+        r"(Temporary failure resolving|Could not resolve host)",
+        "The container could not resolve host names",
+        "Check the container runtime's DNS and proxy settings",
+    ),
+    (
+        r"ERROR (403: rate limit exceeded|429)",
+        "GitHub rate-limited the download",
+        "Retry later, or mirror the file and set dwarf2json_url in the config",
+    ),
+];
+
+/// A log pattern with the failure it indicates, from the `[[failures]]` config tables
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FailurePattern {
+    /// Regex matched against each container log line
+    pub pattern: String,
+    /// What went wrong
+    pub cause: String,
+    /// What to do about it
+    pub hint: String,
+}
+
+/// A known failure recognized in a run's log
+#[derive(Debug, Clone, Serialize)]
+pub struct KnownFailure {
+    pub cause: String,
+    pub hint: String,
+    /// First log line that matched
+    pub line: String,
+}
+
+/// Matches container log lines against the known failures
+pub struct FailureClassifier {
+    patterns: RegexSet,
+    /// Cause and hint per pattern, configured ones first
+    explanations: Vec<(String, String)>,
+}
+
+impl FailureClassifier {
+    /// Build the classifier from the configured patterns, which take precedence
+    /// over the built-in ones
+    pub fn new(configured: &[FailurePattern]) -> Result<Self> {
+        let entries: Vec<(&str, &str, &str)> = configured
+            .iter()
+            .map(|p| (p.pattern.as_str(), p.cause.as_str(), p.hint.as_str()))
+            .chain(KNOWN_FAILURES.iter().copied())
+            .collect();
+        let patterns = RegexSet::new(entries.iter().map(|(pattern, _, _)| pattern))
+            .context("Invalid failure pattern")?;
+        Ok(Self {
+            patterns,
+            explanations: entries
+                .iter()
+                .map(|(_, cause, hint)| (cause.to_string(), hint.to_string()))
+                .collect(),
+        })
+    }
+
+    /// The known failure a log line shows, if any
+    pub fn classify(&self, line: &str) -> Option<KnownFailure> {
+        let index = self.patterns.matches(line).into_iter().next()?;
+        let (cause, hint) = &self.explanations[index];
+        Some(KnownFailure {
+            cause: cause.clone(),
+            hint: hint.clone(),
+            line: line.to_string(),
+        })
+    }
+}
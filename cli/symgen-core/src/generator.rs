@@ -0,0 +1,2002 @@
+use anyhow::{anyhow, Context, Result};
+use futures::future::join_all;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+use crate::acquisition::AcquisitionSource;
+use crate::artifacts::{self, sha256_file, sidecar_path, SymbolManifest, LOG_SUFFIX};
+use crate::cache::{Cache, ISF_NAMESPACE};
+use crate::config::{Config, ScriptOverrides};
+use crate::distros::{
+    check_rpm_kernel, fallback_versions, find_version, ubuntu_cloud_flavor, ubuntu_package_version,
+    wsl_release, Arch, Distro, DistroVersion,
+};
+use crate::docker::{
+    DockerClient, OutputTransfer, RemovePolicy, RunOptions, DISTRO_LABEL, DWARF2JSON_LABEL,
+    JOB_LABEL, KERNEL_LABEL, PREBAKED_LABEL, SCRIPT_DIR, SCRIPT_NAME, TAG_LABEL,
+};
+use crate::dwarf2json;
+use crate::estimate::format_duration;
+use crate::failures::{FailureClassifier, KnownFailure};
+use crate::history::{self, HistoryEntry};
+use crate::isf::{self, SymgenMetadata};
+use crate::jobs::{JobStore, NewJob};
+use crate::netfs::{self, Scratch};
+use crate::output::{JsonResult, Output};
+use crate::postprocess::{self, HookContext};
+use crate::proxy::ProxySettings;
+use crate::registry::RegistrySettings;
+use crate::script::{
+    dwarf2json_source, render_bake_script, render_flavor_probe, render_kernel_listing,
+    render_script, symbol_filename, Compression, RenderedScript, ScriptOptions,
+    DWARF2JSON_INPUT_PATH, DWARF2JSON_URL,
+};
+use crate::sign;
+use crate::steps::{
+    parse_marker, Marker, StepDuration, StepTracker, RESULT_DEBUGINFO_BYTES,
+    RESULT_DWARF2JSON_SHA256, RESULT_FLAVORS, RESULT_KERNEL, RESULT_SOURCE_PACKAGE,
+    RESULT_SYSTEM_MAP, RESULT_SYSTEM_MAP_SOURCE, RESULT_VMLINUX,
+};
+use crate::store;
+use crate::upload::{S3Client, S3Destination};
+use crate::vol3;
+
+/// Script exit code meaning the release's repositories lack the kernel's debug symbols
+const EXIT_DEBUGINFO_NOT_FOUND: i64 = 2;
+/// A delta run warns when more than one symbol in this many was added or removed
+const DELTA_CHANGE_LIMIT: usize = 100;
+/// Log fragments (lowercased) from apt, dnf and TLS clients rejecting data as not valid yet
+const CLOCK_SKEW_PATTERNS: &[&str] = &[
+    "is not valid yet",
+    "not yet valid",
+    "time warp or clock problem",
+];
+
+/// Result of symbol generation
+#[derive(Debug, Serialize)]
+pub struct GenerationResult {
+    /// Value of the `symgen.job` label on the run's containers
+    pub job_id: String,
+    pub kernel_version: String,
+    pub distro: String,
+    pub distro_version: String,
+    pub symbol_file: String,
+    pub file_size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// dwarf2json release that produced the file, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dwarf2json_version: Option<String>,
+    /// SHA256 of the dwarf2json binary that produced the file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dwarf2json_sha256: Option<String>,
+    /// vmlinux inside the container the symbols were read from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vmlinux: Option<String>,
+    /// System.map inside the container, when one was used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_map: Option<String>,
+    /// Where the System.map came from: `installed`, `package` (unpacked from the
+    /// kernel package) or `symtab` (derived from vmlinux)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_map_source: Option<String>,
+    /// Package that shipped the vmlinux (`name=version` or an rpm NVRA)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_package: Option<String>,
+    /// Originally selected release when the symbols came from a fallback release
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback_from: Option<String>,
+    /// Wall time of each script step, from the container's log timestamps
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub step_durations: Vec<StepDuration>,
+    /// The file was copied from the symbol cache; no container ran
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub from_cache: bool,
+    /// Content-addressed copy `symbol_file` links to, with a store configured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stored_at: Option<String>,
+    /// Copy placed in the Volatility3 symbol directory with `--install-vol3`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installed_at: Option<String>,
+    /// Object the file was uploaded to, with an upload destination configured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uploaded_to: Option<String>,
+    /// Contents of the manifest sidecar written with `--manifest`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<SymbolManifest>,
+    /// Detached signatures written with `--sign`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub signatures: Vec<String>,
+    /// An existing symbol file was replaced (`--force`)
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub overwritten: bool,
+}
+
+impl GenerationResult {
+    /// Result describing a symbol file that no container run produced
+    fn for_file(
+        job_id: &str,
+        kernel: &str,
+        version: &DistroVersion,
+        path: &Path,
+        tag: Option<String>,
+    ) -> Result<Self> {
+        Ok(Self {
+            job_id: job_id.to_string(),
+            kernel_version: kernel.to_string(),
+            distro: version.distro.display_name().to_string(),
+            distro_version: version.version.clone(),
+            symbol_file: path.to_string_lossy().to_string(),
+            file_size: std::fs::metadata(path)
+                .context("Failed to get file metadata")?
+                .len(),
+            tag,
+            dwarf2json_version: None,
+            dwarf2json_sha256: None,
+            vmlinux: None,
+            system_map: None,
+            system_map_source: None,
+            source_package: None,
+            fallback_from: None,
+            step_durations: Vec::new(),
+            from_cache: false,
+            stored_at: None,
+            installed_at: None,
+            uploaded_to: None,
+            manifest: None,
+            signatures: Vec::new(),
+            overwritten: false,
+        })
+    }
+}
+
+/// Result reported when the requested symbol file is already present
+#[derive(Debug, Serialize)]
+pub struct ExistingSymbol {
+    /// Always true; lets consumers tell this result from a `GenerationResult`
+    pub already_exists: bool,
+    /// `skipped` with `--skip-existing`, else `exists`
+    pub status: &'static str,
+    pub kernel_version: String,
+    pub distro: String,
+    pub distro_version: String,
+    pub symbol_file: String,
+    pub file_size: u64,
+    pub sha256: String,
+}
+
+/// What a generation run left in the output directory
+#[derive(Debug)]
+pub enum Generated {
+    /// The symbol file was written by this run
+    Created(Box<GenerationResult>),
+    /// The symbol file was already there; nothing was done
+    Existing(Box<GenerationResult>),
+}
+
+impl Generated {
+    pub fn into_result(self) -> GenerationResult {
+        match self {
+            Self::Created(result) | Self::Existing(result) => *result,
+        }
+    }
+
+    pub fn into_path(self) -> PathBuf {
+        PathBuf::from(self.into_result().symbol_file)
+    }
+}
+
+/// What to do when the symbol file is already in the output directory
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IfExists {
+    /// Leave it alone and report that nothing was done
+    #[default]
+    Report,
+    /// Generate it again, bypassing the cache, and replace it
+    Overwrite,
+    /// Leave it alone and count the run as a success
+    Skip,
+}
+
+/// Options controlling a single generation run
+///
+/// Stored as JSON with each job so a retry runs with the same options.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenerateOptions {
+    /// Strip types and enums not reachable from any symbol after generation
+    pub minimal: bool,
+    /// Record symgen version, tag and source package under `metadata.symgen`
+    pub annotate: bool,
+    /// Host commands run over the decompressed symbol file, after those in the config
+    pub post_process: Vec<String>,
+    /// Case or engagement label recorded with the run
+    pub tag: Option<String>,
+    /// How the symbol file is retrieved from the container
+    pub transfer: OutputTransfer,
+    /// When to remove the container after the run
+    pub remove: RemovePolicy,
+    /// Architecture of the target kernel
+    pub arch: Arch,
+    /// Trust the Ubuntu proposed/ddebs repositories without checking signatures
+    pub allow_unauthenticated: bool,
+    /// Leave out the Ubuntu `-proposed` pocket
+    pub no_proposed: bool,
+    /// Leave out the Ubuntu `-updates` debug symbol pocket
+    pub no_updates: bool,
+    /// Give up instead of retrying other releases when debug symbols are missing
+    pub no_fallback: bool,
+    /// Extra arguments passed through to dwarf2json
+    pub converter_args: Vec<String>,
+    /// Where the output gets compressed
+    pub compression: Compression,
+    /// Save the full container output as a `.log` sidecar
+    pub log_file: bool,
+    /// Skip the symbol file cache lookup
+    pub no_cache: bool,
+    /// Where the debug kernel comes from
+    pub acquisition: AcquisitionSource,
+    /// Ignore apt Release file dates (host clock is skewed)
+    pub fix_time: bool,
+    /// ISF of an earlier upload with the same ABI to take types from
+    pub delta_from: Option<PathBuf>,
+    /// Banner of the target kernel, written into `linux_banner` for delta runs
+    pub banner: Option<String>,
+    /// Copy the finished file into the local Volatility3 symbol directory
+    pub install_vol3: bool,
+    /// Write `.sha256` and `.manifest.json` sidecars next to the finished file
+    pub manifest: bool,
+    /// Write detached GPG signatures of the finished file and its manifest
+    pub sign: bool,
+    /// GPG key to sign with (default: gpg's default key)
+    pub gpg_key: Option<String>,
+    /// Ubuntu upload (`101` of `5.15.0-91.101`) to pin package installs to
+    pub ubuntu_upload: Option<String>,
+    /// What to do with a symbol file already in the output directory
+    pub if_exists: IfExists,
+    /// Kill a container that runs longer than this
+    pub timeout: Option<Duration>,
+    /// Image run instead of the release's `docker_image`; rules out fallback releases
+    pub image: Option<String>,
+}
+
+/// A container ran past [`GenerateOptions::timeout`] and was killed
+#[derive(Debug)]
+pub struct TimedOut {
+    pub limit: Duration,
+    /// Step the script was stuck in
+    pub step: Option<String>,
+}
+
+impl std::fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Generation timed out after {}",
+            format_duration(self.limit.as_secs())
+        )?;
+        if let Some(step) = &self.step {
+            write!(f, " during step {}", step)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for TimedOut {}
+
+/// Outcome of running the generation script for one release
+struct Attempt {
+    exit_code: i64,
+    /// The container was killed for running past the timeout
+    timed_out: bool,
+    /// Whether the log showed signatures or certificates rejected as not valid yet
+    clock_skew: bool,
+    /// Known failures recognized in the log, one per cause
+    known_failures: Vec<KnownFailure>,
+    /// Step the script was in when it exited
+    last_step: Option<String>,
+    dwarf2json_version: Option<String>,
+    dwarf2json_sha256: Option<String>,
+    vmlinux: Option<String>,
+    system_map: Option<String>,
+    system_map_source: Option<String>,
+    source_package: Option<String>,
+    debuginfo_bytes: Option<u64>,
+    step_durations: Vec<StepDuration>,
+}
+
+/// Symbol generator using Docker
+pub struct SymbolGenerator {
+    docker: DockerClient,
+    config: Config,
+    /// PEM certificates of the configured CA certificates
+    ca_certs: Vec<String>,
+    /// Site shell code run first in every container (`--pre-script`)
+    pre_script: Option<String>,
+    /// Site shell code run after each generation (`--post-script`)
+    post_script: Option<String>,
+    /// Proxy the containers go through
+    proxy: ProxySettings,
+    /// Job records; generation goes on without them if the database cannot be opened
+    jobs: Option<JobStore>,
+    failures: FailureClassifier,
+    /// Where finished files are uploaded; set up front so missing credentials fail before a run
+    upload: Option<(S3Destination, S3Client)>,
+}
+
+impl SymbolGenerator {
+    /// Create a new symbol generator
+    pub async fn new(config: Config) -> Result<Self> {
+        let ca_certs = config.ca_cert_pems()?;
+        let (pre_script, post_script) = config.hook_scripts()?;
+        let proxy = ProxySettings::resolve(config.proxy.as_deref())?;
+        let failures = FailureClassifier::new(&config.failures)?;
+        let upload = match &config.upload {
+            Some(url) => Some((
+                S3Destination::parse(url)?,
+                S3Client::from_env(
+                    config.upload_endpoint.as_deref(),
+                    config.upload_region.as_deref(),
+                )?,
+            )),
+            None => None,
+        };
+        let docker = DockerClient::new(config.runtime)
+            .await?
+            .with_registry(RegistrySettings {
+                mirror: config.registry_mirror.clone(),
+                auth_file: config.registry_auth.clone(),
+            });
+        let jobs = config
+            .state_path()
+            .and_then(|dir| match JobStore::open(&dir) {
+                Ok(store) => Some(store),
+                Err(e) => {
+                    tracing::warn!("Job state is not recorded: {:#}", e);
+                    None
+                }
+            });
+        Ok(Self {
+            docker,
+            config,
+            ca_certs,
+            pre_script,
+            post_script,
+            proxy,
+            jobs,
+            failures,
+            upload,
+        })
+    }
+
+    /// The job database, if it could be opened
+    pub fn jobs(&self) -> Option<&JobStore> {
+        self.jobs.as_ref()
+    }
+
+    /// Generate a Volatility3 symbol file
+    pub async fn generate(
+        &self,
+        kernel: &str,
+        distro_str: &str,
+        version: &str,
+        output_dir: Option<&str>,
+        options: &GenerateOptions,
+        output: &Output,
+    ) -> Result<Generated> {
+        let job_id = self.queue_job(
+            kernel, distro_str, version, output_dir, options, None, output,
+        );
+        self.generate_job(
+            &job_id, kernel, distro_str, version, output_dir, options, output,
+        )
+        .await
+    }
+
+    /// Record a pending job and return its id
+    #[allow(clippy::too_many_arguments)]
+    pub fn queue_job(
+        &self,
+        kernel: &str,
+        distro: &str,
+        version: &str,
+        output_dir: Option<&str>,
+        options: &GenerateOptions,
+        retry_of: Option<&str>,
+        output: &Output,
+    ) -> String {
+        let job_id = uuid::Uuid::new_v4().to_string();
+        // Stored absolute so a retry from another directory writes to the same place
+        let output_dir = std::env::current_dir()
+            .map(|cwd| {
+                cwd.join(output_dir.unwrap_or("."))
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .ok();
+        let stored_options = serde_json::to_string(options).ok();
+        let job = NewJob {
+            kernel,
+            distro,
+            release: version,
+            arch: options.arch.name(),
+            output_dir: output_dir.as_deref(),
+            tag: options.tag.as_deref(),
+            retry_of,
+            options: stored_options.as_deref(),
+        };
+        self.record_job(|jobs| jobs.insert(&job_id, &job), output);
+        job_id
+    }
+
+    /// Run a job queued with [`Self::queue_job`], recording how it ends
+    #[allow(clippy::too_many_arguments)]
+    pub async fn generate_job(
+        &self,
+        job_id: &str,
+        kernel: &str,
+        distro_str: &str,
+        version: &str,
+        output_dir: Option<&str>,
+        options: &GenerateOptions,
+        output: &Output,
+    ) -> Result<Generated> {
+        self.record_job(|jobs| jobs.start(job_id), output);
+        let result = self
+            .run_generation(
+                job_id, kernel, distro_str, version, output_dir, options, output,
+            )
+            .await;
+        match &result {
+            Ok(Generated::Created(r) | Generated::Existing(r)) => {
+                self.record_job(|jobs| jobs.succeed(job_id, &r.symbol_file), output)
+            }
+            Err(e) => self.record_job(|jobs| jobs.fail(job_id, &format!("{:#}", e)), output),
+        }
+        result
+    }
+
+    /// Update the job database, warning instead of failing the run when that fails
+    fn record_job(&self, update: impl FnOnce(&JobStore) -> Result<()>, output: &Output) {
+        if let Some(jobs) = &self.jobs {
+            if let Err(e) = update(jobs) {
+                output.warning(&format!("Failed to record job state: {:#}", e));
+            }
+        }
+    }
+
+    /// Generation itself, without job bookkeeping
+    #[allow(clippy::too_many_arguments)]
+    async fn run_generation(
+        &self,
+        job_id: &str,
+        kernel: &str,
+        distro_str: &str,
+        version: &str,
+        output_dir: Option<&str>,
+        options: &GenerateOptions,
+        output: &Output,
+    ) -> Result<Generated> {
+        let started = Instant::now();
+        let job_id = job_id.to_string();
+
+        let distro_version = resolve_release(kernel, distro_str, version, options)?;
+        let distro = distro_version.distro;
+        let arch = options.arch;
+        if distro == Distro::Wsl {
+            // System.map only comes out of the same build as vmlinux
+            if options.delta_from.is_some() {
+                return Err(anyhow!("--delta-from is not available for WSL2 kernels"));
+            }
+            if kernel.ends_with('+') {
+                output.warning("Kernel was built from a modified tree; symbols come from the published source and may not match");
+            }
+        }
+
+        let acquisition = options.acquisition.resolve();
+        let from_repositories = acquisition.searches_repositories();
+        if options.delta_from.is_some() && options.banner.is_none() {
+            return Err(anyhow!(
+                "--delta-from needs the kernel banner (-b) to update linux_banner"
+            ));
+        }
+
+        let kernel = if from_repositories && distro.lacks_flavor(kernel) {
+            self.resolve_flavor(kernel, &distro_version, &job_id, options, output)
+                .await?
+        } else {
+            kernel.to_string()
+        };
+        let kernel = kernel.as_str();
+
+        output.info(&format!(
+            "Generating symbol for {} {} kernel {}",
+            distro.display_name(),
+            version,
+            kernel
+        ));
+        if let Some(tag) = &options.tag {
+            output.info(&format!("Tagged run: {}", tag));
+        }
+        if let Some(version) = package_version(kernel, distro, options) {
+            output.info(&format!("Pinning Ubuntu packages to version {}", version));
+        }
+        if let Some(flavor) =
+            ubuntu_cloud_flavor(kernel).filter(|_| distro == Distro::Ubuntu && options.no_updates)
+        {
+            output.warning(&format!(
+                "{} kernels are published to -updates after release; --no-updates may leave no debug symbols",
+                flavor
+            ));
+        }
+        if arch != Arch::Amd64 {
+            output.info(&format!("Target architecture: {}", arch.name()));
+        }
+        if let Some(base) = &options.delta_from {
+            output.info(&format!(
+                "Delta run: types from {}, symbols from System.map",
+                base.display()
+            ));
+        } else if !from_repositories {
+            output.info(&format!("Debug kernel source: {}", acquisition.name()));
+        }
+        if options.allow_unauthenticated {
+            if distro == Distro::Ubuntu {
+                output.warning("Repository signatures will not be verified");
+            } else {
+                output.warning("--allow-unauthenticated only applies to Ubuntu and is ignored");
+            }
+        }
+        if options.fix_time {
+            if distro.uses_apt() {
+                output.warning("Ignoring apt Release file dates (--fix-time)");
+            } else {
+                output.warning("--fix-time only applies to apt-based distributions and is ignored");
+            }
+        }
+        if (options.no_proposed || options.no_updates) && distro != Distro::Ubuntu {
+            output.warning("--no-proposed/--no-updates only apply to Ubuntu and are ignored");
+        }
+
+        // Found before the run so a missing Volatility3 does not waste one
+        let vol3_dir = options
+            .install_vol3
+            .then(vol3::linux_symbol_dir)
+            .transpose()?;
+        if options.sign {
+            sign::check_key(options.gpg_key.as_deref())?;
+        }
+
+        // Determine output directory
+        let output_path = match output_dir {
+            Some(dir) => PathBuf::from(dir),
+            None => std::env::current_dir().context("Failed to get current directory")?,
+        };
+
+        // Ensure output directory exists
+        std::fs::create_dir_all(&output_path).context("Failed to create output directory")?;
+
+        // Generate symbol filename
+        let symbol_path = output_path.join(symbol_filename(kernel, &distro_version, arch));
+
+        // Check if symbol already exists
+        let overwritten = symbol_path.exists() && options.if_exists == IfExists::Overwrite;
+        if overwritten {
+            output.warning(&format!(
+                "Overwriting existing symbol file: {}",
+                symbol_path.display()
+            ));
+        } else if symbol_path.exists() {
+            let status = if options.if_exists == IfExists::Skip {
+                output.info(&format!(
+                    "Skipping existing symbol file: {}",
+                    symbol_path.display()
+                ));
+                "skipped"
+            } else {
+                output.warning(&format!(
+                    "Symbol file already exists: {}",
+                    symbol_path.display()
+                ));
+                "exists"
+            };
+            if output.is_json() {
+                output.result(JsonResult {
+                    success: true,
+                    data: Some(ExistingSymbol {
+                        already_exists: true,
+                        status,
+                        kernel_version: kernel.to_string(),
+                        distro: distro.display_name().to_string(),
+                        distro_version: distro_version.version.clone(),
+                        symbol_file: symbol_path.to_string_lossy().to_string(),
+                        file_size: std::fs::metadata(&symbol_path)
+                            .context("Failed to get file metadata")?
+                            .len(),
+                        sha256: blocking({
+                            let symbol_path = symbol_path.clone();
+                            move || sha256_file(&symbol_path)
+                        })
+                        .await?,
+                    }),
+                    error: None,
+                });
+            }
+            let result = GenerationResult::for_file(
+                &job_id,
+                kernel,
+                &distro_version,
+                &symbol_path,
+                options.tag.clone(),
+            )?;
+            return Ok(Generated::Existing(Box::new(result)));
+        }
+
+        // Converter arguments, a user-supplied vmlinux or borrowed types change
+        // the output, so such files are neither reused nor shared
+        let cache = self
+            .config
+            .cache_path()
+            .filter(|_| {
+                options.converter_args.is_empty()
+                    && from_repositories
+                    && options.delta_from.is_none()
+            })
+            .map(Cache::new);
+        if let Some(cache) = cache.as_ref().filter(|_| !options.no_cache && !overwritten) {
+            let name = symbol_filename(kernel, &distro_version, arch);
+            let fetched = blocking({
+                let (cache, name, symbol_path) = (cache.clone(), name.clone(), symbol_path.clone());
+                move || cache.fetch(ISF_NAMESPACE, &name, &symbol_path)
+            })
+            .await;
+            match fetched {
+                Ok(true) => {
+                    output.success(&format!(
+                        "Reused cached symbol file {}",
+                        cache.entry_path(ISF_NAMESPACE, &name).display()
+                    ));
+                    rewrite_isf(&symbol_path, kernel, &distro_version, None, options, output)
+                        .await?;
+                    self.post_process(&symbol_path, kernel, &distro_version, options, output)
+                        .await?;
+                    let stored_at = self.store(&symbol_path, output).await?;
+                    let installed_at =
+                        install_vol3(&symbol_path, vol3_dir.as_deref(), output).await?;
+                    let uploaded_to = self.upload(&symbol_path, output).await?;
+                    let mut result = GenerationResult {
+                        from_cache: true,
+                        stored_at,
+                        installed_at,
+                        uploaded_to,
+                        ..GenerationResult::for_file(
+                            &job_id,
+                            kernel,
+                            &distro_version,
+                            &symbol_path,
+                            options.tag.clone(),
+                        )?
+                    };
+                    if options.manifest {
+                        result.manifest =
+                            Some(write_manifest(&symbol_path, &result, arch, output).await?);
+                    }
+                    result.signatures = sign_artifacts(&symbol_path, options, output).await?;
+                    if output.is_json() {
+                        output.result(JsonResult {
+                            success: true,
+                            data: Some(&result),
+                            error: None,
+                        });
+                    }
+                    return Ok(Generated::Created(Box::new(result)));
+                }
+                Ok(false) => {}
+                Err(e) => output.warning(&format!("Symbol cache lookup failed: {:#}", e)),
+            }
+        }
+
+        // Every attempt appends to the same log, renamed below if a fallback release wins
+        let log_path = sidecar_path(&symbol_path, LOG_SUFFIX);
+        if options.log_file {
+            std::fs::write(&log_path, b"").context("Failed to create log file")?;
+        }
+
+        // Bind-mounting NFS/SMB shares often fails or hits root squash, so the
+        // container writes to a local directory and the results are copied over
+        let scratch = match netfs::network_fs_type(&output_path) {
+            Some(fs_type) if options.transfer == OutputTransfer::Bind => {
+                let scratch = Scratch::create(&job_id)?;
+                output.warning(&format!(
+                    "{} is on a network filesystem ({}); generating in {} and copying the result over",
+                    output_path.display(),
+                    fs_type,
+                    scratch.path().display()
+                ));
+                Some(scratch)
+            }
+            // With --force the old file stays until a new one has been generated
+            _ if overwritten => Some(Scratch::within(&output_path, &job_id)?),
+            _ => None,
+        };
+        let work_path = scratch
+            .as_ref()
+            .map_or(output_path.as_path(), Scratch::path);
+
+        let mut candidates = vec![distro_version.clone()];
+        // Fallback releases would need images of their own
+        if !options.no_fallback && from_repositories && options.image.is_none() {
+            candidates.extend(fallback_versions(&distro_version));
+        }
+
+        let mut generated = None;
+        for (i, candidate) in candidates.iter().enumerate() {
+            if i > 0 {
+                output.warning(&format!(
+                    "Debug symbols for {} not found in {} {}, retrying with {}",
+                    kernel,
+                    distro.display_name(),
+                    candidates[i - 1].version,
+                    candidate.version
+                ));
+            }
+
+            let log_file = options
+                .log_file
+                .then(|| log_path.clone())
+                .or_else(|| self.jobs.as_ref().map(|jobs| jobs.log_path(&job_id)));
+            let attempt = self
+                .run_attempt(
+                    kernel,
+                    candidate,
+                    work_path,
+                    &output_path,
+                    &job_id,
+                    log_file,
+                    options,
+                    output,
+                )
+                .await?;
+            if attempt.exit_code == EXIT_DEBUGINFO_NOT_FOUND && i + 1 < candidates.len() {
+                continue;
+            }
+            if attempt.timed_out {
+                for failure in &attempt.known_failures {
+                    output.error(&format!("{} (log: {})", failure.cause, failure.line));
+                    output.info(&format!("Hint: {}", failure.hint));
+                }
+                return Err(TimedOut {
+                    limit: options.timeout.unwrap_or_default(),
+                    step: attempt.last_step,
+                }
+                .into());
+            }
+            if attempt.exit_code != 0 {
+                if attempt.clock_skew {
+                    output.error(
+                        "Signatures or certificates were rejected as not valid yet: the host clock \
+                         (which containers share) looks wrong. Fix the host time, e.g. \
+                         `timedatectl set-ntp true`, or pass --fix-time to ignore apt Release dates",
+                    );
+                }
+                match &attempt.last_step {
+                    Some(step) => output.error(&format!(
+                        "Container exited with code {} during step {}",
+                        attempt.exit_code, step
+                    )),
+                    None => {
+                        output.error(&format!("Container exited with code {}", attempt.exit_code))
+                    }
+                }
+                for failure in &attempt.known_failures {
+                    output.error(&format!("{} (log: {})", failure.cause, failure.line));
+                    output.info(&format!("Hint: {}", failure.hint));
+                }
+                if !attempt.step_durations.is_empty() {
+                    output.info(&format!(
+                        "Step times: {}",
+                        step_times(&attempt.step_durations)
+                    ));
+                }
+                return Err(anyhow!("Symbol generation failed"));
+            }
+
+            generated = Some((candidate, attempt));
+            break;
+        }
+        let (used_version, attempt) =
+            generated.ok_or_else(|| anyhow!("Symbol generation failed"))?;
+
+        let fallback_from = if used_version.version != distro_version.version {
+            output.success(&format!(
+                "Debug symbols found in {} {} (fallback from {})",
+                distro.display_name(),
+                used_version.version,
+                distro_version.version
+            ));
+            Some(distro_version.version.clone())
+        } else {
+            None
+        };
+        let symbol_path = output_path.join(symbol_filename(kernel, used_version, arch));
+        if let Some(scratch) = scratch {
+            // Checked here, as a file replaced with --force would still be in place
+            let generated = scratch
+                .path()
+                .join(symbol_filename(kernel, used_version, arch));
+            if !generated.exists() && !generated.with_extension("").exists() {
+                return Err(anyhow!("Symbol file was not created"));
+            }
+            output.progress(&format!("Moving results to {}...", output_path.display()));
+            let destination = output_path.clone();
+            blocking(move || scratch.deliver(&destination)).await?;
+        }
+        if options.log_file && fallback_from.is_some() {
+            std::fs::rename(&log_path, sidecar_path(&symbol_path, LOG_SUFFIX))
+                .context("Failed to rename log file")?;
+        }
+
+        if options.compression == Compression::Host {
+            let raw_path = symbol_path.with_extension("");
+            if !raw_path.exists() {
+                return Err(anyhow!("Symbol file was not created"));
+            }
+            output.progress("Compressing symbol file on the host...");
+            blocking(move || isf::compress_file(&raw_path)).await?;
+        }
+
+        // Verify symbol file was created
+        if !symbol_path.exists() {
+            return Err(anyhow!("Symbol file was not created"));
+        }
+
+        if let (Some(base), Some(banner)) = (&options.delta_from, &options.banner) {
+            output.progress("Combining the new symbols with the base types...");
+            let stats = blocking({
+                let (base, symbol_path, banner) =
+                    (base.clone(), symbol_path.clone(), banner.clone());
+                move || isf::apply_delta_file(&base, &symbol_path, &banner)
+            })
+            .await?;
+            output.info(&format!(
+                "{} symbols: {} moved, {} added, {} removed",
+                stats.symbols, stats.moved, stats.added, stats.removed
+            ));
+            if (stats.added + stats.removed) * DELTA_CHANGE_LIMIT > stats.symbols {
+                output.warning(
+                    "Many symbols were added or removed; the kernels may not share an ABI, \
+                     so types from the base ISF could be wrong. Consider a full generation",
+                );
+            }
+        }
+
+        // Cache the full file; --minimal is applied on every reuse
+        if let Some(cache) = &cache {
+            let name = symbol_filename(kernel, used_version, arch);
+            let stored = blocking({
+                let (cache, symbol_path) = (cache.clone(), symbol_path.clone());
+                move || cache.store(ISF_NAMESPACE, &name, &symbol_path)
+            })
+            .await;
+            if let Err(e) = stored {
+                output.warning(&format!("Failed to cache symbol file: {:#}", e));
+            }
+        }
+
+        match attempt.system_map_source.as_deref() {
+            Some("package") => output.info("System.map was unpacked from the kernel package"),
+            Some("symtab") => output
+                .warning("No System.map was published; derived it from the vmlinux symbol table"),
+            Some(_) => {}
+            None if options.delta_from.is_none() => output.warning(
+                "No System.map could be found or derived; symbols come from vmlinux alone",
+            ),
+            None => {}
+        }
+        rewrite_isf(
+            &symbol_path,
+            kernel,
+            used_version,
+            Some(&attempt),
+            options,
+            output,
+        )
+        .await?;
+        self.post_process(&symbol_path, kernel, used_version, options, output)
+            .await?;
+        let stored_at = self.store(&symbol_path, output).await?;
+        let installed_at = install_vol3(&symbol_path, vol3_dir.as_deref(), output).await?;
+        let uploaded_to = self.upload(&symbol_path, output).await?;
+
+        let file_size = std::fs::metadata(&symbol_path)
+            .context("Failed to get file metadata")?
+            .len();
+
+        output.success(&format!(
+            "Symbol file created: {} ({} bytes)",
+            symbol_path.display(),
+            file_size
+        ));
+        if !attempt.step_durations.is_empty() {
+            output.info(&format!(
+                "Step times: {}",
+                step_times(&attempt.step_durations)
+            ));
+        }
+
+        if let Some(history_path) = self.config.history_path() {
+            let entry = HistoryEntry {
+                finished_at: chrono::Utc::now().to_rfc3339(),
+                distro: distro.display_name().to_string(),
+                distro_version: used_version.version.clone(),
+                kernel: kernel.to_string(),
+                arch: arch.name().to_string(),
+                duration_secs: started.elapsed().as_secs(),
+                debuginfo_bytes: attempt.debuginfo_bytes,
+                symbol_bytes: file_size,
+                symbol_file: Some(symbol_filename(kernel, used_version, arch)),
+                tag: options.tag.clone(),
+            };
+            if let Err(e) = blocking(move || history::append(&history_path, &entry)).await {
+                output.warning(&format!("Failed to record run history: {:#}", e));
+            }
+        }
+
+        let mut result = GenerationResult {
+            job_id,
+            kernel_version: kernel.to_string(),
+            distro: distro.display_name().to_string(),
+            distro_version: used_version.version.clone(),
+            symbol_file: symbol_path.to_string_lossy().to_string(),
+            file_size,
+            tag: options.tag.clone(),
+            dwarf2json_version: attempt.dwarf2json_version,
+            dwarf2json_sha256: attempt.dwarf2json_sha256,
+            vmlinux: attempt.vmlinux,
+            system_map: attempt.system_map,
+            system_map_source: attempt.system_map_source,
+            source_package: attempt.source_package,
+            fallback_from,
+            step_durations: attempt.step_durations,
+            from_cache: false,
+            stored_at,
+            installed_at,
+            uploaded_to,
+            manifest: None,
+            signatures: Vec::new(),
+            overwritten,
+        };
+        if options.manifest {
+            result.manifest = Some(write_manifest(&symbol_path, &result, arch, output).await?);
+        }
+        result.signatures = sign_artifacts(&symbol_path, options, output).await?;
+
+        // Output JSON result if in JSON mode
+        if output.is_json() {
+            output.result(JsonResult {
+                success: true,
+                data: Some(&result),
+                error: None,
+            });
+        }
+
+        Ok(Generated::Created(Box::new(result)))
+    }
+
+    /// Generate symbols for several kernels of one release, running up to `jobs`
+    /// containers at once.
+    ///
+    /// With more than one job, each run reports through output labeled with its
+    /// kernel and prints step changes rather than a spinner, so overlapping runs
+    /// stay readable. Results come back in the order of `kernels`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn generate_batch(
+        &self,
+        kernels: &[String],
+        distro: &str,
+        version: &str,
+        output_dir: Option<&str>,
+        options: &GenerateOptions,
+        jobs: usize,
+        output: &Output,
+    ) -> Vec<Result<Generated>> {
+        let semaphore = Semaphore::new(jobs.max(1));
+        let started = Cell::new(0);
+        let finished = Cell::new(0);
+        let total = kernels.len();
+
+        // Queue every kernel up front so an interrupted batch shows what never ran
+        let job_ids: Vec<String> = kernels
+            .iter()
+            .map(|kernel| {
+                self.queue_job(kernel, distro, version, output_dir, options, None, output)
+            })
+            .collect();
+
+        let runs = kernels.iter().zip(&job_ids).map(|(kernel, job_id)| {
+            let (semaphore, started, finished) = (&semaphore, &started, &finished);
+            async move {
+                let _permit = semaphore
+                    .acquire()
+                    .await
+                    .expect("semaphore is never closed");
+                started.set(started.get() + 1);
+                output.progress(&format!("[{}/{}] {}", started.get(), total, kernel));
+
+                let job_output = if jobs > 1 {
+                    output.labeled(kernel)
+                } else {
+                    output.clone()
+                };
+                let result = self
+                    .generate_job(
+                        job_id,
+                        kernel,
+                        distro,
+                        version,
+                        output_dir,
+                        options,
+                        &job_output,
+                    )
+                    .await;
+
+                finished.set(finished.get() + 1);
+                if jobs > 1 {
+                    let outcome = if result.is_ok() { "done" } else { "failed" };
+                    output.info(&format!(
+                        "{} {} ({}/{} finished)",
+                        kernel,
+                        outcome,
+                        finished.get(),
+                        total
+                    ));
+                }
+                result
+            }
+        });
+        join_all(runs).await
+    }
+
+    /// Move a finished symbol file into the configured store, returning where it went
+    async fn store(&self, symbol_path: &Path, output: &Output) -> Result<Option<String>> {
+        let Some(root) = self.config.store_dir.clone() else {
+            return Ok(None);
+        };
+        let symbol_path = symbol_path.to_path_buf();
+        let stored = blocking(move || store::ingest(&root, &symbol_path)).await?;
+        output.info(&format!("Stored as {}", stored.display()));
+        Ok(Some(stored.to_string_lossy().to_string()))
+    }
+
+    /// Upload a finished symbol file and its checksum to the configured destination
+    async fn upload(&self, symbol_path: &Path, output: &Output) -> Result<Option<String>> {
+        let Some((destination, client)) = &self.upload else {
+            return Ok(None);
+        };
+        output.progress("Uploading symbol file...");
+        let uploaded = client
+            .upload_with_checksum(destination, symbol_path)
+            .await?;
+        output.info(&format!("Uploaded as {}", uploaded));
+        Ok(Some(uploaded))
+    }
+
+    /// Run the configured and requested post-processing hooks on a finished
+    /// symbol file. The cache keeps the unprocessed file, so hooks apply on reuse too.
+    async fn post_process(
+        &self,
+        symbol_path: &Path,
+        kernel: &str,
+        version: &DistroVersion,
+        options: &GenerateOptions,
+        output: &Output,
+    ) -> Result<()> {
+        let commands: Vec<String> = self
+            .config
+            .post_process
+            .iter()
+            .chain(&options.post_process)
+            .cloned()
+            .collect();
+        if commands.is_empty() {
+            return Ok(());
+        }
+
+        output.progress(&format!(
+            "Running {} post-processing hook(s)...",
+            commands.len()
+        ));
+        let (symbol_path, kernel, distro_version, arch) = (
+            symbol_path.to_path_buf(),
+            kernel.to_string(),
+            version.version.clone(),
+            options.arch.name(),
+        );
+        let distro = version.distro.name();
+        blocking(move || {
+            postprocess::run(
+                &symbol_path,
+                &commands,
+                &HookContext {
+                    kernel: &kernel,
+                    distro,
+                    distro_version: &distro_version,
+                    arch,
+                },
+            )
+        })
+        .await
+    }
+
+    /// Find the flavored kernel name for a kernel given without its flavor suffix
+    async fn resolve_flavor(
+        &self,
+        kernel: &str,
+        distro_version: &DistroVersion,
+        job_id: &str,
+        options: &GenerateOptions,
+        output: &Output,
+    ) -> Result<String> {
+        let distro = distro_version.distro;
+        output.info(&format!(
+            "Kernel {} has no flavor suffix, probing the repositories...",
+            kernel
+        ));
+
+        let script = render_flavor_probe(
+            distro_version,
+            kernel,
+            &self.probe_options(distro_version, options),
+        );
+        let tracker = self
+            .run_probe(&script, kernel, distro_version, job_id, options, output)
+            .await
+            .context("Flavor probe failed")?;
+        let flavors: Vec<&str> = tracker
+            .result(RESULT_FLAVORS)
+            .map(|list| list.split(',').collect())
+            .unwrap_or_default();
+        let default = distro.default_flavor(options.arch);
+        let flavor = match flavors.as_slice() {
+            [] => {
+                return Err(anyhow!(
+                    "No debug symbol packages found for kernel {} in {} {}",
+                    kernel,
+                    distro.display_name(),
+                    distro_version.version
+                ))
+            }
+            [only] => *only,
+            _ if flavors.contains(&default) => default,
+            _ => {
+                return Err(anyhow!(
+                    "Kernel {} is published with several flavors ({}); pass the full name, e.g. -k {}-{}",
+                    kernel,
+                    flavors.join(", "),
+                    kernel,
+                    flavors[0]
+                ))
+            }
+        };
+
+        if flavors.len() > 1 {
+            output.info(&format!("Available flavors: {}", flavors.join(", ")));
+        }
+        let flavored = format!("{}-{}", kernel, flavor);
+        output.success(&format!("Using kernel {}", flavored));
+        Ok(flavored)
+    }
+
+    /// Kernels whose debug symbols a release's repositories hold, with their build times.
+    ///
+    /// The build time is a Unix timestamp, or 0 where the repository index has none.
+    pub async fn list_published_kernels(
+        &self,
+        distro_version: &DistroVersion,
+        flavor: &str,
+        options: &GenerateOptions,
+        output: &Output,
+    ) -> Result<Vec<(String, i64)>> {
+        output.info(&format!(
+            "Listing kernels in the {} {} repositories...",
+            distro_version.distro.display_name(),
+            distro_version.version
+        ));
+        let job_id = uuid::Uuid::new_v4().to_string();
+        let script = render_kernel_listing(
+            distro_version,
+            flavor,
+            &self.probe_options(distro_version, options),
+        );
+        let tracker = self
+            .run_probe(&script, "", distro_version, &job_id, options, output)
+            .await
+            .context("Kernel listing failed")?;
+
+        Ok(tracker
+            .results(RESULT_KERNEL)
+            .into_iter()
+            .filter_map(|line| {
+                let (release, built) = line.split_once(' ')?;
+                Some((release.to_string(), built.parse().unwrap_or(0)))
+            })
+            .collect())
+    }
+
+    /// Script options for probes that only query the repositories
+    fn probe_options(
+        &self,
+        distro_version: &DistroVersion,
+        options: &GenerateOptions,
+    ) -> ScriptOptions {
+        ScriptOptions {
+            arch: options.arch,
+            allow_unauthenticated: options.allow_unauthenticated,
+            no_proposed: options.no_proposed,
+            no_updates: options.no_updates,
+            overrides: self.config.script_overrides(distro_version.distro),
+            fix_time: options.fix_time,
+            ca_certs: self.ca_certs.clone(),
+            pre_script: self.pre_script.clone(),
+            proxy: self.proxy.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Run a script that produces only results, not files, and collect its markers
+    async fn run_probe(
+        &self,
+        script: &RenderedScript,
+        kernel: &str,
+        distro_version: &DistroVersion,
+        job_id: &str,
+        options: &GenerateOptions,
+        output: &Output,
+    ) -> Result<StepTracker> {
+        self.docker
+            .pull_image(&distro_version.docker_image, options.arch.platform())
+            .await?;
+
+        let tracker = RefCell::new(StepTracker::new(script.steps.clone()));
+        let clock_skew = Cell::new(false);
+        let scratch = std::env::temp_dir();
+
+        let run = self
+            .docker
+            .run_container(
+                &distro_version.docker_image,
+                &script.text,
+                &scratch,
+                &RunOptions {
+                    platform: options.arch.platform(),
+                    transfer: OutputTransfer::Copy,
+                    remove: RemovePolicy::Always,
+                    log_file: None,
+                    labels: run_labels(job_id, kernel, distro_version, options),
+                    inputs: Vec::new(),
+                    timeout: options.timeout,
+                    env: self.proxy.env(),
+                    diagnostics_dir: None,
+                },
+                |log, time| {
+                    tracker.borrow_mut().observe(log, time);
+                    clock_skew.set(clock_skew.get() || shows_clock_skew(log));
+                },
+            )
+            .await?;
+        if run.timed_out {
+            return Err(TimedOut {
+                limit: options.timeout.unwrap_or_default(),
+                step: tracker.borrow().current().map(str::to_string),
+            }
+            .into());
+        }
+        if run.exit_code != 0 {
+            if clock_skew.get() {
+                output.error("Repository signatures were rejected as not valid yet; check the host clock or pass --fix-time");
+            }
+            return Err(anyhow!("Container exited with code {}", run.exit_code));
+        }
+        Ok(tracker.into_inner())
+    }
+
+    /// The local image `symgen build-images` made for a release, if there is one,
+    /// and whether its dwarf2json is the one this run would fetch
+    async fn prebaked(
+        &self,
+        distro_version: &DistroVersion,
+        overrides: &ScriptOverrides,
+        arch: Arch,
+    ) -> Option<(String, bool)> {
+        let image = prebaked_image(distro_version, arch);
+        let labels = self.docker.image_labels(&image).await?;
+        labels.get(PREBAKED_LABEL)?;
+        let dwarf2json_baked =
+            labels.get(DWARF2JSON_LABEL) == Some(&dwarf2json_source(arch, overrides));
+        Some((image, dwarf2json_baked))
+    }
+
+    /// Build the prebaked image for a release (`symgen build-images`): run the
+    /// release's tool and repository setup plus the dwarf2json download in a
+    /// container, then save that container as [`prebaked_image`].
+    ///
+    /// Returns the name of the image.
+    pub async fn build_image(
+        &self,
+        distro_version: &DistroVersion,
+        options: &GenerateOptions,
+        output: &Output,
+    ) -> Result<String> {
+        let arch = options.arch;
+        let image = prebaked_image(distro_version, arch);
+        let overrides = configured_overrides(&self.config, distro_version, arch, output).await;
+        let dwarf2json = dwarf2json_source(arch, &overrides);
+        let script = render_bake_script(
+            distro_version,
+            &ScriptOptions {
+                overrides,
+                ..self.probe_options(distro_version, options)
+            },
+        );
+
+        output.progress(&format!("Pulling image {}...", distro_version.docker_image));
+        self.docker
+            .pull_image(&distro_version.docker_image, arch.platform())
+            .await?;
+
+        let job_id = uuid::Uuid::new_v4().to_string();
+        let tracker = RefCell::new(StepTracker::new(script.steps.clone()));
+        let run = self
+            .docker
+            .run_container(
+                &distro_version.docker_image,
+                &script.text,
+                &std::env::temp_dir(),
+                &RunOptions {
+                    platform: arch.platform(),
+                    transfer: OutputTransfer::Copy,
+                    remove: RemovePolicy::Never,
+                    log_file: None,
+                    labels: run_labels(&job_id, "", distro_version, options),
+                    inputs: Vec::new(),
+                    timeout: options.timeout,
+                    env: self.proxy.env(),
+                    diagnostics_dir: None,
+                },
+                |log, time| {
+                    if let Some(Marker::Step(step)) = parse_marker(log) {
+                        output.progress(&format!("Baking {}: {}", image, step));
+                    }
+                    tracker.borrow_mut().observe(log, time);
+                },
+            )
+            .await?;
+
+        let committed = if run.timed_out {
+            Err(TimedOut {
+                limit: options.timeout.unwrap_or_default(),
+                step: tracker.borrow().current().map(str::to_string),
+            }
+            .into())
+        } else if run.exit_code != 0 {
+            Err(anyhow!(
+                "Image setup failed in step {} (exit code {})",
+                tracker.borrow().current().unwrap_or("unknown"),
+                run.exit_code
+            ))
+        } else {
+            let labels = HashMap::from([
+                (
+                    PREBAKED_LABEL.to_string(),
+                    format!(
+                        "{} {}",
+                        distro_version.distro.name(),
+                        distro_version.version
+                    ),
+                ),
+                (DWARF2JSON_LABEL.to_string(), dwarf2json),
+            ]);
+            self.docker.commit(&run.container_id, &image, labels).await
+        };
+        if let Err(e) = self.docker.remove(&run.container_id, true).await {
+            output.warning(&format!("{:#}", e));
+        }
+        committed.map(|()| image)
+    }
+
+    /// Host-cached dwarf2json to mount into the container, when one can be used.
+    ///
+    /// Source builds and remote daemons (which cannot see host paths) keep
+    /// setting dwarf2json up inside the container, as does any cache failure.
+    async fn cached_dwarf2json(
+        &self,
+        overrides: &ScriptOverrides,
+        options: &GenerateOptions,
+        output: &Output,
+    ) -> Option<PathBuf> {
+        if options.transfer != OutputTransfer::Bind {
+            return None;
+        }
+        let url = match overrides.dwarf2json_url.as_deref() {
+            Some(url) => url,
+            None if options.arch.has_dwarf2json_release() => DWARF2JSON_URL,
+            None => return None,
+        };
+        let cache = Cache::new(self.config.cache_path()?);
+
+        let expected_sha256 = overrides.dwarf2json_sha256.as_deref();
+        match dwarf2json::cached_binary(&cache, url, options.arch, expected_sha256).await {
+            Ok(path) => Some(path),
+            Err(e) => {
+                output.warning(&format!(
+                    "Could not cache dwarf2json on the host, downloading it in the container: {:#}",
+                    e
+                ));
+                None
+            }
+        }
+    }
+
+    /// Pull the image and run the generation script for one release.
+    ///
+    /// The container writes into `work_path`, a scratch directory for network
+    /// shares and `--force`; failure bundles go to `output_path` so they outlive it.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_attempt(
+        &self,
+        kernel: &str,
+        distro_version: &DistroVersion,
+        work_path: &Path,
+        output_path: &Path,
+        job_id: &str,
+        log_file: Option<PathBuf>,
+        options: &GenerateOptions,
+        output: &Output,
+    ) -> Result<Attempt> {
+        let arch = options.arch;
+        let template = site_template(&self.config, distro_version, options, output)?;
+        let overrides = configured_overrides(&self.config, distro_version, arch, output).await;
+
+        // An image from `symgen build-images` saves the tool setup, unless --image names another
+        let prebaked = match &options.image {
+            Some(_) => None,
+            None => self.prebaked(distro_version, &overrides, arch).await,
+        };
+        let prebaked_version = prebaked.as_ref().map(|(image, _)| {
+            output.info(&format!("Using prebaked image {}", image));
+            DistroVersion {
+                docker_image: image.clone(),
+                ..distro_version.clone()
+            }
+        });
+        let distro_version = prebaked_version.as_ref().unwrap_or(distro_version);
+        let dwarf2json_baked = prebaked.as_ref().is_some_and(|(_, baked)| *baked);
+
+        // Pull Docker image
+        output.progress(&format!("Pulling image {}...", distro_version.docker_image));
+        self.docker
+            .pull_image(&distro_version.docker_image, arch.platform())
+            .await?;
+        output.success("Image ready");
+
+        // Without a URL, the default release is downloaded or the same version built from source
+        let dwarf2json_version = match overrides.dwarf2json_url.as_deref() {
+            Some(url) => dwarf2json::release_tag(url).map(str::to_string),
+            None => Some(dwarf2json::PINNED_VERSION.to_string()),
+        };
+        let dwarf2json = if dwarf2json_baked {
+            None
+        } else {
+            self.cached_dwarf2json(&overrides, options, output).await
+        };
+        let mut inputs = options.acquisition.resolve().inputs();
+        if let Some(path) = &dwarf2json {
+            inputs.push((path.clone(), DWARF2JSON_INPUT_PATH.to_string()));
+        }
+
+        // Generate shell script
+        let script = render_script(
+            distro_version,
+            kernel,
+            &ScriptOptions {
+                pre_script: self.pre_script.clone(),
+                post_script: self.post_script.clone(),
+                proxy: self.proxy.clone(),
+                prebaked: prebaked.is_some(),
+                dwarf2json_baked,
+                ..generation_script_options(
+                    kernel,
+                    distro_version,
+                    overrides,
+                    dwarf2json.is_some(),
+                    self.ca_certs.clone(),
+                    template,
+                    options,
+                )
+            },
+        );
+
+        // Create progress bar for non-JSON mode; concurrent runs print step changes instead
+        let progress = if !output.is_json() && !output.is_labeled() && !output.is_quiet() {
+            let pb = ProgressBar::new_spinner();
+            pb.set_style(
+                ProgressStyle::default_spinner()
+                    .template("{spinner:.cyan} {msg}")
+                    .unwrap(),
+            );
+            pb.enable_steady_tick(Duration::from_millis(100));
+            Some(pb)
+        } else {
+            None
+        };
+
+        // Run container
+        output.progress("Running symbol generation in container...");
+        let tracker = RefCell::new(StepTracker::new(script.steps));
+        let clock_skew = Cell::new(false);
+        let known_failures = RefCell::new(Vec::<KnownFailure>::new());
+
+        let run = self
+            .docker
+            .run_container(
+                &distro_version.docker_image,
+                &script.text,
+                work_path,
+                &RunOptions {
+                    platform: arch.platform(),
+                    transfer: options.transfer,
+                    remove: options.remove,
+                    log_file,
+                    labels: run_labels(job_id, kernel, distro_version, options),
+                    inputs,
+                    timeout: options.timeout,
+                    env: self.proxy.env(),
+                    diagnostics_dir: (work_path != output_path).then(|| output_path.to_path_buf()),
+                },
+                |log, time| {
+                    // Step markers drive progress; `>>>` lines add detail within a step
+                    let trimmed = log.trim();
+                    clock_skew.set(clock_skew.get() || shows_clock_skew(trimmed));
+                    if let Some(failure) = self.failures.classify(trimmed) {
+                        let mut known = known_failures.borrow_mut();
+                        if !known.iter().any(|k| k.cause == failure.cause) {
+                            known.push(failure);
+                        }
+                    }
+                    let mut tracker = tracker.borrow_mut();
+                    if tracker.observe(trimmed, time) {
+                        if let Some(label) = tracker.label() {
+                            if let Some(pb) = &progress {
+                                pb.set_message(label.clone());
+                            }
+                            if output.is_json() || output.is_labeled() {
+                                output.progress(&label);
+                            }
+                        }
+                    } else if trimmed.starts_with(">>>") || trimmed.starts_with("===") {
+                        if let Some(pb) = &progress {
+                            match tracker.label() {
+                                Some(label) => pb.set_message(format!("{} {}", label, trimmed)),
+                                None => pb.set_message(trimmed.to_string()),
+                            }
+                        }
+                        if output.is_json() {
+                            output.progress(trimmed);
+                        }
+                    }
+                },
+            )
+            .await?;
+
+        // Clear progress bar
+        if let Some(pb) = progress {
+            pb.finish_and_clear();
+        }
+
+        if let Some(e) = &run.cleanup_error {
+            output.warning(&format!("Container cleanup failed: {}", e));
+        }
+        if run.kept {
+            output.info(&format!(
+                "Container kept: {} ({})",
+                run.container_name,
+                &run.container_id[..run.container_id.len().min(12)]
+            ));
+            if run.exit_code != 0 {
+                // An exited container cannot be exec'd into; a snapshot of it can be entered
+                let command = self.docker.command();
+                output.info(&format!(
+                    "Debug it with: {} commit {} symgen-debug && {} run --rm -it symgen-debug bash \
+                     (the script is {}/{}; `symgen clean` removes the container)",
+                    command, run.container_name, command, SCRIPT_DIR, SCRIPT_NAME
+                ));
+            }
+        }
+        if let Some(dir) = &run.failure_dir {
+            output.info(&format!("Failure diagnostics saved to {}", dir.display()));
+        }
+
+        let tracker = tracker.into_inner();
+        Ok(Attempt {
+            exit_code: run.exit_code,
+            timed_out: run.timed_out,
+            clock_skew: clock_skew.get(),
+            known_failures: known_failures.into_inner(),
+            last_step: tracker.current().map(str::to_string),
+            dwarf2json_version,
+            dwarf2json_sha256: tracker.result(RESULT_DWARF2JSON_SHA256).map(str::to_string),
+            vmlinux: tracker.result(RESULT_VMLINUX).map(str::to_string),
+            system_map: tracker.result(RESULT_SYSTEM_MAP).map(str::to_string),
+            system_map_source: tracker.result(RESULT_SYSTEM_MAP_SOURCE).map(str::to_string),
+            source_package: tracker.result(RESULT_SOURCE_PACKAGE).map(str::to_string),
+            debuginfo_bytes: tracker
+                .result(RESULT_DEBUGINFO_BYTES)
+                .and_then(|v| v.parse().ok()),
+            step_durations: tracker.durations(),
+        })
+    }
+}
+
+/// The release to generate from, after checking that it can build the kernel
+fn resolve_release(
+    kernel: &str,
+    distro_str: &str,
+    version: &str,
+    options: &GenerateOptions,
+) -> Result<DistroVersion> {
+    let distro = Distro::from_str(distro_str)
+        .ok_or_else(|| anyhow!("Unknown distribution: {}", distro_str))?;
+    let mut distro_version = find_version(distro, version).ok_or_else(|| {
+        anyhow!(
+            "Unsupported version {} for {}",
+            version,
+            distro.display_name()
+        )
+    })?;
+    if let Some(image) = &options.image {
+        distro_version.docker_image = image.clone();
+    }
+
+    let arch = options.arch;
+    if !distro.supports_arch(arch) {
+        return Err(anyhow!(
+            "{} is not available for {}",
+            distro.display_name(),
+            arch.name()
+        ));
+    }
+    if !distro.uses_apt() {
+        check_rpm_kernel(kernel, arch).map_err(|e| anyhow!(e))?;
+    }
+    if distro == Distro::Wsl && wsl_release(kernel).is_none() {
+        return Err(anyhow!(
+            "{} is not a WSL2 kernel release (expected e.g. 5.15.153.1-microsoft-standard-WSL2)",
+            kernel
+        ));
+    }
+    Ok(distro_version)
+}
+
+/// The configured script overrides for a release, with a configured
+/// dwarf2json version turned into its download URL
+async fn configured_overrides(
+    config: &Config,
+    distro_version: &DistroVersion,
+    arch: Arch,
+    output: &Output,
+) -> ScriptOverrides {
+    let mut overrides = config.script_overrides(distro_version.distro);
+    if let Some(version) = config.dwarf2json_version.as_deref() {
+        if overrides.dwarf2json_url.is_none() && arch.has_dwarf2json_release() {
+            let cache = config.cache_path().map(Cache::new);
+            let tag = dwarf2json::resolve_version(cache.as_ref(), version, output).await;
+            overrides.dwarf2json_url = Some(dwarf2json::release_url(&tag, arch));
+        }
+    }
+    overrides
+}
+
+/// The site's script template for the release, when the run would otherwise
+/// use the distribution's built-in repository steps
+fn site_template(
+    config: &Config,
+    distro_version: &DistroVersion,
+    options: &GenerateOptions,
+    output: &Output,
+) -> Result<Option<String>> {
+    if options.acquisition != AcquisitionSource::PackageManager || options.delta_from.is_some() {
+        return Ok(None);
+    }
+    let template = config.script_template(distro_version.distro)?;
+    if template.is_some() {
+        output.info(&format!(
+            "Using the site script template for {}",
+            distro_version.distro.display_name()
+        ));
+    }
+    Ok(template)
+}
+
+/// Local image `symgen build-images` makes for a release: `symgen/ubuntu:22.04`,
+/// with the architecture appended for anything but amd64
+pub fn prebaked_image(distro_version: &DistroVersion, arch: Arch) -> String {
+    let image = format!(
+        "symgen/{}:{}",
+        distro_version.distro.name(),
+        distro_version.version
+    );
+    match arch {
+        Arch::Amd64 => image,
+        _ => format!("{}-{}", image, arch.name()),
+    }
+}
+
+/// Script options for a full generation run
+fn generation_script_options(
+    kernel: &str,
+    distro_version: &DistroVersion,
+    overrides: ScriptOverrides,
+    dwarf2json_mounted: bool,
+    ca_certs: Vec<String>,
+    template: Option<String>,
+    options: &GenerateOptions,
+) -> ScriptOptions {
+    ScriptOptions {
+        arch: options.arch,
+        allow_unauthenticated: options.allow_unauthenticated,
+        no_proposed: options.no_proposed,
+        no_updates: options.no_updates,
+        overrides,
+        converter_args: options.converter_args.clone(),
+        compression: options.compression,
+        acquisition: options.acquisition.clone(),
+        fix_time: options.fix_time,
+        dwarf2json_mounted,
+        system_map_only: options.delta_from.is_some(),
+        package_version: package_version(kernel, distro_version.distro, options),
+        ca_certs,
+        template,
+        ..Default::default()
+    }
+}
+
+/// The script a generation run would execute, rendered without touching the
+/// container runtime so it can be run by hand, e.g. inside an offline network.
+///
+/// The script downloads dwarf2json itself instead of expecting the host-cached
+/// copy, and the kernel needs its flavor suffix since no probe can run.
+pub async fn render_generation_script(
+    config: &Config,
+    kernel: &str,
+    distro_str: &str,
+    version: &str,
+    options: &GenerateOptions,
+    output: &Output,
+) -> Result<(DistroVersion, RenderedScript)> {
+    let distro_version = resolve_release(kernel, distro_str, version, options)?;
+    if options.acquisition.resolve().searches_repositories()
+        && distro_version.distro.lacks_flavor(kernel)
+    {
+        return Err(anyhow!(
+            "Kernel {} has no flavor suffix; give the full name (e.g. -k {}-{}) to print its script",
+            kernel,
+            kernel,
+            distro_version.distro.default_flavor(options.arch)
+        ));
+    }
+    let template = site_template(config, &distro_version, options, output)?;
+    let overrides = configured_overrides(config, &distro_version, options.arch, output).await;
+    let (pre_script, post_script) = config.hook_scripts()?;
+    let script_options = ScriptOptions {
+        pre_script,
+        post_script,
+        proxy: ProxySettings::resolve(config.proxy.as_deref())?,
+        ..generation_script_options(
+            kernel,
+            &distro_version,
+            overrides,
+            false,
+            config.ca_cert_pems()?,
+            template,
+            options,
+        )
+    };
+    let script = render_script(&distro_version, kernel, &script_options);
+    Ok((distro_version, script))
+}
+
+/// Exact package version for an Ubuntu kernel whose upload is known
+fn package_version(kernel: &str, distro: Distro, options: &GenerateOptions) -> Option<String> {
+    let upload = options
+        .ubuntu_upload
+        .as_deref()
+        .filter(|_| distro == Distro::Ubuntu)?;
+    ubuntu_package_version(kernel, upload)
+}
+
+/// Write the checksum and manifest sidecars of a finished symbol file
+async fn write_manifest(
+    symbol_path: &Path,
+    result: &GenerationResult,
+    arch: Arch,
+    output: &Output,
+) -> Result<SymbolManifest> {
+    let manifest = SymbolManifest {
+        symbol_file: symbol_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        sha256: blocking({
+            let symbol_path = symbol_path.to_path_buf();
+            move || sha256_file(&symbol_path)
+        })
+        .await?,
+        size: result.file_size,
+        kernel: result.kernel_version.clone(),
+        distro: result.distro.clone(),
+        distro_version: result.distro_version.clone(),
+        arch: arch.name().to_string(),
+        tag: result.tag.clone(),
+        dwarf2json_version: result.dwarf2json_version.clone(),
+        dwarf2json_sha256: result.dwarf2json_sha256.clone(),
+        source_package: result.source_package.clone(),
+        system_map_source: result.system_map_source.clone(),
+        from_cache: result.from_cache,
+        symgen_version: env!("CARGO_PKG_VERSION").to_string(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    artifacts::write_manifest(symbol_path, &manifest)?;
+    output.info(&format!(
+        "Wrote {} and {}",
+        sidecar_path(symbol_path, artifacts::CHECKSUM_SUFFIX).display(),
+        sidecar_path(symbol_path, artifacts::MANIFEST_SUFFIX).display()
+    ));
+    Ok(manifest)
+}
+
+/// Sign a finished symbol file, and its manifest when one was written, with `--sign`
+async fn sign_artifacts(
+    symbol_path: &Path,
+    options: &GenerateOptions,
+    output: &Output,
+) -> Result<Vec<String>> {
+    if !options.sign {
+        return Ok(Vec::new());
+    }
+    let mut files = vec![symbol_path.to_path_buf()];
+    if options.manifest {
+        files.push(sidecar_path(symbol_path, artifacts::MANIFEST_SUFFIX));
+    }
+    let mut signatures = Vec::new();
+    for file in files {
+        let key = options.gpg_key.clone();
+        let signature = blocking(move || sign::detach_sign(&file, key.as_deref())).await?;
+        output.info(&format!("Signed {}", signature.display()));
+        signatures.push(signature.to_string_lossy().to_string());
+    }
+    Ok(signatures)
+}
+
+/// Copy a finished symbol file into the Volatility3 symbol directory, if one was resolved
+async fn install_vol3(
+    symbol_path: &Path,
+    dir: Option<&Path>,
+    output: &Output,
+) -> Result<Option<String>> {
+    let Some(dir) = dir else {
+        return Ok(None);
+    };
+    let (symbol_path, dir) = (symbol_path.to_path_buf(), dir.to_path_buf());
+    let installed = blocking(move || vol3::install(&symbol_path, &dir)).await?;
+    output.info(&format!(
+        "Installed for Volatility3 as {}",
+        installed.display()
+    ));
+    Ok(Some(installed.to_string_lossy().to_string()))
+}
+
+/// Apply `--minimal` and `--annotate` to a finished symbol file in one rewrite
+async fn rewrite_isf(
+    symbol_path: &Path,
+    kernel: &str,
+    version: &DistroVersion,
+    attempt: Option<&Attempt>,
+    options: &GenerateOptions,
+    output: &Output,
+) -> Result<()> {
+    if !options.minimal && !options.annotate {
+        return Ok(());
+    }
+    let metadata = options.annotate.then(|| SymgenMetadata {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        kernel: kernel.to_string(),
+        distro: version.distro.display_name().to_string(),
+        distro_version: version.version.clone(),
+        arch: options.arch.name().to_string(),
+        tag: options.tag.clone(),
+        source_package: attempt.and_then(|attempt| attempt.source_package.clone()),
+        system_map_source: attempt.and_then(|attempt| attempt.system_map_source.clone()),
+    });
+    let (symbol_path, minimal, output) =
+        (symbol_path.to_path_buf(), options.minimal, output.clone());
+    blocking(move || {
+        let mut symbols = isf::load(&symbol_path)?;
+        if minimal {
+            output.progress("Removing unreferenced types...");
+            let stats = isf::minimize(&mut symbols);
+            output.info(&format!(
+                "Kept {}/{} user types and {}/{} enums",
+                stats.user_types_after,
+                stats.user_types_before,
+                stats.enums_after,
+                stats.enums_before
+            ));
+        }
+        if let Some(metadata) = &metadata {
+            isf::annotate(&mut symbols, metadata);
+        }
+        output.progress("Writing symbol file...");
+        isf::save(&symbol_path, &symbols)
+    })
+    .await
+}
+
+/// Run work that blocks (xz, ISF rewrites, hashing, waiting on cache locks,
+/// hooks, gpg) on tokio's blocking pool, so the other runs of a batch keep going
+async fn blocking<T: Send + 'static>(
+    work: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .context("Blocking task panicked")?
+}
+
+/// `install_debuginfo 2m 10s, generate 45s`; steps under a second are left out
+fn step_times(durations: &[StepDuration]) -> String {
+    durations
+        .iter()
+        .filter(|d| d.seconds >= 1.0)
+        .map(|d| format!("{} {}", d.step, format_duration(d.seconds as u64)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Whether a log line shows data rejected because the clock is behind
+fn shows_clock_skew(line: &str) -> bool {
+    let line = line.to_lowercase();
+    CLOCK_SKEW_PATTERNS
+        .iter()
+        .any(|pattern| line.contains(pattern))
+}
+
+/// `symgen.*` labels attributing a container to its run
+fn run_labels(
+    job_id: &str,
+    kernel: &str,
+    distro_version: &DistroVersion,
+    options: &GenerateOptions,
+) -> HashMap<String, String> {
+    let mut labels = HashMap::from([
+        (JOB_LABEL.to_string(), job_id.to_string()),
+        (KERNEL_LABEL.to_string(), kernel.to_string()),
+        (
+            DISTRO_LABEL.to_string(),
+            format!(
+                "{} {}",
+                distro_version.distro.name(),
+                distro_version.version
+            ),
+        ),
+    ]);
+    if let Some(tag) = &options.tag {
+        labels.insert(TAG_LABEL.to_string(), tag.clone());
+    }
+    labels
+}
@@ -0,0 +1,106 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use crate::cache::FileLock;
+use crate::distros::Distro;
+
+/// One successful generation, kept to estimate future runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub finished_at: String,
+    pub distro: String,
+    pub distro_version: String,
+    pub kernel: String,
+    pub arch: String,
+    /// Wall-clock time of the whole run, image pull included
+    pub duration_secs: u64,
+    /// Unpacked size of the debug packages the script installed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debuginfo_bytes: Option<u64>,
+    /// Size of the final symbol file
+    pub symbol_bytes: u64,
+    /// File name of the symbol file, which is also its cache entry name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_file: Option<String>,
+    /// Case label given with `--tag`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+impl HistoryEntry {
+    /// `major.minor` of the kernel release (`5.15` for `5.15.0-91-generic`)
+    pub fn kernel_series(&self) -> String {
+        kernel_series(&self.kernel)
+    }
+}
+
+/// `major.minor` of a kernel release string
+pub fn kernel_series(kernel: &str) -> String {
+    kernel.split('.').take(2).collect::<Vec<_>>().join(".")
+}
+
+/// Release a kernel was last generated for, so a banner naming no
+/// distribution can go straight to what worked before
+pub fn known_release(entries: &[HistoryEntry], kernel: &str) -> Option<(Distro, String)> {
+    entries
+        .iter()
+        .rev()
+        .filter(|entry| entry.kernel == kernel)
+        .find_map(|entry| {
+            let distro = Distro::all()
+                .iter()
+                .copied()
+                .find(|d| d.display_name() == entry.distro)?;
+            Some((distro, entry.distro_version.clone()))
+        })
+}
+
+/// Symbol files generated by runs tagged `tag`
+pub fn tagged_files(entries: &[HistoryEntry], tag: &str) -> HashSet<String> {
+    entries
+        .iter()
+        .filter(|entry| entry.tag.as_deref() == Some(tag))
+        .filter_map(|entry| entry.symbol_file.clone())
+        .collect()
+}
+
+/// Append an entry to the history file, creating it if needed.
+///
+/// Appends are not atomic on network filesystems, so writers take the file's lock.
+pub fn append(path: &Path, entry: &HistoryEntry) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let _lock = FileLock::acquire(path)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open history file {}", path.display()))?;
+    let line = serde_json::to_string(entry).context("Failed to serialize history entry")?;
+    writeln!(file, "{}", line).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Read every entry; a missing file is an empty history and bad lines are skipped
+pub fn load(path: &Path) -> Result<Vec<HistoryEntry>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to open {}", path.display())),
+    };
+
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.with_context(|| format!("Failed to read {}", path.display()))?;
+        match serde_json::from_str(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => tracing::warn!("Skipping malformed history line: {}", e),
+        }
+    }
+    Ok(entries)
+}
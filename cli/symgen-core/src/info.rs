@@ -0,0 +1,213 @@
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use xz2::read::XzDecoder;
+
+use crate::banner::parse_banner_str;
+use crate::isf;
+
+const XZ_MAGIC: &[u8] = &[0xfd, b'7', b'z', b'X', b'Z', 0x00];
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+/// What is known about a symbol file without trusting its name
+#[derive(Debug, Serialize)]
+pub struct SymbolInfo {
+    pub path: String,
+    pub compression: Compression,
+    /// `metadata.format` of the ISF
+    pub format: Option<String>,
+    /// Tool that wrote the ISF, from `metadata.producer` (`dwarf2json 0.9.0`)
+    pub producer: Option<String>,
+    /// Kernel banner recorded in `linux_banner`
+    pub banner: Option<String>,
+    /// Kernel release parsed from the banner
+    pub kernel: Option<String>,
+    pub symbols: usize,
+    pub user_types: usize,
+    pub enums: usize,
+    pub base_types: Vec<BaseType>,
+    /// Files the types and symbols were read from, from `metadata.linux`
+    pub sources: Vec<Source>,
+    /// Provenance recorded by `--annotate`
+    pub symgen: Option<Value>,
+}
+
+/// How the file is stored on disk
+#[derive(Debug, Serialize)]
+pub struct Compression {
+    /// `xz`, `gzip` or `none`
+    pub format: &'static str,
+    /// Integrity check of the xz stream (`CRC64`, `SHA-256`, ...)
+    pub check: Option<&'static str>,
+    pub file_size: u64,
+    /// Size of the JSON once decompressed
+    pub json_size: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BaseType {
+    pub name: String,
+    pub kind: Option<String>,
+    pub size: Option<u64>,
+    pub signed: Option<bool>,
+    pub endian: Option<String>,
+}
+
+/// A debug file or symbol table the ISF was generated from
+#[derive(Debug, Serialize)]
+pub struct Source {
+    /// `types` or `symbols`
+    pub section: String,
+    /// `dwarf`, `symtab` or `system-map`
+    pub kind: Option<String>,
+    pub name: Option<String>,
+}
+
+/// Read a symbol file and describe it.
+///
+/// The compression is detected from the file's contents rather than its
+/// extension, so renamed or re-compressed files are described correctly.
+pub fn inspect(path: &Path) -> Result<SymbolInfo> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open symbol file {}", path.display()))?;
+    let file_size = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    let head = reader.fill_buf()?.to_vec();
+
+    let (format, check, decoded): (_, _, Box<dyn Read>) = if head.starts_with(XZ_MAGIC) {
+        (
+            "xz",
+            head.get(7).map(|flags| xz_check(flags & 0x0f)),
+            Box::new(XzDecoder::new(reader)),
+        )
+    } else if head.starts_with(GZIP_MAGIC) {
+        ("gzip", None, Box::new(GzDecoder::new(reader)))
+    } else {
+        ("none", None, Box::new(reader))
+    };
+    let mut counted = Counted {
+        inner: decoded,
+        count: 0,
+    };
+    let isf: Value = serde_json::from_reader(&mut counted)
+        .with_context(|| format!("Failed to parse symbol file {}", path.display()))?;
+
+    let base_types = isf
+        .get("base_types")
+        .and_then(Value::as_object)
+        .map(|types| {
+            types
+                .iter()
+                .map(|(name, entry)| BaseType {
+                    name: name.clone(),
+                    kind: entry
+                        .get("kind")
+                        .and_then(Value::as_str)
+                        .map(str::to_string),
+                    size: entry.get("size").and_then(Value::as_u64),
+                    signed: entry.get("signed").and_then(Value::as_bool),
+                    endian: entry
+                        .get("endian")
+                        .and_then(Value::as_str)
+                        .map(str::to_string),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut sources = Vec::new();
+    for section in ["types", "symbols"] {
+        // dwarf2json records one source object, or a list when given several inputs
+        let entries = match isf.pointer(&format!("/metadata/linux/{}", section)) {
+            Some(Value::Array(entries)) => entries.iter().collect(),
+            Some(entry @ Value::Object(_)) => vec![entry],
+            _ => Vec::new(),
+        };
+        for entry in entries {
+            sources.push(Source {
+                section: section.to_string(),
+                kind: entry
+                    .get("kind")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                name: entry
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+            });
+        }
+    }
+
+    let producer = isf.pointer("/metadata/producer").map(|producer| {
+        let field = |key: &str| {
+            producer
+                .get(key)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+        };
+        format!("{} {}", field("name"), field("version"))
+            .trim()
+            .to_string()
+    });
+    let banner = isf::banner(&isf);
+    Ok(SymbolInfo {
+        path: path.to_string_lossy().to_string(),
+        compression: Compression {
+            format,
+            check,
+            file_size,
+            json_size: counted.count,
+        },
+        format: isf
+            .pointer("/metadata/format")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        producer: producer.filter(|producer| !producer.is_empty()),
+        kernel: banner
+            .as_deref()
+            .and_then(parse_banner_str)
+            .map(|parsed| parsed.kernel_version),
+        banner,
+        symbols: section_len(&isf, "symbols"),
+        user_types: section_len(&isf, "user_types"),
+        enums: section_len(&isf, "enums"),
+        base_types,
+        sources,
+        symgen: isf.pointer("/metadata/symgen").cloned(),
+    })
+}
+
+/// Name of an xz stream check ID
+fn xz_check(id: u8) -> &'static str {
+    match id {
+        0x00 => "none",
+        0x01 => "CRC32",
+        0x04 => "CRC64",
+        0x0a => "SHA-256",
+        _ => "unknown",
+    }
+}
+
+fn section_len(isf: &Value, section: &str) -> usize {
+    isf.get(section)
+        .and_then(Value::as_object)
+        .map_or(0, |s| s.len())
+}
+
+/// Counts the bytes read through it
+struct Counted<R> {
+    inner: R,
+    count: u64,
+}
+
+impl<R: Read> Read for Counted<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count += read as u64;
+        Ok(read)
+    }
+}
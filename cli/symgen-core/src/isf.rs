@@ -0,0 +1,512 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use xz2::read::XzDecoder;
+use xz2::stream::{Check, MtStreamBuilder};
+use xz2::write::XzEncoder;
+
+/// Statistics reported after minimizing an ISF
+#[derive(Debug, Default)]
+pub struct MinimizeStats {
+    pub user_types_before: usize,
+    pub user_types_after: usize,
+    pub enums_before: usize,
+    pub enums_after: usize,
+}
+
+/// Statistics reported after merging several ISFs
+#[derive(Debug, Default)]
+pub struct MergeStats {
+    pub symbols: usize,
+    pub user_types: usize,
+    pub enums: usize,
+    pub base_types: usize,
+    /// Entries defined differently by more than one input (first one wins)
+    pub conflicts: Vec<String>,
+}
+
+/// Statistics reported after rebasing an ISF onto a new System.map
+#[derive(Debug, Default)]
+pub struct DeltaStats {
+    pub symbols: usize,
+    /// Symbols present in both builds at a different address
+    pub moved: usize,
+    /// Symbols only in the new build
+    pub added: usize,
+    /// Symbols only in the base build
+    pub removed: usize,
+}
+
+/// How a symbol file was produced, recorded under `metadata.symgen` with `--annotate`
+#[derive(Debug, Clone, Serialize)]
+pub struct SymgenMetadata {
+    /// symgen version that generated the file
+    pub version: String,
+    pub generated_at: String,
+    pub kernel: String,
+    pub distro: String,
+    pub distro_version: String,
+    pub arch: String,
+    /// Case or engagement label of the run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Package that shipped the vmlinux, as `name=version` (deb) or an NVRA (rpm)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_package: Option<String>,
+    /// Where the System.map came from (`installed`, `package` or `symtab`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_map_source: Option<String>,
+}
+
+/// Sections merged by name across inputs
+const MERGED_SECTIONS: &[&str] = &["base_types", "user_types", "enums", "symbols"];
+
+/// Load an ISF file, transparently decompressing `.xz` files
+pub fn load(path: &Path) -> Result<Value> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open symbol file {}", path.display()))?;
+    let reader: Box<dyn Read> = if is_xz(path) {
+        Box::new(XzDecoder::new(BufReader::new(file)))
+    } else {
+        Box::new(BufReader::new(file))
+    };
+
+    serde_json::from_reader(reader)
+        .with_context(|| format!("Failed to parse symbol file {}", path.display()))
+}
+
+/// Write an ISF file, compressing with xz when the path ends in `.xz`
+pub fn save(path: &Path, isf: &Value) -> Result<()> {
+    let file = File::create(path)
+        .with_context(|| format!("Failed to create symbol file {}", path.display()))?;
+
+    if is_xz(path) {
+        let mut encoder = XzEncoder::new(BufWriter::new(file), 9);
+        serde_json::to_writer(&mut encoder, isf).context("Failed to serialize symbol file")?;
+        encoder
+            .finish()
+            .context("Failed to compress symbol file")?
+            .flush()?;
+    } else {
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer(&mut writer, isf).context("Failed to serialize symbol file")?;
+        writer.flush()?;
+    }
+
+    Ok(())
+}
+
+/// Compress a raw `.json` file to `.json.xz` using every available core, removing the input.
+///
+/// Uses preset 6: at preset 9 the 192 MiB blocks leave too few blocks in a kernel
+/// ISF to keep more than a couple of threads busy.
+pub fn compress_file(json_path: &Path) -> Result<PathBuf> {
+    let mut xz_name = json_path.as_os_str().to_os_string();
+    xz_name.push(".xz");
+    let xz_path = PathBuf::from(xz_name);
+
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get() as u32);
+    let stream = MtStreamBuilder::new()
+        .threads(threads)
+        .preset(6)
+        .check(Check::Crc64)
+        .encoder()
+        .context("Failed to set up xz encoder")?;
+
+    let mut input =
+        File::open(json_path).with_context(|| format!("Failed to open {}", json_path.display()))?;
+    let output = File::create(&xz_path)
+        .with_context(|| format!("Failed to create {}", xz_path.display()))?;
+    let mut encoder = XzEncoder::new_stream(BufWriter::new(output), stream);
+
+    std::io::copy(&mut input, &mut encoder)
+        .with_context(|| format!("Failed to compress {}", json_path.display()))?;
+    encoder
+        .finish()
+        .context("Failed to compress symbol file")?
+        .flush()?;
+
+    std::fs::remove_file(json_path)
+        .with_context(|| format!("Failed to remove {}", json_path.display()))?;
+    Ok(xz_path)
+}
+
+/// Decompress a `.json.xz` file next to itself, keeping the input
+pub fn decompress_file(xz_path: &Path) -> Result<PathBuf> {
+    let json_path = xz_path.with_extension("");
+    let input =
+        File::open(xz_path).with_context(|| format!("Failed to open {}", xz_path.display()))?;
+    let mut output = BufWriter::new(
+        File::create(&json_path)
+            .with_context(|| format!("Failed to create {}", json_path.display()))?,
+    );
+
+    std::io::copy(&mut XzDecoder::new(BufReader::new(input)), &mut output)
+        .with_context(|| format!("Failed to decompress {}", xz_path.display()))?;
+    output.flush()?;
+    Ok(json_path)
+}
+
+fn is_xz(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "xz")
+}
+
+/// Drop user types and enums that are not reachable from any symbol.
+///
+/// This is a lossy reduction: plugins that look up types by name without going
+/// through a symbol (e.g. constructing a `module` object from a raw offset) will
+/// no longer find them. Symbols, base types and metadata are always kept intact.
+pub fn minimize(isf: &mut Value) -> MinimizeStats {
+    let mut stats = MinimizeStats {
+        user_types_before: section_len(isf, "user_types"),
+        enums_before: section_len(isf, "enums"),
+        ..Default::default()
+    };
+
+    let mut reachable = Reachable::default();
+    let mut pending: Vec<String> = Vec::new();
+
+    // Seed from every symbol's type descriptor
+    if let Some(symbols) = isf.get("symbols").and_then(Value::as_object) {
+        for symbol in symbols.values() {
+            if let Some(descriptor) = symbol.get("type") {
+                reachable.visit(descriptor, &mut pending);
+            }
+        }
+    }
+
+    // Walk user type fields until no new types are discovered
+    while let Some(name) = pending.pop() {
+        let Some(fields) = isf
+            .get("user_types")
+            .and_then(|types| types.get(&name))
+            .and_then(|t| t.get("fields"))
+            .and_then(Value::as_object)
+        else {
+            continue;
+        };
+        for field in fields.values() {
+            if let Some(descriptor) = field.get("type") {
+                reachable.visit(descriptor, &mut pending);
+            }
+        }
+    }
+
+    retain_section(isf, "user_types", &reachable.user_types);
+    retain_section(isf, "enums", &reachable.enums);
+
+    stats.user_types_after = section_len(isf, "user_types");
+    stats.enums_after = section_len(isf, "enums");
+    stats
+}
+
+/// Minimize a symbol file in place
+pub fn minimize_file(path: &Path) -> Result<MinimizeStats> {
+    let mut isf = load(path)?;
+    let stats = minimize(&mut isf);
+    save(path, &isf)?;
+    Ok(stats)
+}
+
+/// Merge several ISFs into one, deduplicating entries by name.
+///
+/// The first input provides the metadata. Identical definitions (typically the
+/// base types every module repeats) are collapsed; differing definitions keep
+/// the first occurrence and are reported as conflicts.
+pub fn merge(inputs: Vec<Value>) -> (Value, MergeStats) {
+    let mut stats = MergeStats::default();
+    let mut inputs = inputs.into_iter();
+    let mut merged = inputs
+        .next()
+        .unwrap_or_else(|| Value::Object(Default::default()));
+
+    for isf in inputs {
+        for section in MERGED_SECTIONS {
+            let Some(entries) = isf.get(*section).and_then(Value::as_object) else {
+                continue;
+            };
+            let target = merged
+                .as_object_mut()
+                .map(|root| {
+                    root.entry(section.to_string())
+                        .or_insert_with(|| Value::Object(Default::default()))
+                })
+                .and_then(Value::as_object_mut);
+            let Some(target) = target else {
+                continue;
+            };
+
+            for (name, entry) in entries {
+                match target.get(name) {
+                    Some(existing) if existing != entry => {
+                        stats.conflicts.push(format!("{}.{}", section, name));
+                    }
+                    Some(_) => {}
+                    None => {
+                        target.insert(name.clone(), entry.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    stats.symbols = section_len(&merged, "symbols");
+    stats.user_types = section_len(&merged, "user_types");
+    stats.enums = section_len(&merged, "enums");
+    stats.base_types = section_len(&merged, "base_types");
+    (merged, stats)
+}
+
+/// Merge symbol files from disk and write the combined ISF
+pub fn merge_files(inputs: &[PathBuf], output: &Path) -> Result<MergeStats> {
+    let isfs = inputs
+        .iter()
+        .map(|path| load(path))
+        .collect::<Result<Vec<_>>>()?;
+    let (merged, stats) = merge(isfs);
+    save(output, &merged)?;
+    Ok(stats)
+}
+
+/// Rebase an ISF onto a new upload of the same kernel ABI.
+///
+/// Types, enums and base types come from `base` unchanged; symbol addresses
+/// come from `symbols`, an ISF converted from the new kernel's System.map
+/// alone. Symbol types are carried over from the base by name, and
+/// `linux_banner` gets the new kernel's banner so Volatility matches it.
+pub fn apply_delta(mut base: Value, symbols: Value, banner: &str) -> (Value, DeltaStats) {
+    let mut stats = DeltaStats::default();
+    let old = base
+        .get_mut("symbols")
+        .map(Value::take)
+        .and_then(|v| match v {
+            Value::Object(map) => Some(map),
+            _ => None,
+        })
+        .unwrap_or_default();
+    let mut new = match symbols.get("symbols") {
+        Some(Value::Object(map)) => map.clone(),
+        _ => Default::default(),
+    };
+
+    for (name, symbol) in new.iter_mut() {
+        let Some(previous) = old.get(name) else {
+            stats.added += 1;
+            continue;
+        };
+        if previous.get("address") != symbol.get("address") {
+            stats.moved += 1;
+        }
+        if let (Some(target), Some(kind)) = (symbol.as_object_mut(), previous.get("type")) {
+            target.entry("type").or_insert_with(|| kind.clone());
+        }
+    }
+    stats.removed = old.keys().filter(|name| !new.contains_key(*name)).count();
+
+    if let Some(Value::Object(symbol)) = new.get_mut("linux_banner") {
+        let mut data = banner.trim_end().as_bytes().to_vec();
+        data.extend_from_slice(b"\n\0");
+        symbol.insert(
+            "constant_data".to_string(),
+            Value::String(base64_encode(&data)),
+        );
+    }
+    stats.symbols = new.len();
+
+    if let Some(root) = base.as_object_mut() {
+        root.insert("symbols".to_string(), Value::Object(new));
+    }
+    // Record the new System.map as the symbols' source
+    if let (Some(source), Some(linux)) = (
+        symbols.pointer("/metadata/linux/symbols"),
+        base.pointer_mut("/metadata/linux")
+            .and_then(Value::as_object_mut),
+    ) {
+        linux.insert("symbols".to_string(), source.clone());
+    }
+    (base, stats)
+}
+
+/// Rebase the base ISF onto the System.map-only ISF at `path`, replacing it
+pub fn apply_delta_file(base: &Path, path: &Path, banner: &str) -> Result<DeltaStats> {
+    let (rebased, stats) = apply_delta(load(base)?, load(path)?, banner);
+    save(path, &rebased)?;
+    Ok(stats)
+}
+
+/// Record symgen provenance under `metadata.symgen`.
+///
+/// Volatility ignores metadata keys it does not know, so annotated files load as before.
+pub fn annotate(isf: &mut Value, metadata: &SymgenMetadata) {
+    let Some(root) = isf.as_object_mut() else {
+        return;
+    };
+    let section = root
+        .entry("metadata")
+        .or_insert_with(|| Value::Object(Default::default()));
+    if let Some(section) = section.as_object_mut() {
+        section.insert(
+            "symgen".to_string(),
+            serde_json::to_value(metadata).expect("metadata serializes"),
+        );
+    }
+}
+
+/// The kernel banner recorded in an ISF's `linux_banner` constant data
+pub fn banner(isf: &Value) -> Option<String> {
+    let data = isf
+        .pointer("/symbols/linux_banner/constant_data")?
+        .as_str()?;
+    let bytes = base64_decode(data)?;
+    let text = String::from_utf8_lossy(&bytes);
+    Some(text.trim_end_matches(['\0', '\n']).to_string())
+}
+
+/// Standard base64 with padding, as used for ISF `constant_data`
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let group = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[((group >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn base64_decode(data: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(data.len() / 4 * 3);
+    let (mut group, mut bits) = (0u32, 0);
+    for c in data.bytes().filter(|&c| c != b'=') {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        group = (group << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((group >> bits) as u8);
+        }
+    }
+    Some(decoded)
+}
+
+#[derive(Default)]
+struct Reachable {
+    user_types: HashSet<String>,
+    enums: HashSet<String>,
+}
+
+impl Reachable {
+    /// Record every type named by a descriptor, queueing newly found user types
+    fn visit(&mut self, descriptor: &Value, pending: &mut Vec<String>) {
+        let Some(object) = descriptor.as_object() else {
+            return;
+        };
+
+        if let (Some(kind), Some(name)) = (
+            object.get("kind").and_then(Value::as_str),
+            object.get("name").and_then(Value::as_str),
+        ) {
+            match kind {
+                "struct" | "union" | "class" if self.user_types.insert(name.to_string()) => {
+                    pending.push(name.to_string());
+                }
+                "enum" => {
+                    self.enums.insert(name.to_string());
+                }
+                _ => {}
+            }
+        }
+
+        // Pointers, arrays and bitfields nest their target descriptor
+        for key in ["subtype", "type"] {
+            if let Some(nested) = object.get(key) {
+                self.visit(nested, pending);
+            }
+        }
+    }
+}
+
+fn section_len(isf: &Value, section: &str) -> usize {
+    isf.get(section)
+        .and_then(Value::as_object)
+        .map_or(0, |s| s.len())
+}
+
+fn retain_section(isf: &mut Value, section: &str, keep: &HashSet<String>) {
+    if let Some(entries) = isf.get_mut(section).and_then(Value::as_object_mut) {
+        entries.retain(|name, _| keep.contains(name));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn minimize_keeps_reachable_types() {
+        let mut isf = json!({
+            "symbols": {
+                "init_task": {"address": 1, "type": {"kind": "struct", "name": "task_struct"}}
+            },
+            "user_types": {
+                "task_struct": {"fields": {
+                    "mm": {"type": {"kind": "pointer", "subtype": {"kind": "struct", "name": "mm_struct"}}},
+                    "state": {"type": {"kind": "enum", "name": "task_state"}}
+                }},
+                "mm_struct": {"fields": {}},
+                "unused": {"fields": {}}
+            },
+            "enums": {"task_state": {}, "unused_enum": {}}
+        });
+
+        let stats = minimize(&mut isf);
+        assert_eq!((stats.user_types_before, stats.user_types_after), (3, 2));
+        assert_eq!((stats.enums_before, stats.enums_after), (2, 1));
+        assert!(isf["user_types"].get("mm_struct").is_some());
+        assert!(isf["user_types"].get("unused").is_none());
+        assert!(isf["enums"].get("task_state").is_some());
+    }
+
+    #[test]
+    fn merge_dedupes_and_reports_conflicts() {
+        let kernel = json!({
+            "metadata": {"producer": "kernel"},
+            "base_types": {"int": {"size": 4}},
+            "symbols": {"init_task": {"address": 1}}
+        });
+        let module = json!({
+            "metadata": {"producer": "module"},
+            "base_types": {"int": {"size": 4}, "long": {"size": 8}},
+            "symbols": {"init_task": {"address": 2}, "mod_init": {"address": 3}}
+        });
+
+        let (merged, stats) = merge(vec![kernel, module]);
+        assert_eq!(merged["metadata"]["producer"], "kernel");
+        assert_eq!(merged["symbols"]["init_task"]["address"], 1);
+        assert_eq!(stats.symbols, 2);
+        assert_eq!(stats.base_types, 2);
+        assert_eq!(stats.conflicts, ["symbols.init_task"]);
+    }
+}
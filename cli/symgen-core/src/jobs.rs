@@ -0,0 +1,291 @@
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use rusqlite::{params, Connection, Row};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Error recorded for jobs whose process went away while they were running
+const INTERRUPTED: &str = "interrupted (symgen exited while the job was running)";
+
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS jobs (
+    id TEXT PRIMARY KEY,
+    kernel TEXT NOT NULL,
+    distro TEXT NOT NULL,
+    release TEXT NOT NULL,
+    arch TEXT NOT NULL,
+    output_dir TEXT,
+    tag TEXT,
+    status TEXT NOT NULL,
+    symbol_file TEXT,
+    error TEXT,
+    pid INTEGER,
+    retry_of TEXT,
+    options TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+)";
+
+const COLUMNS: &str =
+    "id, kernel, distro, release, arch, output_dir, tag, status, symbol_file, error, retry_of, \
+     created_at, updated_at, options";
+
+/// Where a recorded job stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+        }
+    }
+
+    fn parse(name: &str) -> Self {
+        match name {
+            "pending" => Self::Pending,
+            "running" => Self::Running,
+            "succeeded" => Self::Succeeded,
+            _ => Self::Failed,
+        }
+    }
+}
+
+/// What a job was asked to generate
+#[derive(Debug, Clone)]
+pub struct NewJob<'a> {
+    pub kernel: &'a str,
+    pub distro: &'a str,
+    pub release: &'a str,
+    pub arch: &'a str,
+    pub output_dir: Option<&'a str>,
+    pub tag: Option<&'a str>,
+    /// Job this one re-runs
+    pub retry_of: Option<&'a str>,
+    /// Generate options as JSON, restored on retry
+    pub options: Option<&'a str>,
+}
+
+/// A job as stored in the database
+#[derive(Debug, Clone, Serialize)]
+pub struct JobRecord {
+    pub id: String,
+    pub kernel: String,
+    pub distro: String,
+    pub release: String,
+    pub arch: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    pub status: JobStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_of: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Generate options as JSON; missing for jobs recorded before they were stored
+    #[serde(skip)]
+    pub options: Option<String>,
+}
+
+impl JobRecord {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            kernel: row.get(1)?,
+            distro: row.get(2)?,
+            release: row.get(3)?,
+            arch: row.get(4)?,
+            output_dir: row.get(5)?,
+            tag: row.get(6)?,
+            status: JobStatus::parse(&row.get::<_, String>(7)?),
+            symbol_file: row.get(8)?,
+            error: row.get(9)?,
+            retry_of: row.get(10)?,
+            created_at: row.get(11)?,
+            updated_at: row.get(12)?,
+            options: row.get(13)?,
+        })
+    }
+}
+
+/// Job state kept in `jobs.db` under the state directory, with each job's
+/// container output in `logs/<id>.log`.
+///
+/// Several symgen processes may share the database; writers wait for each
+/// other rather than failing on a locked file.
+pub struct JobStore {
+    conn: Mutex<Connection>,
+    dir: PathBuf,
+}
+
+impl JobStore {
+    /// Open or create the store in `dir`.
+    ///
+    /// Jobs left running by a process that no longer exists are marked failed,
+    /// so a crash mid-batch shows up as interrupted jobs rather than stale ones.
+    pub fn open(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir.join("logs"))
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = dir.join("jobs.db");
+        let conn = Connection::open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        conn.busy_timeout(Duration::from_secs(10))?;
+        conn.execute(SCHEMA, [])
+            .context("Failed to create jobs table")?;
+        migrate(&conn).context("Failed to update jobs table")?;
+
+        let store = Self {
+            conn: Mutex::new(conn),
+            dir: dir.to_path_buf(),
+        };
+        store.fail_orphans()?;
+        Ok(store)
+    }
+
+    /// Container log of a job
+    pub fn log_path(&self, id: &str) -> PathBuf {
+        self.dir.join("logs").join(format!("{}.log", id))
+    }
+
+    /// Record a new pending job under `id`
+    pub fn insert(&self, id: &str, job: &NewJob) -> Result<()> {
+        let now = now();
+        self.conn()
+            .execute(
+                "INSERT INTO jobs (id, kernel, distro, release, arch, output_dir, tag, status, retry_of, options, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'pending', ?8, ?9, ?10, ?10)",
+                params![id, job.kernel, job.distro, job.release, job.arch, job.output_dir, job.tag, job.retry_of, job.options, now],
+            )
+            .context("Failed to record job")?;
+        Ok(())
+    }
+
+    /// Mark a job as running in this process
+    pub fn start(&self, id: &str) -> Result<()> {
+        self.update(
+            "UPDATE jobs SET status = 'running', pid = ?2, error = NULL, updated_at = ?3 WHERE id = ?1",
+            params![id, std::process::id(), now()],
+        )
+    }
+
+    pub fn succeed(&self, id: &str, symbol_file: &str) -> Result<()> {
+        self.update(
+            "UPDATE jobs SET status = 'succeeded', symbol_file = ?2, pid = NULL, updated_at = ?3 WHERE id = ?1",
+            params![id, symbol_file, now()],
+        )
+    }
+
+    pub fn fail(&self, id: &str, error: &str) -> Result<()> {
+        self.update(
+            "UPDATE jobs SET status = 'failed', error = ?2, pid = NULL, updated_at = ?3 WHERE id = ?1",
+            params![id, error, now()],
+        )
+    }
+
+    /// Most recent jobs first, optionally only those with `status` or `tag`
+    pub fn list(
+        &self,
+        status: Option<JobStatus>,
+        tag: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<JobRecord>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM jobs WHERE (?1 IS NULL OR status = ?1) AND (?2 IS NULL OR tag = ?2) \
+             ORDER BY created_at DESC LIMIT ?3",
+            COLUMNS
+        ))?;
+        let records = stmt
+            .query_map(
+                params![status.map(|s| s.name()), tag, limit as i64],
+                JobRecord::from_row,
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Failed to read jobs")?;
+        Ok(records)
+    }
+
+    /// Look a job up by its id or a unique prefix of it
+    pub fn find(&self, id: &str) -> Result<JobRecord> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM jobs WHERE id LIKE ?1 || '%' LIMIT 2",
+            COLUMNS
+        ))?;
+        let mut records = stmt
+            .query_map(params![id], JobRecord::from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Failed to read jobs")?;
+        match records.len() {
+            0 => bail!("No job matches {}", id),
+            1 => Ok(records.remove(0)),
+            _ => bail!("Job id {} is ambiguous; give more characters", id),
+        }
+    }
+
+    fn fail_orphans(&self) -> Result<()> {
+        let conn = self.conn();
+        let orphans = conn
+            .prepare("SELECT id, pid FROM jobs WHERE status = 'running'")?
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Option<u32>>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        for (id, pid) in orphans {
+            if pid.is_some_and(process_alive) {
+                continue;
+            }
+            conn.execute(
+                "UPDATE jobs SET status = 'failed', error = ?2, pid = NULL, updated_at = ?3 WHERE id = ?1",
+                params![id, INTERRUPTED, now()],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn update(&self, sql: &str, params: impl rusqlite::Params) -> Result<()> {
+        self.conn()
+            .execute(sql, params)
+            .context("Failed to update job")?;
+        Ok(())
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Add columns introduced after the table was first created
+fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+    let columns = conn
+        .prepare("SELECT name FROM pragma_table_info('jobs')")?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    if !columns.iter().any(|name| name == "options") {
+        conn.execute("ALTER TABLE jobs ADD COLUMN options TEXT", [])?;
+    }
+    Ok(())
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+fn process_alive(pid: u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
@@ -0,0 +1,148 @@
+//! Volatility3 Linux symbol generation as a library.
+//!
+//! This is the engine behind the `symgen` binary: kernel banner parsing
+//! ([`banner`]), distribution metadata ([`distros`]), generation scripts
+//! ([`script`]) and the Docker/Podman runner ([`docker`]). [`Generator`] runs
+//! a whole generation the way `symgen generate` does:
+//!
+//! ```no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! use symgen_core::{Config, GenerateRequest, Generator};
+//!
+//! let generator = Generator::new(Config::default()).await?;
+//! let result = generator
+//!     .generate(GenerateRequest::new("5.15.0-91-generic", "ubuntu", "22.04"))
+//!     .await?;
+//! println!("{} ({} bytes)", result.symbol_file, result.file_size);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Nothing is printed unless an [`Output`] is given with
+//! [`Generator::with_output`].
+
+use anyhow::{anyhow, Result};
+use std::path::PathBuf;
+
+/// A `regex::Regex` compiled on first use and kept for the life of the process
+macro_rules! cached_regex {
+    ($pattern:expr) => {{
+        static RE: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+        RE.get_or_init(|| regex::Regex::new($pattern).expect("valid regex"))
+    }};
+}
+
+pub mod acquisition;
+pub mod artifacts;
+pub mod banner;
+pub mod banner_index;
+pub mod bundle;
+pub mod cache;
+pub mod config;
+pub mod distros;
+pub mod docker;
+pub mod dwarf2json;
+pub mod estimate;
+pub mod failures;
+pub mod generator;
+pub mod history;
+pub mod info;
+pub mod isf;
+pub mod jobs;
+pub mod netfs;
+pub mod output;
+pub mod postprocess;
+pub mod proxy;
+pub mod registry;
+pub mod remote_index;
+pub mod scan;
+pub mod script;
+pub mod sign;
+pub mod snap;
+pub mod steps;
+pub mod store;
+pub mod upgrade;
+pub mod upload;
+pub mod verify;
+pub mod vol3;
+
+pub use config::Config;
+pub use generator::{GenerateOptions, GenerationResult};
+pub use output::Output;
+
+use generator::SymbolGenerator;
+
+/// A kernel to generate symbols for
+#[derive(Debug, Default)]
+pub struct GenerateRequest {
+    /// Kernel release (`5.15.0-91-generic`)
+    pub kernel: String,
+    /// Distribution name as accepted by `-d` (`ubuntu`, `debian`, ...)
+    pub distro: String,
+    /// Distribution release (`22.04`)
+    pub release: String,
+    /// Directory the symbol file is written to (default: current directory)
+    pub output_dir: Option<PathBuf>,
+    pub options: GenerateOptions,
+}
+
+impl GenerateRequest {
+    pub fn new(kernel: &str, distro: &str, release: &str) -> Self {
+        Self {
+            kernel: kernel.to_string(),
+            distro: distro.to_string(),
+            release: release.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Generates symbol files in containers
+pub struct Generator {
+    inner: SymbolGenerator,
+    output: Output,
+}
+
+impl Generator {
+    /// Connect to the configured container runtime
+    pub async fn new(config: Config) -> Result<Self> {
+        Ok(Self {
+            inner: SymbolGenerator::new(config).await?,
+            output: Output::quiet(),
+        })
+    }
+
+    /// Report progress through `output` instead of staying silent
+    pub fn with_output(mut self, output: Output) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// Generate the symbol file for a kernel.
+    ///
+    /// A symbol file already present in the output directory is returned
+    /// without running anything, as is one found in the symbol cache
+    /// (`from_cache`).
+    pub async fn generate(&self, request: GenerateRequest) -> Result<GenerationResult> {
+        let output_dir = request
+            .output_dir
+            .as_deref()
+            .map(|dir| {
+                dir.to_str()
+                    .ok_or_else(|| anyhow!("Output directory {} is not UTF-8", dir.display()))
+            })
+            .transpose()?;
+        let generated = self
+            .inner
+            .generate(
+                &request.kernel,
+                &request.distro,
+                &request.release,
+                output_dir,
+                &request.options,
+                &self.output,
+            )
+            .await?;
+        Ok(generated.into_result())
+    }
+}
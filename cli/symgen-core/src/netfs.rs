@@ -0,0 +1,137 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::cache::copy_atomic;
+
+/// Mount table of the current process
+const MOUNTS_PATH: &str = "/proc/self/mounts";
+
+/// Filesystem types served over the network, where bind mounts into
+/// containers tend to fail or hit root-squash permission errors
+const NETWORK_FS_TYPES: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "9p",
+    "afs",
+    "ceph",
+    "glusterfs",
+    "lustre",
+    "fuse.sshfs",
+    "fuse.rclone",
+    "fuse.davfs2",
+    "fuse.glusterfs",
+];
+
+/// Type of the network filesystem `path` is on, or `None` when it is local
+/// or the mount table cannot be read (e.g. outside Linux)
+pub fn network_fs_type(path: &Path) -> Option<String> {
+    let path = std::fs::canonicalize(path).ok()?;
+    let mounts = std::fs::read_to_string(MOUNTS_PATH).ok()?;
+
+    // The longest mount point containing the path is the one it lives on
+    let (_, fs_type) = mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let mount_point = unescape(fields.nth(1)?);
+            let fs_type = fields.next()?;
+            path.starts_with(&mount_point)
+                .then_some((mount_point, fs_type))
+        })
+        .max_by_key(|(mount_point, _)| mount_point.as_os_str().len())?;
+
+    NETWORK_FS_TYPES
+        .contains(&fs_type)
+        .then(|| fs_type.to_string())
+}
+
+/// Decode the octal escapes (`\040` for a space) used in the mount table
+fn unescape(field: &str) -> PathBuf {
+    let mut decoded = Vec::with_capacity(field.len());
+    let bytes = field.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let octal = bytes
+            .get(i + 1..i + 4)
+            .filter(|_| bytes[i] == b'\\')
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+        match octal {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 4;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    PathBuf::from(String::from_utf8_lossy(&decoded).into_owned())
+}
+
+/// Directory the container writes to in place of the output directory, for
+/// network shares and `--force` runs; removed with everything left in it when dropped
+pub struct Scratch {
+    path: PathBuf,
+}
+
+impl Scratch {
+    pub fn create(job_id: &str) -> Result<Self> {
+        Self::at(std::env::temp_dir().join(format!("symgen-{}", job_id)))
+    }
+
+    /// Scratch directory inside `dir`, so its files can be renamed into place
+    pub fn within(dir: &Path, job_id: &str) -> Result<Self> {
+        Self::at(dir.join(format!(".symgen-{}", job_id)))
+    }
+
+    fn at(path: PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(&path)
+            .with_context(|| format!("Failed to create scratch directory {}", path.display()))?;
+        Ok(Self { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Move every file the run produced into `destination`, replacing files of
+    /// the same name.
+    ///
+    /// Files are renamed when both directories share a filesystem. Otherwise
+    /// they are copied, each through a temporary file so readers of the share
+    /// never see a partial one.
+    pub fn deliver(&self, destination: &Path) -> Result<()> {
+        let entries = std::fs::read_dir(&self.path)
+            .with_context(|| format!("Failed to read scratch directory {}", self.path.display()))?;
+        for entry in entries {
+            let entry = entry.context("Failed to read scratch directory")?;
+            if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+                continue;
+            }
+            let target = destination.join(entry.file_name());
+            if std::fs::rename(entry.path(), &target).is_ok() {
+                continue;
+            }
+            copy_atomic(&entry.path(), &target).with_context(|| {
+                format!(
+                    "Failed to copy {} to {}",
+                    entry.path().display(),
+                    target.display()
+                )
+            })?;
+            let _ = std::fs::remove_file(entry.path());
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
@@ -0,0 +1,196 @@
+use colored::Colorize;
+use serde::Serialize;
+
+/// Output handler supporting both human-readable and JSON formats
+#[derive(Clone)]
+pub struct Output {
+    json_mode: bool,
+    /// Print nothing at all (library use)
+    quiet: bool,
+    /// Job the messages belong to when several run at once
+    label: Option<String>,
+    /// JSON mode: write message events to stdout as NDJSON instead of to stderr
+    event_stream: bool,
+}
+
+#[derive(Serialize)]
+struct JsonMessage<'a> {
+    level: &'a str,
+    message: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    job: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+pub struct JsonResult<T: Serialize> {
+    pub success: bool,
+    pub data: Option<T>,
+    pub error: Option<String>,
+}
+
+/// Result printed for a command that failed
+#[derive(Serialize)]
+struct JsonFailure<'a> {
+    success: bool,
+    data: Option<()>,
+    error: &'a str,
+    /// Stable name for failures scripts handle specially (`timeout`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_code: Option<&'a str>,
+}
+
+impl Output {
+    pub fn new(json_mode: bool) -> Self {
+        Self {
+            json_mode,
+            quiet: false,
+            label: None,
+            event_stream: false,
+        }
+    }
+
+    /// JSON output whose stdout is a single NDJSON stream: message events,
+    /// then each result on one line
+    pub fn event_stream() -> Self {
+        Self {
+            event_stream: true,
+            ..Self::new(true)
+        }
+    }
+
+    /// Output that prints nothing, for embedding the generator in other tools
+    pub fn quiet() -> Self {
+        Self {
+            json_mode: false,
+            quiet: true,
+            label: None,
+            event_stream: false,
+        }
+    }
+
+    /// Output whose messages are attributed to a job, for runs that overlap
+    pub fn labeled(&self, label: &str) -> Self {
+        Self {
+            json_mode: self.json_mode,
+            quiet: self.quiet,
+            label: Some(label.to_string()),
+            event_stream: self.event_stream,
+        }
+    }
+
+    /// Whether messages carry a job label (no spinners then: they would fight over the line)
+    pub fn is_labeled(&self) -> bool {
+        self.label.is_some()
+    }
+
+    fn text(&self, message: &str) -> String {
+        match &self.label {
+            Some(label) => format!("{} {}", format!("[{}]", label).dimmed(), message),
+            None => message.to_string(),
+        }
+    }
+
+    /// A message event in JSON mode. Stdout is kept for results, so pipelines
+    /// can parse it, unless the events were asked for as an NDJSON stream.
+    fn event(&self, level: &str, message: &str) {
+        let line = serde_json::to_string(&JsonMessage {
+            level,
+            message,
+            job: self.label.as_deref(),
+        })
+        .unwrap();
+        if self.event_stream {
+            println!("{}", line);
+        } else {
+            eprintln!("{}", line);
+        }
+    }
+
+    pub fn info(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
+        if self.json_mode {
+            self.event("info", message);
+        } else {
+            println!("{} {}", "[*]".blue(), self.text(message));
+        }
+    }
+
+    pub fn success(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
+        if self.json_mode {
+            self.event("success", message);
+        } else {
+            println!("{} {}", "[+]".green(), self.text(message));
+        }
+    }
+
+    pub fn error(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
+        if self.json_mode {
+            self.event("error", message);
+        } else {
+            eprintln!("{} {}", "[!]".red(), self.text(message));
+        }
+    }
+
+    pub fn warning(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
+        if self.json_mode {
+            self.event("warning", message);
+        } else {
+            println!("{} {}", "[!]".yellow(), self.text(message));
+        }
+    }
+
+    pub fn progress(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
+        if self.json_mode {
+            self.event("progress", message);
+        } else {
+            println!("{} {}", "[>]".cyan(), self.text(message));
+        }
+    }
+
+    pub fn result<T: Serialize>(&self, result: JsonResult<T>) {
+        self.print_result(&result);
+    }
+
+    /// Result of a failed command, with an `error_code` for failures scripts handle specially
+    pub fn failure(&self, error: &str, error_code: Option<&str>) {
+        self.print_result(&JsonFailure {
+            success: false,
+            data: None,
+            error,
+            error_code,
+        });
+    }
+
+    fn print_result(&self, result: &impl Serialize) {
+        if !self.json_mode || self.quiet {
+            return;
+        }
+        if self.event_stream {
+            println!("{}", serde_json::to_string(result).unwrap());
+        } else {
+            println!("{}", serde_json::to_string_pretty(result).unwrap());
+        }
+    }
+
+    pub fn is_json(&self) -> bool {
+        self.json_mode
+    }
+
+    pub fn is_quiet(&self) -> bool {
+        self.quiet
+    }
+}
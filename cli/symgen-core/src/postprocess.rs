@@ -0,0 +1,67 @@
+use anyhow::{bail, Context, Result};
+use serde::de::IgnoredAny;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::process::Command;
+
+use crate::isf;
+
+/// Facts about the run exposed to hooks as `SYMGEN_*` environment variables
+pub struct HookContext<'a> {
+    pub kernel: &'a str,
+    pub distro: &'a str,
+    pub distro_version: &'a str,
+    pub arch: &'a str,
+}
+
+/// Run post-processing hooks over the decompressed ISF, then compress it again.
+///
+/// Each command runs through `sh -c` with the JSON path as `$1` and in
+/// `SYMGEN_ISF`, and edits the file in place. Hooks run in order; a non-zero
+/// exit or a file that no longer parses as JSON fails the run, and removes the
+/// symbol file so an unprocessed copy is never mistaken for a finished one.
+pub fn run(symbol_path: &Path, commands: &[String], context: &HookContext) -> Result<()> {
+    let json_path = isf::decompress_file(symbol_path)?;
+    if let Err(e) = run_hooks(&json_path, commands, context) {
+        let _ = std::fs::remove_file(&json_path);
+        let _ = std::fs::remove_file(symbol_path);
+        return Err(e);
+    }
+    isf::compress_file(&json_path)?;
+    Ok(())
+}
+
+fn run_hooks(json_path: &Path, commands: &[String], context: &HookContext) -> Result<()> {
+    for command in commands {
+        tracing::info!("Running post-processing hook: {}", command);
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .arg("sh")
+            .arg(json_path)
+            .env("SYMGEN_ISF", json_path)
+            .env("SYMGEN_KERNEL", context.kernel)
+            .env("SYMGEN_DISTRO", context.distro)
+            .env("SYMGEN_DISTRO_VERSION", context.distro_version)
+            .env("SYMGEN_ARCH", context.arch)
+            // Keep stdout clean for --json
+            .stdout(std::io::stderr())
+            .status()
+            .with_context(|| format!("Failed to run post-processing hook `{}`", command))?;
+        if !status.success() {
+            bail!("Post-processing hook `{}` failed with {}", command, status);
+        }
+
+        let file = File::open(json_path).with_context(|| {
+            format!(
+                "Post-processing hook `{}` removed {}",
+                command,
+                json_path.display()
+            )
+        })?;
+        serde_json::from_reader::<_, IgnoredAny>(BufReader::new(file))
+            .with_context(|| format!("Post-processing hook `{}` left invalid JSON", command))?;
+    }
+    Ok(())
+}
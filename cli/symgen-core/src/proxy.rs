@@ -0,0 +1,85 @@
+use anyhow::{bail, Result};
+
+/// Host environment variables passed into the containers, in the spellings tools look for
+const PROXY_VARS: &[(&str, &str)] = &[
+    ("HTTP_PROXY", "http_proxy"),
+    ("HTTPS_PROXY", "https_proxy"),
+    ("NO_PROXY", "no_proxy"),
+];
+
+/// HTTP(S) proxy the containers reach the repositories and downloads through
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProxySettings {
+    pub http: Option<String>,
+    pub https: Option<String>,
+    /// Comma-separated hosts and domains reached directly
+    pub no_proxy: Option<String>,
+}
+
+impl ProxySettings {
+    /// The proxy given with `--proxy` (or `proxy` in the config) for both
+    /// schemes, else the host's `HTTP_PROXY`/`HTTPS_PROXY`; `NO_PROXY` always
+    /// comes from the host
+    pub fn resolve(proxy: Option<&str>) -> Result<Self> {
+        let host = |index: usize| {
+            let (upper, lower) = PROXY_VARS[index];
+            std::env::var(upper)
+                .or_else(|_| std::env::var(lower))
+                .ok()
+                .filter(|value| !value.is_empty())
+        };
+        let no_proxy = host(2);
+        let settings = match proxy {
+            Some(url) => {
+                if !(url.starts_with("http://") || url.starts_with("https://")) {
+                    bail!("Proxy must be an http:// or https:// URL (got '{}')", url);
+                }
+                Self {
+                    http: Some(url.to_string()),
+                    https: Some(url.to_string()),
+                    no_proxy,
+                }
+            }
+            None => Self {
+                http: host(0),
+                https: host(1),
+                no_proxy,
+            },
+        };
+        // The URLs end up in apt and dnf configuration as well as the environment
+        for url in [&settings.http, &settings.https].into_iter().flatten() {
+            if url.contains(|c: char| c.is_whitespace() || c == '"' || c == '\'') {
+                bail!("Proxy URL '{}' contains whitespace or quotes", url);
+            }
+        }
+        Ok(settings)
+    }
+
+    /// Whether no proxy is set (`NO_PROXY` alone changes nothing)
+    pub fn is_empty(&self) -> bool {
+        self.http.is_none() && self.https.is_none()
+    }
+
+    /// Container environment (`KEY=VALUE`), in both upper and lower case
+    pub fn env(&self) -> Vec<String> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        [&self.http, &self.https, &self.no_proxy]
+            .into_iter()
+            .zip(PROXY_VARS)
+            .filter_map(|(value, names)| value.as_ref().map(|value| (value, names)))
+            .flat_map(|(value, (upper, lower))| {
+                [
+                    format!("{}={}", upper, value),
+                    format!("{}={}", lower, value),
+                ]
+            })
+            .collect()
+    }
+
+    /// Proxy for package managers that take a single one (dnf, yum)
+    pub fn package_proxy(&self) -> Option<&str> {
+        self.http.as_deref().or(self.https.as_deref())
+    }
+}
@@ -0,0 +1,228 @@
+use anyhow::{anyhow, Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bollard::auth::DockerCredentials;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Registry of images named without one
+const DOCKER_HUB: &str = "docker.io";
+/// Key Docker stores Docker Hub credentials under
+const DOCKER_HUB_AUTH_KEY: &str = "https://index.docker.io/v1/";
+
+/// Where images are pulled from and with which credentials
+#[derive(Debug, Clone, Default)]
+pub struct RegistrySettings {
+    /// Prefix put in front of images named without a registry
+    /// (`registry.corp.local/mirror` turns `ubuntu:22.04` into
+    /// `registry.corp.local/mirror/ubuntu:22.04`)
+    pub mirror: Option<String>,
+    /// Docker `config.json`-format credentials file given explicitly; errors reading it are fatal
+    pub auth_file: Option<PathBuf>,
+}
+
+impl RegistrySettings {
+    /// The image actually pulled and run for `image`
+    pub fn image(&self, image: &str) -> String {
+        match &self.mirror {
+            Some(mirror) if explicit_registry(image).is_none() => {
+                format!("{}/{}", mirror.trim_end_matches('/'), image)
+            }
+            _ => image.to_string(),
+        }
+    }
+
+    /// Credentials for pulling `image` (as returned by [`Self::image`]), if any are stored.
+    ///
+    /// Without `auth_file`, Docker's own `config.json` is read if it exists.
+    pub fn credentials(&self, image: &str) -> Result<Option<DockerCredentials>> {
+        let registry = explicit_registry(image).unwrap_or(DOCKER_HUB);
+        let (path, explicit) = match &self.auth_file {
+            Some(path) => (path.clone(), true),
+            None => match default_auth_file() {
+                Some(path) if path.exists() => (path, false),
+                _ => return Ok(None),
+            },
+        };
+        match read_credentials(&path, registry) {
+            Ok(credentials) => Ok(credentials),
+            Err(e) if !explicit => {
+                tracing::warn!(
+                    "Ignoring registry credentials in {}: {:#}",
+                    path.display(),
+                    e
+                );
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// `$DOCKER_CONFIG/config.json`, else `~/.docker/config.json`
+pub fn default_auth_file() -> Option<PathBuf> {
+    match std::env::var_os("DOCKER_CONFIG") {
+        Some(dir) => Some(PathBuf::from(dir).join("config.json")),
+        None => dirs::home_dir().map(|home| home.join(".docker").join("config.json")),
+    }
+}
+
+/// Registry host named in an image reference; the first path component
+/// counts as one when it has a dot or port, or is `localhost`
+pub fn explicit_registry(image: &str) -> Option<&str> {
+    let (first, _) = image.split_once('/')?;
+    (first.contains('.') || first.contains(':') || first == "localhost").then_some(first)
+}
+
+/// The parts of Docker's `config.json` that hold credentials
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct AuthFile {
+    auths: HashMap<String, AuthEntry>,
+    creds_store: Option<String>,
+    cred_helpers: HashMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct AuthEntry {
+    /// base64 of `user:password`
+    auth: Option<String>,
+    identitytoken: Option<String>,
+}
+
+/// Reply of `docker-credential-<helper> get`
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct HelperCredentials {
+    username: String,
+    secret: String,
+}
+
+/// Credentials for `registry` in a `config.json`-format file: a per-registry
+/// credential helper, then a stored `auth` entry, then the default credential store
+fn read_credentials(path: &Path, registry: &str) -> Result<Option<DockerCredentials>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read registry credentials {}", path.display()))?;
+    let file: AuthFile = serde_json::from_str(&contents)
+        .with_context(|| format!("Invalid registry credentials {}", path.display()))?;
+    let server = if registry == DOCKER_HUB {
+        DOCKER_HUB_AUTH_KEY
+    } else {
+        registry
+    };
+
+    if let Some(helper) = file
+        .cred_helpers
+        .iter()
+        .find(|(key, _)| auth_key_host(key) == registry)
+        .map(|(_, helper)| helper)
+    {
+        return helper_credentials(helper, server);
+    }
+
+    let entry = file
+        .auths
+        .iter()
+        .find(|(key, entry)| {
+            auth_key_host(key) == registry
+                && (entry.auth.is_some() || entry.identitytoken.is_some())
+        })
+        .map(|(_, entry)| entry);
+    if let Some(entry) = entry {
+        let (username, password) = match &entry.auth {
+            Some(auth) => {
+                let decoded = STANDARD
+                    .decode(auth.trim())
+                    .ok()
+                    .and_then(|bytes| String::from_utf8(bytes).ok())
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "auth for {} in {} is not base64 user:password",
+                            registry,
+                            path.display()
+                        )
+                    })?;
+                let (username, password) = decoded.split_once(':').ok_or_else(|| {
+                    anyhow!(
+                        "auth for {} in {} is not base64 user:password",
+                        registry,
+                        path.display()
+                    )
+                })?;
+                (Some(username.to_string()), Some(password.to_string()))
+            }
+            None => (None, None),
+        };
+        return Ok(Some(DockerCredentials {
+            username,
+            password,
+            identitytoken: entry.identitytoken.clone(),
+            serveraddress: Some(server.to_string()),
+            ..Default::default()
+        }));
+    }
+
+    match &file.creds_store {
+        Some(store) => helper_credentials(store, server),
+        None => Ok(None),
+    }
+}
+
+/// Registry host of a `config.json` key (`https://index.docker.io/v1/`, `registry.corp.local:5000`, ...)
+fn auth_key_host(key: &str) -> &str {
+    let host = key
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .split('/')
+        .next()
+        .unwrap_or(key);
+    match host {
+        "index.docker.io" | "registry-1.docker.io" => DOCKER_HUB,
+        _ => host,
+    }
+}
+
+/// Ask `docker-credential-<helper>` for a server's credentials; none when it has no entry
+fn helper_credentials(helper: &str, server: &str) -> Result<Option<DockerCredentials>> {
+    let program = format!("docker-credential-{}", helper);
+    let mut child = Command::new(&program)
+        .arg("get")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", program))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(server.as_bytes())?;
+    }
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("Failed to run {}", program))?;
+    if !output.status.success() {
+        // "credentials not found in native keychain"
+        return Ok(None);
+    }
+    let reply: HelperCredentials = serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("Unexpected reply from {}", program))?;
+    // Helpers return identity tokens under the username `<token>`
+    let credentials = if reply.username == "<token>" {
+        DockerCredentials {
+            identitytoken: Some(reply.secret),
+            ..Default::default()
+        }
+    } else {
+        DockerCredentials {
+            username: Some(reply.username),
+            password: Some(reply.secret),
+            ..Default::default()
+        }
+    };
+    Ok(Some(DockerCredentials {
+        serveraddress: Some(server.to_string()),
+        ..credentials
+    }))
+}
@@ -0,0 +1,114 @@
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use crate::artifacts::sha256_file;
+use crate::banner_index::collect_symbol_files;
+use crate::isf;
+use crate::output::Output;
+
+/// Index file name Volatility3's `--remote-isf-url` is usually pointed at
+pub const INDEX_FILENAME: &str = "banners.json";
+
+/// What building a remote index found
+#[derive(Debug, Default, Serialize)]
+pub struct RemoteIndexStats {
+    /// Symbol files listed in the index
+    pub files: usize,
+    /// Distinct banners
+    pub banners: usize,
+    /// Files with the same content as one already listed, e.g. store symlinks
+    pub duplicates: usize,
+    /// Files that are not symbol files or record no banner
+    pub skipped: usize,
+}
+
+/// Build a Volatility3 remote ISF index (version 1) over the symbol files under `dir`.
+///
+/// Keys are the base64 `linux_banner` bytes exactly as the ISF records them,
+/// which is what Volatility3 compares against the banners it finds in memory.
+/// Each file is listed as `base_url` followed by its path relative to `dir`.
+pub fn build(
+    dir: &Path,
+    base_url: &str,
+    skip: Option<&Path>,
+    output: &Output,
+) -> Result<(Value, RemoteIndexStats)> {
+    if !base_url.starts_with("https://") && !base_url.starts_with("http://") {
+        bail!(
+            "The base URL must start with https:// or http:// (got {})",
+            base_url
+        );
+    }
+    let base_url = base_url.trim_end_matches('/');
+
+    let mut files = Vec::new();
+    collect_symbol_files(dir, &mut files)?;
+    files.sort();
+
+    let mut stats = RemoteIndexStats::default();
+    let mut seen = HashSet::new();
+    let mut linux: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for path in files {
+        if skip.is_some_and(|skip| skip == path) {
+            continue;
+        }
+        output.progress(&format!("Reading {}", path.display()));
+        let Some(identifier) = isf::load(&path)
+            .ok()
+            .and_then(|isf| banner_identifier(&isf))
+        else {
+            stats.skipped += 1;
+            continue;
+        };
+        if !seen.insert(sha256_file(&path)?) {
+            stats.duplicates += 1;
+            continue;
+        }
+
+        let relative = path
+            .strip_prefix(dir)
+            .context("Symbol file outside the index directory")?;
+        let relative: Vec<String> = relative
+            .components()
+            .map(|part| url_escape(&part.as_os_str().to_string_lossy()))
+            .collect();
+        linux
+            .entry(identifier)
+            .or_default()
+            .push(format!("{}/{}", base_url, relative.join("/")));
+        stats.files += 1;
+    }
+    stats.banners = linux.len();
+
+    let index = json!({
+        "version": 1,
+        "linux": linux,
+        "mac": {},
+        "windows": {},
+    });
+    Ok((index, stats))
+}
+
+/// The `linux_banner` constant data of an ISF, still base64-encoded
+fn banner_identifier(isf: &Value) -> Option<String> {
+    isf.pointer("/symbols/linux_banner/constant_data")
+        .and_then(Value::as_str)
+        .filter(|data| !data.is_empty())
+        .map(str::to_string)
+}
+
+/// Percent-encode a path segment, keeping the characters symbol file names use
+fn url_escape(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'+' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -0,0 +1,87 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::banner::{banner_regex, parse_banner, BannerParseResult, MAX_BANNER_LEN};
+
+/// Bytes read from the image at a time
+const CHUNK_SIZE: usize = 16 << 20;
+/// Bytes carried over between chunks so a banner crossing a chunk boundary is seen whole
+const OVERLAP: usize = MAX_BANNER_LEN + 64;
+/// Offsets kept per banner; a dump can hold thousands of copies of the same one
+const MAX_OFFSETS: usize = 16;
+
+/// A distinct kernel banner found in a memory image
+#[derive(Debug, Clone, Serialize)]
+pub struct ScannedBanner {
+    pub banner: String,
+    /// Number of copies found
+    pub count: usize,
+    /// File offsets of the first copies
+    pub offsets: Vec<u64>,
+    #[serde(flatten)]
+    pub result: BannerParseResult,
+}
+
+/// Scan a raw memory dump or LiME file for kernel banners.
+///
+/// The file is read in chunks, so images larger than memory work. LiME range
+/// headers are a few bytes between long runs of raw memory and need no special
+/// handling. Banners come back most frequent first: the running kernel's
+/// banner usually has many copies, while stray ones (page cache, old logs) are rare.
+pub fn scan_image(path: &Path) -> Result<Vec<ScannedBanner>> {
+    let mut file =
+        File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let re = banner_regex();
+    let mut found: Vec<ScannedBanner> = Vec::new();
+    let mut buf = Vec::with_capacity(CHUNK_SIZE + OVERLAP);
+    // File offset of buf[0]
+    let mut base = 0u64;
+
+    loop {
+        let read = (&mut file)
+            .take(CHUNK_SIZE as u64)
+            .read_to_end(&mut buf)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let eof = read < CHUNK_SIZE;
+        // Matches starting in the carried-over tail are found again, whole, in the next round
+        let limit = if eof {
+            buf.len()
+        } else {
+            buf.len().saturating_sub(OVERLAP)
+        };
+
+        for m in re.find_iter(&buf).take_while(|m| m.start() < limit) {
+            let Some(parsed) = parse_banner(m.as_bytes()).into_iter().next() else {
+                continue;
+            };
+            let offset = base + m.start() as u64;
+            match found.iter_mut().find(|entry| entry.banner == parsed.banner) {
+                Some(entry) => {
+                    entry.count += 1;
+                    if entry.offsets.len() < MAX_OFFSETS {
+                        entry.offsets.push(offset);
+                    }
+                }
+                None => found.push(ScannedBanner {
+                    banner: parsed.banner,
+                    count: 1,
+                    offsets: vec![offset],
+                    result: parsed.result,
+                }),
+            }
+        }
+
+        if eof {
+            break;
+        }
+        buf.drain(..limit);
+        base += limit as u64;
+    }
+
+    // Stable, so equally frequent banners stay in file order
+    found.sort_by_key(|banner| std::cmp::Reverse(banner.count));
+    Ok(found)
+}
@@ -0,0 +1,1848 @@
+use serde::{Deserialize, Serialize};
+
+use crate::acquisition::AcquisitionSource;
+use crate::config::ScriptOverrides;
+use crate::distros::{rpm_kernel_variant, wsl_release, Arch, Distro, DistroVersion};
+use crate::docker::DIAGNOSTICS_DIR;
+use crate::dwarf2json;
+use crate::proxy::ProxySettings;
+
+/// Default dwarf2json release used inside the containers
+pub const DWARF2JSON_URL: &str =
+    "https://github.com/volatilityfoundation/dwarf2json/releases/download/v0.8.0/dwarf2json-linux-amd64";
+/// Where a host-cached dwarf2json binary appears inside the container
+pub const DWARF2JSON_INPUT_PATH: &str = "/symgen-input/dwarf2json";
+/// dwarf2json module built from source on architectures without a release binary
+const DWARF2JSON_MODULE: &str = "github.com/volatilityfoundation/dwarf2json@v0.8.0";
+/// Ubuntu Debug Symbol Archive signing key, fetched when the keyring package is unavailable
+const DDEBS_KEY: &str = "F2EDC64DC5AEE1F6B9C621F0C8CAB6595FDFF622";
+/// Kali archive, serving kernels and their `-dbg` packages alike
+const KALI_MIRROR: &str = "http://http.kali.org/kali";
+/// Kali suites searched for debug packages: the rolling release, then the
+/// latest quarterly snapshot whose kernels may have left rolling already
+const KALI_DEBUG_SUITES: &[&str] = &["kali-rolling", "kali-last-snapshot"];
+/// Microsoft's WSL2 kernel tree, tagged `linux-msft-wsl-<release>` per release
+const WSL_KERNEL_REPO: &str = "microsoft/WSL2-Linux-Kernel";
+
+/// APT suites enabled for Debian kernel debug packages, per release.
+///
+/// `linux-image-*-dbg` is an ordinary package published next to the kernel, so
+/// kernels shipped through debian-security need the security suite itself;
+/// `-debug` suites carry the automatic dbgsym packages of everything else.
+const DEBIAN_DEBUG_SUITES: &[(&str, &[(&str, &str)])] = &[
+    (
+        "buster",
+        &[
+            ("http://archive.debian.org/debian-debug", "buster-debug"),
+            (
+                "http://archive.debian.org/debian-security",
+                "buster/updates",
+            ),
+        ],
+    ),
+    (
+        "bullseye",
+        &[
+            ("http://deb.debian.org/debian-debug", "bullseye-debug"),
+            (
+                "http://deb.debian.org/debian-debug",
+                "bullseye-proposed-updates-debug",
+            ),
+            (
+                "http://security.debian.org/debian-security",
+                "bullseye-security",
+            ),
+        ],
+    ),
+    (
+        "bookworm",
+        &[
+            ("http://deb.debian.org/debian-debug", "bookworm-debug"),
+            (
+                "http://deb.debian.org/debian-debug",
+                "bookworm-proposed-updates-debug",
+            ),
+            (
+                "http://security.debian.org/debian-security",
+                "bookworm-security",
+            ),
+        ],
+    ),
+];
+/// CentOS Stream Koji package stores, per release, for kernels gone from the mirrors
+const CENTOS_KOJI: &[(&str, &str)] =
+    &[("9", "https://kojihub.stream.centos.org/kojifiles/packages")];
+/// Seconds between progress lines while dwarf2json runs silently
+const HEARTBEAT_SECS: u32 = 15;
+/// apt configuration ignoring Release file dates, for hosts with a skewed clock
+const APT_IGNORE_DATES: &str = r#"# Release files look "not valid yet" or expired to a skewed clock (--fix-time)
+cat > /etc/apt/apt.conf.d/99symgen-fix-time << 'EOF'
+Acquire::Check-Valid-Until "false";
+Acquire::Check-Date "false";
+EOF
+"#;
+/// Variables filled in per run in site script templates (`Config::script_template`)
+pub const TEMPLATE_VARS: &[&str] = &["KERNEL", "DISTRO", "VERSION", "CODENAME", "ARCH"];
+/// Steps whose work is already done in images from `symgen build-images`
+const PREBAKED_STEPS: &[&str] = &["install_tools", "setup_keyring"];
+/// Go toolchain used for the source build (distro packages are too old on some releases)
+const GO_VERSION: &str = "1.22.5";
+
+/// Where the generated JSON gets xz-compressed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Compression {
+    /// Single-threaded `xz -9` inside the container
+    #[default]
+    Container,
+    /// Multithreaded xz on the host after the raw JSON is retrieved
+    Host,
+}
+
+/// Inputs to script rendering besides the release and kernel
+///
+/// New options keep being added, so outside this crate start from `Default`
+/// and set the fields needed:
+///
+/// ```
+/// use symgen_core::distros::{find_version, Arch, Distro};
+/// use symgen_core::script::{render_script, ScriptOptions};
+///
+/// let mut options = ScriptOptions::default();
+/// options.arch = Arch::Arm64;
+/// let version = find_version(Distro::Ubuntu, "22.04").unwrap();
+/// let script = render_script(&version, "5.15.0-91-generic", &options);
+/// assert!(script.steps.contains(&"install_debuginfo"));
+/// ```
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct ScriptOptions {
+    /// Architecture of the target kernel
+    pub arch: Arch,
+    /// Trust the Ubuntu proposed/ddebs repositories without checking signatures
+    pub allow_unauthenticated: bool,
+    /// Leave out the Ubuntu `-proposed` pocket
+    pub no_proposed: bool,
+    /// Leave out the Ubuntu `-updates` debug symbol pocket
+    pub no_updates: bool,
+    /// Site configuration for the distro
+    pub overrides: ScriptOverrides,
+    /// Extra arguments appended to the `dwarf2json linux` invocation
+    pub converter_args: Vec<String>,
+    /// Where the output gets compressed
+    pub compression: Compression,
+    /// How the debug kernel gets into the container
+    pub acquisition: AcquisitionSource,
+    /// Ignore Release file dates so a skewed clock does not break apt
+    pub fix_time: bool,
+    /// dwarf2json is mounted from the host cache at `DWARF2JSON_INPUT_PATH`
+    pub dwarf2json_mounted: bool,
+    /// Convert only the kernel's System.map, skipping the debug packages (`--delta-from`)
+    pub system_map_only: bool,
+    /// Exact Ubuntu package version to install (`5.15.0-91.101`), instead of whatever apt picks
+    pub package_version: Option<String>,
+    /// PEM CA certificates to trust before any HTTPS download
+    pub ca_certs: Vec<String>,
+    /// Proxy for apt, dnf and the downloads
+    pub proxy: ProxySettings,
+    /// Site template replacing the release's built-in repository steps
+    pub template: Option<String>,
+    /// The image was made by `symgen build-images`: tools and repository keys are installed
+    pub prebaked: bool,
+    /// The prebaked image holds the dwarf2json this run would fetch, at `/usr/local/bin/dwarf2json`
+    pub dwarf2json_baked: bool,
+    /// Shell code run as the `pre_script` step, ahead of every other step
+    pub pre_script: Option<String>,
+    /// Shell code run as the `post_script` step, after compression and before `finish`
+    pub post_script: Option<String>,
+}
+
+/// A generation script built from named steps
+///
+/// Naming the steps lets site configuration insert commands around them or
+/// replace them outright without forking the built-in scripts. Each step
+/// announces itself with an `@@STEP:<name>@@` line for the host to track.
+pub struct Script {
+    preamble: String,
+    steps: Vec<Step>,
+}
+
+struct Step {
+    name: &'static str,
+    body: String,
+}
+
+impl Script {
+    /// Start a script with the given preamble (shebang, shell options, variables)
+    pub fn new(preamble: impl Into<String>) -> Self {
+        Self {
+            preamble: preamble.into(),
+            steps: Vec::new(),
+        }
+    }
+
+    /// Append shell code to the preamble, ahead of every step
+    pub fn prelude(mut self, code: &str) -> Self {
+        self.preamble.push('\n');
+        self.preamble.push_str(code);
+        self
+    }
+
+    /// Append a named step
+    pub fn step(mut self, name: &'static str, body: impl Into<String>) -> Self {
+        self.steps.push(Step {
+            name,
+            body: body.into(),
+        });
+        self
+    }
+
+    /// Insert a named step ahead of step `before`, or at the end if the script lacks it
+    pub fn step_before(
+        mut self,
+        before: &str,
+        name: &'static str,
+        body: impl Into<String>,
+    ) -> Self {
+        let index = self
+            .steps
+            .iter()
+            .position(|step| step.name == before)
+            .unwrap_or(self.steps.len());
+        self.steps.insert(
+            index,
+            Step {
+                name,
+                body: body.into(),
+            },
+        );
+        self
+    }
+
+    /// Append shell code to the end of step `name`, if the script has it
+    pub fn extend_step(mut self, name: &str, code: &str) -> Self {
+        if let Some(step) = self.steps.iter_mut().find(|step| step.name == name) {
+            step.body.push_str(code);
+        }
+        self
+    }
+
+    /// Render the final script, applying any configured overrides
+    pub fn render(&self, overrides: &ScriptOverrides) -> String {
+        let mut script = self.preamble.clone();
+
+        for step in &self.steps {
+            script.push('\n');
+            script.push_str(&format!(
+                "SYMGEN_STEP={name}\necho \"@@STEP:{name}@@\"\n",
+                name = step.name
+            ));
+
+            if let Some(commands) = overrides.before.get(step.name) {
+                push_commands(&mut script, step.name, "before", commands);
+            }
+
+            match overrides.replace.get(step.name) {
+                Some(replacement) => {
+                    script.push_str(&format!("# {} (replaced by config)\n", step.name));
+                    script.push_str(replacement);
+                    if !replacement.ends_with('\n') {
+                        script.push('\n');
+                    }
+                }
+                None => script.push_str(&step.body),
+            }
+
+            if let Some(commands) = overrides.after.get(step.name) {
+                push_commands(&mut script, step.name, "after", commands);
+            }
+        }
+
+        script
+    }
+
+    /// Drop the named steps
+    pub fn without(mut self, names: &[&str]) -> Self {
+        self.steps.retain(|step| !names.contains(&step.name));
+        self
+    }
+
+    /// Drop every step after `name`
+    pub fn until(mut self, name: &str) -> Self {
+        if let Some(index) = self.steps.iter().position(|step| step.name == name) {
+            self.steps.truncate(index + 1);
+        }
+        self
+    }
+
+    /// Names of the steps, in the order they run
+    pub fn step_names(&self) -> Vec<&'static str> {
+        self.steps.iter().map(|step| step.name).collect()
+    }
+}
+
+/// A rendered script together with the steps it announces
+pub struct RenderedScript {
+    pub text: String,
+    pub steps: Vec<&'static str>,
+}
+
+fn push_commands(script: &mut String, step: &str, position: &str, commands: &[String]) {
+    script.push_str(&format!("# Configured commands {} {}\n", position, step));
+    for command in commands {
+        script.push_str(command);
+        script.push('\n');
+    }
+}
+
+/// File name of the symbol file generated for a kernel
+pub fn symbol_filename(kernel: &str, version: &DistroVersion, arch: Arch) -> String {
+    let distro_prefix = match version.distro {
+        Distro::Ubuntu => format!(
+            "Ubuntu_{}",
+            version.codename.as_ref().unwrap_or(&version.version)
+        ),
+        Distro::Debian => format!(
+            "Debian_{}",
+            version.codename.as_ref().unwrap_or(&version.version)
+        ),
+        Distro::Fedora => format!("Fedora_{}", version.version),
+        Distro::CentOS => format!("CentOS_{}", version.version),
+        Distro::RHEL => format!("RHEL_{}", version.version),
+        Distro::Oracle => format!("Oracle_{}", version.version),
+        Distro::Rocky => format!("Rocky_{}", version.version),
+        Distro::Alma => format!("Alma_{}", version.version),
+        Distro::Kali => format!("Kali_{}", version.version),
+        Distro::Wsl => "WSL2".to_string(),
+    };
+    // Ubuntu kernels are named the same on every architecture
+    if arch == Arch::Amd64 || arch.is_named_in(kernel) {
+        format!("{}_{}.json.xz", distro_prefix, kernel)
+    } else {
+        format!("{}_{}_{}.json.xz", distro_prefix, kernel, arch.name())
+    }
+}
+
+/// Render the generation script for a kernel on a distro release.
+///
+/// This is a pure function of its inputs: no Docker access, no config lookup.
+pub fn render_script(
+    version: &DistroVersion,
+    kernel: &str,
+    options: &ScriptOptions,
+) -> RenderedScript {
+    let arch = options.arch;
+    let overrides = &options.overrides;
+    let json_name = symbol_filename(kernel, version, arch);
+    let json_name = json_name.trim_end_matches(".xz");
+    let source_url = overrides
+        .dwarf2json_url
+        .as_deref()
+        .or(arch.has_dwarf2json_release().then_some(DWARF2JSON_URL));
+    let expected_sha256 = expected_dwarf2json_sha256(overrides, source_url);
+    let dwarf2json_url =
+        source_url.filter(|_| !options.dwarf2json_mounted && !options.dwarf2json_baked);
+    let setup_dwarf2json = match dwarf2json_url {
+        _ if options.dwarf2json_baked => dwarf2json_prebaked(expected_sha256),
+        _ if options.dwarf2json_mounted => dwarf2json_install(expected_sha256),
+        Some(url) => dwarf2json_download(url, expected_sha256),
+        None => dwarf2json_source_build(arch),
+    };
+
+    let script = if options.system_map_only {
+        system_map_script(version, kernel, options)
+    } else {
+        options
+            .acquisition
+            .resolve()
+            .script(version, kernel, options)
+    };
+    // Prebaked images come with the tools and keys these steps install
+    let script = if options.prebaked {
+        script.without(PREBAKED_STEPS)
+    } else {
+        script
+    };
+    // wget is available once install_tools has run; the download then overlaps
+    // the repository setup and debug package download instead of following them
+    let script = match dwarf2json_url {
+        Some(url) => script.extend_step("install_tools", &dwarf2json_prefetch(url)),
+        None => script,
+    };
+
+    let converter_args: String = options
+        .converter_args
+        .iter()
+        .map(|arg| format!(" {}", shell_quote(arg)))
+        .collect();
+
+    let script = add_common_steps(
+        proxy_prelude(
+            trust_prelude(time_prelude(script, version, options), options),
+            options,
+        )
+        .prelude(&failure_trap()),
+        kernel,
+        json_name,
+        setup_dwarf2json,
+        &converter_args,
+        options.compression,
+        options.system_map_only,
+        system_map_unpack(version, kernel, options),
+    );
+    let script = match &options.post_script {
+        Some(code) => script.step_before("finish", "post_script", hook_body(code, "--post-script")),
+        None => script,
+    };
+    let script = pre_script(script, options);
+    RenderedScript {
+        text: script.render(overrides),
+        steps: script.step_names(),
+    }
+}
+
+/// Where a run's dwarf2json comes from: its download URL, or the module built
+/// from source. Prebaked images record it to tell whether their copy fits a run.
+pub fn dwarf2json_source(arch: Arch, overrides: &ScriptOverrides) -> String {
+    match overrides.dwarf2json_url.as_deref() {
+        Some(url) => url.to_string(),
+        None if arch.has_dwarf2json_release() => DWARF2JSON_URL.to_string(),
+        None => format!("source:{}", DWARF2JSON_MODULE),
+    }
+}
+
+/// Render the script `symgen build-images` runs before saving the container as
+/// an image: the release's setup through its repository configuration, then
+/// dwarf2json installed at `/usr/local/bin/dwarf2json`
+pub fn render_bake_script(version: &DistroVersion, options: &ScriptOptions) -> RenderedScript {
+    let overrides = &options.overrides;
+    let source_url = overrides.dwarf2json_url.as_deref().or(options
+        .arch
+        .has_dwarf2json_release()
+        .then_some(DWARF2JSON_URL));
+    let setup_dwarf2json = match source_url {
+        Some(url) => dwarf2json_download(url, expected_dwarf2json_sha256(overrides, Some(url))),
+        None => dwarf2json_source_build(options.arch),
+    };
+    // WSL2 has no repositories to add
+    let setup = if version.distro == Distro::Wsl {
+        "install_tools"
+    } else {
+        "add_repos"
+    };
+    let script = trust_prelude(
+        time_prelude(distro_script(version, "", options), version, options),
+        options,
+    );
+    let script = proxy_prelude(script, options)
+        .prelude(&failure_trap())
+        .until(setup)
+        .step("setup_dwarf2json", setup_dwarf2json)
+        .step(
+            "finish",
+            r#"# Drop downloaded packages; the repository indexes stay for the next update
+(apt-get clean || dnf -y -q clean packages || yum -y -q clean packages) 2>/dev/null || true
+echo "=== Image setup completed successfully ==="
+"#,
+        );
+    let script = pre_script(script, options);
+    RenderedScript {
+        text: script.render(overrides),
+        steps: script.step_names(),
+    }
+}
+
+/// The release's built-in script, getting vmlinux from its debug repositories
+pub fn distro_script(version: &DistroVersion, kernel: &str, options: &ScriptOptions) -> Script {
+    let arch = options.arch;
+    match version.distro {
+        Distro::Ubuntu => ubuntu_script(
+            kernel,
+            version.codename.as_deref().unwrap_or("jammy"),
+            options,
+        ),
+        Distro::Debian => debian_script(kernel, version.codename.as_deref().unwrap_or("bookworm")),
+        Distro::Fedora => fedora_script(kernel, &version.version, arch),
+        Distro::CentOS => rhel_script(kernel, &version.version, "CentOS", arch),
+        Distro::RHEL => rhel_script(kernel, &version.version, "RHEL", arch),
+        Distro::Oracle => oracle_script(kernel, &version.version, arch),
+        Distro::Rocky => rhel_script(kernel, &version.version, "Rocky", arch),
+        Distro::Alma => rhel_script(kernel, &version.version, "Alma", arch),
+        Distro::Kali => kali_script(kernel),
+        Distro::Wsl => wsl_script(kernel, arch),
+    }
+}
+
+/// The release's setup followed by an `install_system_map` step fetching the
+/// kernel's regular (non-debug) package for its System.map, leaving `/boot/System.map-<kernel>`
+fn system_map_script(version: &DistroVersion, kernel: &str, options: &ScriptOptions) -> Script {
+    let install = match version.distro {
+        Distro::Ubuntu => {
+            let install_opts = if options.allow_unauthenticated {
+                " --allow-unauthenticated"
+            } else {
+                ""
+            };
+            let pin = version_pin(options);
+            format!(
+                r#"# System.map ships in linux-modules; the debug symbols are not needed
+echo ">>> Installing linux-modules-{kernel} for System.map..."
+if ! apt-get install -y -qq{install_opts} linux-modules-{kernel}{pin} 2>/dev/null; then
+    echo "ERROR: Could not find/install linux-modules-{kernel}"
+    exit 2
+fi
+"#
+            )
+        }
+        Distro::Debian | Distro::Kali => format!(
+            r#"# System.map ships in the kernel image package; the debug symbols are not needed
+echo ">>> Installing linux-image-{kernel} for System.map..."
+if ! apt-get install -y -qq linux-image-{kernel} 2>/dev/null; then
+    echo "ERROR: Could not find/install linux-image-{kernel}"
+    exit 2
+fi
+"#
+        ),
+        _ => {
+            let rpm_arch = options.arch.rpm_arch();
+            let packages = rpm_kernel_packages(version.distro, kernel);
+            format!(
+                r#"# Unpack System.map from the kernel package without installing it or its debuginfo
+echo ">>> Downloading the kernel package for System.map..."
+yum -y -q install cpio yum-utils 2>/dev/null || dnf -y -q install cpio dnf-plugins-core
+mkdir -p /tmp/symgen-kernel
+FOUND=""
+for PKG in {packages}; do
+    if (cd /tmp/symgen-kernel && (dnf download -q --arch {rpm_arch} "$PKG" 2>/dev/null || yumdownloader -q --archlist={rpm_arch} "$PKG" 2>/dev/null)) \
+        && ls /tmp/symgen-kernel/*.rpm >/dev/null 2>&1; then
+        FOUND="$PKG"
+        break
+    fi
+done
+if [ -z "$FOUND" ]; then
+    echo "ERROR: Could not download a kernel package for {kernel}"
+    exit 2
+fi
+(cd /tmp/symgen-kernel && for RPM in *.rpm; do rpm2cpio "$RPM" | cpio -idm --quiet; done)
+MAP=$(find /tmp/symgen-kernel -name 'System.map*' -type f | head -1)
+[ -n "$MAP" ] && cp "$MAP" "/boot/System.map-{kernel}"
+rm -rf /tmp/symgen-kernel
+"#
+            )
+        }
+    };
+
+    distro_script(version, kernel, options)
+        .until("install_tools")
+        .step("install_system_map", install)
+}
+
+/// Regular (non-debug) packages that may carry an rpm kernel's System.map, most likely first
+fn rpm_kernel_packages(distro: Distro, kernel: &str) -> String {
+    let (base, variant) = rpm_kernel_variant(kernel);
+    match (distro, variant) {
+        (Distro::Fedora, Some(variant)) => {
+            format!("kernel-{variant}-core-{base} kernel-{variant}-{base}")
+        }
+        (Distro::Oracle, _) if kernel.contains("uek") => {
+            format!("kernel-uek-core-{kernel} kernel-uek-{kernel}")
+        }
+        _ => format!("kernel-core-{base} kernel-{base}"),
+    }
+}
+
+/// Shell unpacking the kernel's regular package into `/tmp/symgen-map` without
+/// installing it, for its System.map. Never fails; WSL2 builds leave their own
+/// System.map in `/boot`, so there is nothing to unpack.
+fn system_map_unpack(version: &DistroVersion, kernel: &str, options: &ScriptOptions) -> String {
+    let (packages, fetch, unpack) = match version.distro {
+        Distro::Wsl => return String::new(),
+        Distro::Ubuntu => {
+            let pin = version_pin(options);
+            (
+                format!("linux-modules-{kernel}{pin} linux-image-unsigned-{kernel}{pin} linux-image-{kernel}{pin}"),
+                r#"apt-get download -qq "$PKG" 2>/dev/null"#.to_string(),
+                r#"for DEB in *.deb; do [ -f "$DEB" ] && dpkg-deb -x "$DEB" .; done"#,
+            )
+        }
+        Distro::Debian | Distro::Kali => (
+            format!("linux-image-{kernel}-unsigned linux-image-{kernel}"),
+            r#"apt-get download -qq "$PKG" 2>/dev/null"#.to_string(),
+            r#"for DEB in *.deb; do [ -f "$DEB" ] && dpkg-deb -x "$DEB" .; done"#,
+        ),
+        _ => {
+            let rpm_arch = options.arch.rpm_arch();
+            (
+                rpm_kernel_packages(version.distro, kernel),
+                format!(
+                    r#"(dnf download -q --arch {rpm_arch} "$PKG" 2>/dev/null || yumdownloader -q --archlist={rpm_arch} "$PKG" 2>/dev/null)"#
+                ),
+                r#"for RPM in *.rpm; do [ -f "$RPM" ] && rpm2cpio "$RPM" | cpio -idm --quiet; done"#,
+            )
+        }
+    };
+    let tools = if version.distro.uses_apt() {
+        ""
+    } else {
+        "    (yum -y -q install cpio yum-utils 2>/dev/null || dnf -y -q install cpio dnf-plugins-core 2>/dev/null) || true\n"
+    };
+    format!(
+        r#"    echo ">>> No System.map installed, unpacking the kernel package for it..."
+{tools}    mkdir -p /tmp/symgen-map
+    (cd /tmp/symgen-map && for PKG in {packages}; do {fetch} && break; done
+        {unpack}) || true
+    MAP=$(find /tmp/symgen-map -name 'System.map*' -type f 2>/dev/null | head -1)
+    if [ -n "$MAP" ]; then
+        SYSTEM_MAP="$MAP"
+        SYSTEM_MAP_SOURCE="package"
+    fi
+"#
+    )
+}
+
+/// Render a script listing the flavors a flavorless kernel version is published with.
+///
+/// Runs the release's repository setup and reports the flavors of matching
+/// debug packages as a comma-separated `flavors` result. Only Ubuntu and
+/// Debian kernel names carry a flavor suffix.
+pub fn render_flavor_probe(
+    version: &DistroVersion,
+    kernel: &str,
+    options: &ScriptOptions,
+) -> RenderedScript {
+    let escaped = kernel.replace('.', "\\.");
+    let (script, pattern) = match version.distro {
+        Distro::Debian | Distro::Kali => (
+            distro_script(version, kernel, options),
+            format!("^linux-image-{escaped}-(.+)-dbg$"),
+        ),
+        _ => (
+            ubuntu_script(
+                kernel,
+                version.codename.as_deref().unwrap_or("jammy"),
+                options,
+            ),
+            format!("^linux-image-(unsigned-)?{escaped}-(.+)-dbgsym$"),
+        ),
+    };
+
+    let script = proxy_prelude(trust_prelude(time_prelude(script, version, options), options), options)
+        .until("add_repos")
+        .step(
+            "list_flavors",
+            format!(
+                r#"# List the flavors this kernel version has debug symbols for
+echo ">>> Looking for flavors of {kernel}..."
+FLAVORS=$(apt-cache pkgnames linux-image- | grep -E '{pattern}' | sed -E 's/{pattern}/\{group}/; s/-unsigned$//' | sort -u | paste -sd, -)
+echo ">>> Flavors found: ${{FLAVORS:-none}}"
+echo "@@RESULT:flavors=$FLAVORS@@"
+"#,
+                group = if version.distro == Distro::Ubuntu { 2 } else { 1 },
+            ),
+        );
+    let script = pre_script(script, options);
+    RenderedScript {
+        text: script.render(&options.overrides),
+        steps: script.step_names(),
+    }
+}
+
+/// Render a script listing the kernels whose debug symbols the release's repositories hold.
+///
+/// Reports one `kernel` result per kernel as `<release> <build time>`, the
+/// build time being a Unix timestamp, or 0 where the index records none (apt).
+pub fn render_kernel_listing(
+    version: &DistroVersion,
+    flavor: &str,
+    options: &ScriptOptions,
+) -> RenderedScript {
+    let rpm_arch = options.arch.rpm_arch();
+    let list = match version.distro {
+        Distro::Ubuntu => format!(
+            r#"apt-cache pkgnames linux-image- | sed -nE 's/^linux-image-(unsigned-)?([0-9.]+-[0-9]+-{flavor})-dbgsym$/\2 0/p'"#
+        ),
+        Distro::Debian => format!(
+            r#"apt-cache pkgnames linux-image- | sed -nE 's/^linux-image-([0-9.]+-[0-9]+-{flavor})(-unsigned)?-dbg$/\1 0/p'"#
+        ),
+        // Kali kernels carry no ABI number (6.6.9-amd64)
+        Distro::Kali => format!(
+            r#"apt-cache pkgnames linux-image- | sed -nE 's/^linux-image-([0-9.]+-{flavor})-dbg$/\1 0/p'"#
+        ),
+        Distro::Oracle => format!(
+            r#"dnf repoquery -q --available --showduplicates --arch {rpm_arch} --qf '%{{version}}-%{{release}}.%{{arch}} %{{buildtime}}' kernel-debuginfo kernel-uek-debuginfo"#
+        ),
+        // Every tagged release can be built; the tags carry no dates
+        Distro::Wsl => format!(
+            r#"wget -qO- "https://api.github.com/repos/{WSL_KERNEL_REPO}/tags?per_page=100" | sed -nE 's/.*"name": *"linux-msft-wsl-([0-9.]+)".*/\1-microsoft-standard-WSL2 0/p'"#
+        ),
+        _ => format!(
+            r#"(dnf repoquery -q --available --showduplicates --arch {rpm_arch} --qf '%{{version}}-%{{release}}.%{{arch}} %{{buildtime}}' kernel-debuginfo 2>/dev/null || repoquery -q --show-duplicates --arch {rpm_arch} --qf '%{{version}}-%{{release}}.%{{arch}} %{{buildtime}}' kernel-debuginfo)"#
+        ),
+    };
+
+    // WSL2 kernels are listed from GitHub rather than from repositories
+    let setup = if version.distro == Distro::Wsl {
+        "install_tools"
+    } else {
+        "add_repos"
+    };
+    let script = trust_prelude(
+        time_prelude(distro_script(version, "", options), version, options),
+        options,
+    );
+    let script = proxy_prelude(script, options).until(setup).step(
+        "list_kernels",
+        format!(
+            r#"# List the kernels with debug symbols in the enabled repositories
+echo ">>> Listing published kernels..."
+{list} | sort -u | while read -r RELEASE BUILT; do
+    echo "@@RESULT:kernel=$RELEASE ${{BUILT:-0}}@@"
+done
+"#
+        ),
+    );
+    let script = pre_script(script, options);
+    RenderedScript {
+        text: script.render(&options.overrides),
+        steps: script.step_names(),
+    }
+}
+
+/// Steps shared by every distro once vmlinux has been located
+#[allow(clippy::too_many_arguments)]
+fn add_common_steps(
+    script: Script,
+    kernel: &str,
+    json_name: &str,
+    setup_dwarf2json: String,
+    converter_args: &str,
+    compression: Compression,
+    system_map_only: bool,
+    system_map_unpack: String,
+) -> Script {
+    let compress = match compression {
+        Compression::Container => {
+            r#"# Compress the symbol file
+echo ">>> Compressing symbol file..."
+xz -9 "$SYMBOL_FILE"
+"#
+        }
+        Compression::Host => {
+            r#"# The host compresses the raw JSON once it has been retrieved
+echo ">>> Leaving compression to the host..."
+"#
+        }
+    };
+    // Without an installed System.map, unpack the kernel package for one, else
+    // rebuild it from the vmlinux symbol table the way the kernel's mksysmap does
+    let missing_system_map = if system_map_only {
+        format!(
+            r#"    echo "ERROR: System.map not found for {kernel}"
+    exit 2"#
+        )
+    } else {
+        format!(
+            r#"{system_map_unpack}    if [ -z "$SYSTEM_MAP" ]; then
+        echo ">>> Deriving System.map from the vmlinux symbol table..."
+        command -v nm >/dev/null || apt-get install -y -qq binutils >/dev/null 2>&1 \
+            || dnf -y -q install binutils >/dev/null 2>&1 || yum -y -q install binutils >/dev/null 2>&1 || true
+        if command -v nm >/dev/null \
+            && nm -n "$VMLINUX" 2>/dev/null | grep -v -e ' [aNUw] ' -e '__crc_' -e ' \$[adt]' -e ' \.L' > "/tmp/System.map-{kernel}" \
+            && [ -s "/tmp/System.map-{kernel}" ]; then
+            SYSTEM_MAP="/tmp/System.map-{kernel}"
+            SYSTEM_MAP_SOURCE="symtab"
+        fi
+    fi
+    if [ -n "$SYSTEM_MAP" ]; then
+        echo ">>> Using System.map from $SYSTEM_MAP_SOURCE: $SYSTEM_MAP"
+        echo "@@RESULT:system_map=$SYSTEM_MAP@@"
+        echo "@@RESULT:system_map_source=$SYSTEM_MAP_SOURCE@@"
+    else
+        echo ">>> No System.map found or derivable, continuing without it..."
+    fi"#
+        )
+    };
+    // Name the package vmlinux came from, for the ISF metadata (--annotate)
+    let source_package = if system_map_only {
+        ""
+    } else {
+        r#"SOURCE_PACKAGE=""
+if command -v rpm >/dev/null && rpm -qf "$VMLINUX" >/dev/null 2>&1; then
+    SOURCE_PACKAGE=$(rpm -qf "$VMLINUX" | head -1)
+elif command -v dpkg-query >/dev/null && OWNER=$(dpkg-query -S "$VMLINUX" 2>/dev/null); then
+    SOURCE_PACKAGE=$(dpkg-query -W -f '${Package}=${Version}' "${OWNER%%:*}" 2>/dev/null || true)
+fi
+if [ -n "$SOURCE_PACKAGE" ]; then
+    echo "@@RESULT:source_package=$SOURCE_PACKAGE@@"
+fi
+"#
+    };
+    let convert = if system_map_only {
+        format!(
+            r#"/usr/local/bin/dwarf2json linux --system-map "$SYSTEM_MAP"{converter_args} > "$SYMBOL_FILE" &"#
+        )
+    } else {
+        format!(
+            r#"if [ -n "$SYSTEM_MAP" ]; then
+    /usr/local/bin/dwarf2json linux --elf "$VMLINUX" --system-map "$SYSTEM_MAP"{converter_args} > "$SYMBOL_FILE" &
+else
+    /usr/local/bin/dwarf2json linux --elf "$VMLINUX"{converter_args} > "$SYMBOL_FILE" &
+fi"#
+        )
+    };
+
+    script
+        .step("setup_dwarf2json", setup_dwarf2json)
+        .step(
+            "find_system_map",
+            format!(
+                r#"# Check for System.map
+SYSTEM_MAP=""
+SYSTEM_MAP_SOURCE=""
+if [ -f "/boot/System.map-{kernel}" ]; then
+    SYSTEM_MAP="/boot/System.map-{kernel}"
+    echo ">>> Found System.map: $SYSTEM_MAP"
+    echo "@@RESULT:system_map=$SYSTEM_MAP@@"
+    echo "@@RESULT:system_map_source=installed@@"
+else
+{missing_system_map}
+fi
+"#
+            ),
+        )
+        .step(
+            "generate",
+            format!(
+                r#"# Generate symbol file (output to the mounted volume)
+echo ">>> Generating Volatility3 symbol file..."
+SYMBOL_FILE="$OUTPUT_DIR/{json_name}"
+echo "@@RESULT:debuginfo_bytes=$(du -sb /usr/lib/debug 2>/dev/null | cut -f1)@@"
+{source_package}
+{convert}
+
+# dwarf2json prints nothing for minutes; report the output size periodically
+DWARF2JSON_PID=$!
+ELAPSED=0
+while kill -0 "$DWARF2JSON_PID" 2>/dev/null; do
+    sleep 1
+    ELAPSED=$((ELAPSED + 1))
+    if [ $((ELAPSED % {HEARTBEAT_SECS})) -eq 0 ]; then
+        WRITTEN=$(stat -c %s "$SYMBOL_FILE" 2>/dev/null || echo 0)
+        echo ">>> dwarf2json running for ${{ELAPSED}}s, $((WRITTEN / 1048576)) MiB written"
+    fi
+done
+wait "$DWARF2JSON_PID"
+"#
+            ),
+        )
+        .step("compress", compress)
+        .step(
+            "finish",
+            r#"echo "=== Symbol generation completed successfully ==="
+ls -la "$OUTPUT_DIR"
+"#,
+        )
+}
+
+fn ubuntu_script(kernel: &str, codename: &str, options: &ScriptOptions) -> Script {
+    // Only amd64 (and i386) live on the main archive; everything else is on ports
+    let archive_url = match options.arch {
+        Arch::Amd64 => "http://archive.ubuntu.com/ubuntu/",
+        _ => "http://ports.ubuntu.com/ubuntu-ports/",
+    };
+
+    // Archived or expired repositories can only be used with signature checks off
+    let (archive_trust, ddebs_trust, update_opts, install_opts) = if options.allow_unauthenticated {
+        (
+            "Trusted: yes",
+            "Trusted: yes",
+            " -o Acquire::AllowInsecureRepositories=true",
+            " --allow-unauthenticated",
+        )
+    } else {
+        (
+            "Signed-by: /usr/share/keyrings/ubuntu-archive-keyring.gpg",
+            "Signed-by: /usr/share/keyrings/ubuntu-dbgsym-keyring.gpg",
+            "",
+            "",
+        )
+    };
+
+    // Proposed occasionally carries kernels whose ABI does not match the published one
+    let proposed_repo = if options.no_proposed {
+        String::new()
+    } else {
+        format!(
+            r#"# Add Ubuntu proposed repository for newer kernel packages
+echo ">>> Adding proposed repository..."
+cat > /etc/apt/sources.list.d/proposed.sources << 'EOF'
+Types: deb
+URIs: {archive_url}
+Suites: {codename}-proposed
+Components: main restricted universe multiverse
+{archive_trust}
+EOF
+
+"#
+        )
+    };
+
+    let pin = version_pin(options);
+    let missing = match &options.package_version {
+        Some(version) => format!(
+            r#"        echo "ERROR: Could not find/install debug symbols for kernel {kernel} version {version}"
+        echo ">>> Versions available:"
+        apt-cache madison linux-image-{kernel}-dbgsym linux-image-unsigned-{kernel}-dbgsym 2>/dev/null || true"#
+        ),
+        None => format!(
+            r#"        echo "ERROR: Could not find/install debug symbols for kernel {kernel}""#
+        ),
+    };
+
+    let mut ddebs_suites = codename.to_string();
+    if !options.no_updates {
+        ddebs_suites.push_str(&format!(" {}-updates", codename));
+    }
+    if !options.no_proposed {
+        ddebs_suites.push_str(&format!(" {}-proposed", codename));
+    }
+
+    Script::new(format!(
+        r#"#!/bin/bash
+set -e
+
+echo "=== Starting symbol generation for Ubuntu kernel {kernel} ==="
+
+# Save output directory (the mounted volume)
+OUTPUT_DIR="$PWD"
+
+# Configure apt for non-interactive mode
+export DEBIAN_FRONTEND=noninteractive
+"#
+    ))
+    .step(
+        "update",
+        r#"# Update package lists
+echo ">>> Updating package lists..."
+apt-get update -qq
+"#,
+    )
+    .step(
+        "install_tools",
+        r#"# Install required packages
+echo ">>> Installing required packages..."
+apt-get install -y -qq wget xz-utils ca-certificates
+"#,
+    )
+    .step(
+        "setup_keyring",
+        format!(
+            r#"# Make sure the archive and debug symbol signing keys are present and current
+echo ">>> Refreshing Ubuntu archive keyrings..."
+apt-get install -y -qq --only-upgrade ubuntu-keyring 2>/dev/null || true
+apt-get install -y -qq ubuntu-dbgsym-keyring 2>/dev/null || true
+if [ ! -s /usr/share/keyrings/ubuntu-dbgsym-keyring.gpg ]; then
+    echo ">>> ubuntu-dbgsym-keyring unavailable, fetching the key from keyserver.ubuntu.com..."
+    apt-get install -y -qq gnupg dirmngr
+    GNUPGHOME=$(mktemp -d)
+    export GNUPGHOME
+    if gpg --batch --keyserver hkps://keyserver.ubuntu.com --recv-keys {DDEBS_KEY}; then
+        gpg --batch --export {DDEBS_KEY} > /usr/share/keyrings/ubuntu-dbgsym-keyring.gpg
+    else
+        echo "WARNING: Could not fetch the ddebs signing key (use --allow-unauthenticated for archived repos)"
+    fi
+    rm -rf "$GNUPGHOME"
+    unset GNUPGHOME
+fi
+"#
+        ),
+    )
+    .step(
+        "add_repos",
+        format!(
+            r#"{proposed_repo}# Add ddebs repository for debug symbols (using official DEB822 format)
+echo ">>> Adding ddebs repository..."
+cat > /etc/apt/sources.list.d/ddebs.sources << 'EOF'
+Types: deb
+URIs: http://ddebs.ubuntu.com/
+Suites: {ddebs_suites}
+Components: main restricted universe multiverse
+{ddebs_trust}
+EOF
+
+# Update with new repos
+apt-get update -qq{update_opts}
+"#
+        ),
+    )
+    .step(
+        "install_debuginfo",
+        format!(
+            r#"# Install kernel debug symbols package
+echo ">>> Installing kernel debug symbols for {kernel}..."
+# Kernels without a signed image (riscv64, armhf, ...) only ship the unsigned dbgsym
+if ! apt-get install -y -qq{install_opts} linux-image-{kernel}-dbgsym{pin} 2>/dev/null; then
+    echo ">>> Trying unsigned image package name..."
+    if ! apt-get install -y -qq{install_opts} linux-image-unsigned-{kernel}-dbgsym{pin} 2>/dev/null; then
+{missing}
+        exit 2
+    fi
+fi
+
+# Install linux-modules package to get System.map
+echo ">>> Installing linux-modules for System.map..."
+apt-get install -y -qq{install_opts} linux-modules-{kernel}{pin} 2>/dev/null || true
+"#
+        ),
+    )
+    .step(
+        "find_vmlinux",
+        format!(
+            r#"# Find vmlinux file from installed location
+echo ">>> Looking for vmlinux..."
+VMLINUX="/usr/lib/debug/boot/vmlinux-{kernel}"
+if [ ! -f "$VMLINUX" ]; then
+    # Try alternative location
+    VMLINUX=$(find /usr/lib/debug -name "vmlinux-{kernel}" -type f 2>/dev/null | head -1)
+fi
+
+if [ -z "$VMLINUX" ] || [ ! -f "$VMLINUX" ]; then
+    echo "ERROR: vmlinux not found in debug package"
+    echo ">>> Searching for any vmlinux files..."
+    find /usr/lib/debug -name "vmlinux*" -type f 2>/dev/null || true
+    exit 2
+fi
+echo ">>> Found vmlinux: $VMLINUX"
+echo "@@RESULT:vmlinux=$VMLINUX@@"
+"#
+        ),
+    )
+}
+
+fn debian_script(kernel: &str, codename: &str) -> Script {
+    let sources: String = match DEBIAN_DEBUG_SUITES
+        .iter()
+        .find(|(release, _)| *release == codename)
+    {
+        Some((_, suites)) => suites
+            .iter()
+            .map(|(uri, suite)| format!("deb {} {} main\n", uri, suite))
+            .collect(),
+        None => format!(
+            "deb http://deb.debian.org/debian-debug {}-debug main\n",
+            codename
+        ),
+    };
+    dbg_package_script(kernel, "Debian", &sources)
+}
+
+/// Kali publishes `-dbg` packages next to its kernels in the main archive
+/// rather than in a separate debug archive
+fn kali_script(kernel: &str) -> Script {
+    let sources: String = KALI_DEBUG_SUITES
+        .iter()
+        .map(|suite| format!("deb {} {} main\n", KALI_MIRROR, suite))
+        .collect();
+    dbg_package_script(kernel, "Kali", &sources)
+}
+
+/// Script for distributions shipping Debian-style `linux-image-<kernel>-dbg` packages
+fn dbg_package_script(kernel: &str, distro_name: &str, sources: &str) -> Script {
+    Script::new(format!(
+        r#"#!/bin/bash
+set -e
+
+echo "=== Starting symbol generation for {distro_name} kernel {kernel} ==="
+
+# Save output directory (the mounted volume)
+OUTPUT_DIR="$PWD"
+
+# Configure apt for non-interactive mode
+export DEBIAN_FRONTEND=noninteractive
+"#
+    ))
+    .step(
+        "update",
+        r#"# Update package lists
+echo ">>> Updating package lists..."
+apt-get update -qq
+"#,
+    )
+    .step(
+        "install_tools",
+        r#"# Install required packages
+echo ">>> Installing required packages..."
+apt-get install -y -qq wget xz-utils ca-certificates
+"#,
+    )
+    .step(
+        "add_repos",
+        format!(
+            r#"# Add {distro_name} debug repositories
+echo ">>> Adding debug repositories..."
+cat > /etc/apt/sources.list.d/debug.list << 'EOF'
+{sources}EOF
+
+# Update with new repos
+apt-get update -qq
+"#
+        ),
+    )
+    .step(
+        "install_debuginfo",
+        format!(
+            r#"# Install kernel debug symbols package
+echo ">>> Installing kernel debug symbols for {kernel}..."
+# Debian uses linux-image-<version>-dbg package naming
+if ! apt-get install -y -qq linux-image-{kernel}-dbg 2>/dev/null; then
+    # Try alternative package name
+    echo ">>> Trying alternative package name..."
+    if ! apt-get install -y -qq linux-image-{kernel}-unsigned-dbg 2>/dev/null; then
+        echo "ERROR: Could not find/install debug symbols for kernel {kernel}"
+        echo ">>> Available debug packages:"
+        apt-cache search linux-image | grep dbg || true
+        exit 2
+    fi
+fi
+
+# Install linux-image package to get System.map
+echo ">>> Installing linux-image for System.map..."
+apt-get install -y -qq linux-image-{kernel} 2>/dev/null || true
+"#
+        ),
+    )
+    .step(
+        "find_vmlinux",
+        format!(
+            r#"# Find vmlinux file from installed location
+echo ">>> Looking for vmlinux..."
+VMLINUX="/usr/lib/debug/boot/vmlinux-{kernel}"
+if [ ! -f "$VMLINUX" ]; then
+    # Try alternative locations
+    VMLINUX=$(find /usr/lib/debug -name "vmlinux-{kernel}" -type f 2>/dev/null | head -1)
+fi
+
+if [ -z "$VMLINUX" ] || [ ! -f "$VMLINUX" ]; then
+    echo "ERROR: vmlinux not found in debug package"
+    echo ">>> Searching for any vmlinux files..."
+    find /usr/lib/debug -name "vmlinux*" -type f 2>/dev/null || true
+    exit 2
+fi
+echo ">>> Found vmlinux: $VMLINUX"
+echo "@@RESULT:vmlinux=$VMLINUX@@"
+"#
+        ),
+    )
+}
+
+/// Build vmlinux from the WSL2 kernel release's tagged source with the config
+/// Microsoft ships in the tree, debug info turned on.
+///
+/// Debug info only adds sections, so type layouts and symbol addresses match
+/// the published kernel built from the same source and compiler settings.
+fn wsl_script(kernel: &str, arch: Arch) -> Script {
+    let release = wsl_release(kernel).unwrap_or_else(|| kernel.to_string());
+    // Releases before 5.15 kept the config under arch/
+    let (config, old_config, make_arch) = match arch {
+        Arch::Arm64 => (
+            "Microsoft/config-wsl-arm64",
+            "arch/arm64/configs/config-wsl",
+            "arm64",
+        ),
+        _ => (
+            "Microsoft/config-wsl",
+            "arch/x86/configs/config-wsl",
+            "x86_64",
+        ),
+    };
+    Script::new(format!(
+        r#"#!/bin/bash
+set -e
+
+echo "=== Starting symbol generation for WSL2 kernel {kernel} ==="
+
+# Save output directory (the mounted volume)
+OUTPUT_DIR="$PWD"
+
+# Configure apt for non-interactive mode
+export DEBIAN_FRONTEND=noninteractive
+"#
+    ))
+    .step(
+        "update",
+        r#"# Update package lists
+echo ">>> Updating package lists..."
+apt-get update -qq
+"#,
+    )
+    .step(
+        "install_tools",
+        r#"# Install required packages
+echo ">>> Installing required packages..."
+apt-get install -y -qq wget xz-utils ca-certificates
+"#,
+    )
+    .step(
+        "fetch_source",
+        format!(
+            r#"# Download the release's source; older releases were tagged with the kernel name
+echo ">>> Downloading WSL2 kernel source for {release}..."
+mkdir -p /usr/src/wsl
+FOUND=""
+for TAG in linux-msft-wsl-{release} {kernel}; do
+    if wget -q "https://github.com/{WSL_KERNEL_REPO}/archive/refs/tags/$TAG.tar.gz" -O /tmp/wsl-kernel.tar.gz; then
+        FOUND="$TAG"
+        break
+    fi
+done
+if [ -z "$FOUND" ]; then
+    echo "ERROR: No source tag for WSL2 kernel {kernel} in {WSL_KERNEL_REPO}"
+    exit 2
+fi
+echo ">>> Found tag $FOUND"
+tar -C /usr/src/wsl --strip-components=1 -xzf /tmp/wsl-kernel.tar.gz
+rm -f /tmp/wsl-kernel.tar.gz
+"#
+        ),
+    )
+    .step(
+        "build_vmlinux",
+        format!(
+            r#"# Build vmlinux with Microsoft's config plus full debug info
+echo ">>> Installing the kernel build toolchain..."
+apt-get install -y -qq build-essential flex bison bc cpio kmod libssl-dev libelf-dev python3 dwarves
+cd /usr/src/wsl
+CONFIG={config}
+[ -f "$CONFIG" ] || CONFIG={old_config}
+if [ ! -f "$CONFIG" ]; then
+    echo "ERROR: The WSL2 source tree carries no kernel config"
+    exit 2
+fi
+cp "$CONFIG" .config
+scripts/config --file .config -e DEBUG_INFO -e DEBUG_INFO_DWARF_TOOLCHAIN_DEFAULT \
+    -d DEBUG_INFO_REDUCED -d DEBUG_INFO_SPLIT -d DEBUG_INFO_NONE -d DEBUG_INFO_BTF
+make -s ARCH={make_arch} olddefconfig
+BUILT=$(make -s ARCH={make_arch} kernelrelease)
+if [ "$BUILT" != "{kernel}" ]; then
+    echo "WARNING: The source builds kernel $BUILT, not {kernel}"
+fi
+echo ">>> Building vmlinux with $(nproc) jobs (this takes a while)..."
+make -s ARCH={make_arch} -j"$(nproc)" vmlinux
+mkdir -p /usr/lib/debug/boot
+cp vmlinux /usr/lib/debug/boot/vmlinux-{kernel}
+cp System.map /boot/System.map-{kernel}
+cd "$OUTPUT_DIR"
+rm -rf /usr/src/wsl
+"#
+        ),
+    )
+    .step(
+        "find_vmlinux",
+        format!(
+            r#"VMLINUX="/usr/lib/debug/boot/vmlinux-{kernel}"
+if [ ! -f "$VMLINUX" ]; then
+    echo "ERROR: vmlinux was not built"
+    exit 2
+fi
+echo ">>> Found vmlinux: $VMLINUX"
+echo "@@RESULT:vmlinux=$VMLINUX@@"
+"#
+        ),
+    )
+}
+
+fn fedora_script(kernel: &str, fedora_version: &str, arch: Arch) -> Script {
+    let rpm_arch = arch.rpm_arch();
+    // `+debug`, `+16k` and `+16k-debug` kernels ship as kernel-<variant>-debuginfo
+    let (base, variant) = rpm_kernel_variant(kernel);
+    let debuginfo = match variant {
+        Some(variant) => format!("kernel-{}-debuginfo", variant),
+        None => "kernel-debuginfo".to_string(),
+    };
+    Script::new(format!(
+        r#"#!/bin/bash
+set -e
+
+echo "=== Starting symbol generation for Fedora {fedora_version} kernel {kernel} ==="
+
+# Save output directory (the mounted volume)
+OUTPUT_DIR="$PWD"
+"#
+    ))
+    .step(
+        "update",
+        r#"# Update package lists
+echo ">>> Updating package lists..."
+dnf -y -q update
+"#,
+    )
+    .step(
+        "install_tools",
+        r#"# Install required packages
+echo ">>> Installing required packages..."
+dnf -y -q install wget xz findutils
+"#,
+    )
+    .step(
+        "add_repos",
+        r#"# Enable debuginfo repository
+echo ">>> Adding debug repository..."
+dnf -y -q install dnf-plugins-core
+dnf config-manager --set-enabled fedora-debuginfo updates-debuginfo || true
+"#,
+    )
+    .step(
+        "install_debuginfo",
+        format!(
+            r#"# Install kernel debug symbols
+echo ">>> Installing kernel debug symbols for {kernel}..."
+if ! dnf -y -q install {debuginfo}-{base} 2>/dev/null; then
+    # Try with common suffix variants
+    if ! dnf -y -q install kernel-debuginfo-common-{rpm_arch}-{base} {debuginfo}-{base} 2>/dev/null; then
+        echo "ERROR: Could not find/install debug symbols for kernel {kernel}"
+        echo ">>> Available debug packages:"
+        dnf search {debuginfo} 2>/dev/null | head -20 || true
+        exit 2
+    fi
+fi
+"#
+        ),
+    )
+    .step(
+        "find_vmlinux",
+        format!(
+            r#"# Find vmlinux file (exclude .py/.pyc files and search in kernel module path)
+echo ">>> Looking for vmlinux..."
+VMLINUX=$(find /usr/lib/debug -path "*{kernel}*/vmlinux" -type f 2>/dev/null | head -1)
+if [ -z "$VMLINUX" ]; then
+    VMLINUX=$(find /usr/lib/debug -name "vmlinux" -type f 2>/dev/null | grep "{kernel}" | head -1)
+fi
+
+if [ -z "$VMLINUX" ] || [ ! -f "$VMLINUX" ]; then
+    echo "ERROR: vmlinux not found in debug package"
+    echo ">>> Searching for vmlinux files..."
+    find /usr/lib/debug -name "vmlinux" -type f 2>/dev/null || true
+    exit 2
+fi
+echo ">>> Found vmlinux: $VMLINUX"
+echo "@@RESULT:vmlinux=$VMLINUX@@"
+"#
+        ),
+    )
+}
+
+fn rhel_script(kernel: &str, rhel_version: &str, distro_name: &str, arch: Arch) -> Script {
+    let rpm_arch = arch.rpm_arch();
+    // aarch64 `+64k` kernels ship as kernel-64k and kernel-64k-debuginfo
+    let (base, variant) = rpm_kernel_variant(kernel);
+    let (image, debuginfo) = match variant {
+        Some(variant) => (
+            format!("kernel-{}", variant),
+            format!("kernel-{}-debuginfo", variant),
+        ),
+        None => ("kernel".to_string(), "kernel-debuginfo".to_string()),
+    };
+    let koji_fallback = match distro_name {
+        "CentOS" => centos_koji_fallback(kernel, rhel_version, arch),
+        _ => String::new(),
+    };
+    let vault_repo = match distro_name {
+        "Rocky" | "Alma" => vault_repo(kernel, distro_name, arch),
+        _ => String::new(),
+    };
+
+    Script::new(format!(
+        r#"#!/bin/bash
+set -e
+
+echo "=== Starting symbol generation for {distro_name} {rhel_version} kernel {kernel} ==="
+
+# Save output directory (the mounted volume)
+OUTPUT_DIR="$PWD"
+"#
+    ))
+    .step(
+        "update",
+        r#"# Update package lists
+echo ">>> Updating package lists..."
+yum -y -q update 2>/dev/null || dnf -y -q update
+"#,
+    )
+    .step(
+        "install_tools",
+        r#"# Install required packages
+echo ">>> Installing required packages..."
+yum -y -q install wget xz findutils 2>/dev/null || dnf -y -q install wget xz findutils
+"#,
+    )
+    .step(
+        "add_repos",
+        format!(
+            r#"# Enable debuginfo repository
+echo ">>> Adding debug repository..."
+yum -y -q install yum-utils 2>/dev/null || dnf -y -q install dnf-plugins-core
+{vault_repo}debuginfo-install -y {image}-{base} 2>/dev/null || true
+"#
+        ),
+    )
+    .step(
+        "install_debuginfo",
+        format!(
+            r#"# Alternative: try to install kernel-debuginfo directly
+echo ">>> Installing kernel debug symbols for {kernel}..."
+if ! yum -y -q install {debuginfo}-{base} 2>/dev/null; then
+    if ! dnf -y -q install {debuginfo}-{base} 2>/dev/null; then
+        # Try common package
+        yum -y -q install kernel-debuginfo-common-{rpm_arch}-{base} {debuginfo}-{base} 2>/dev/null || \
+        dnf -y -q install kernel-debuginfo-common-{rpm_arch}-{base} {debuginfo}-{base} 2>/dev/null || true
+    fi
+fi
+{koji_fallback}"#
+        ),
+    )
+    .step(
+        "find_vmlinux",
+        format!(
+            r#"# Find vmlinux file
+echo ">>> Looking for vmlinux..."
+VMLINUX=$(find /usr/lib/debug -name "vmlinux-{kernel}*" -type f 2>/dev/null | head -1)
+if [ -z "$VMLINUX" ]; then
+    VMLINUX=$(find /usr/lib/debug -name "vmlinux*" -path "*{kernel}*" -type f 2>/dev/null | head -1)
+fi
+
+if [ -z "$VMLINUX" ] || [ ! -f "$VMLINUX" ]; then
+    echo "ERROR: vmlinux not found in debug package"
+    echo ">>> Searching for any vmlinux files..."
+    find /usr/lib/debug -name "vmlinux*" -type f 2>/dev/null || true
+    exit 2
+fi
+echo ">>> Found vmlinux: $VMLINUX"
+echo "@@RESULT:vmlinux=$VMLINUX@@"
+"#
+        ),
+    )
+}
+
+fn oracle_script(kernel: &str, oracle_version: &str, arch: Arch) -> Script {
+    let rpm_arch = arch.rpm_arch();
+    Script::new(format!(
+        r#"#!/bin/bash
+set -e
+
+echo "=== Starting symbol generation for Oracle Linux {oracle_version} kernel {kernel} ==="
+
+# Save output directory (the mounted volume)
+OUTPUT_DIR="$PWD"
+"#
+    ))
+    .step(
+        "update",
+        r#"# Update package lists
+echo ">>> Updating package lists..."
+dnf -y -q makecache
+"#,
+    )
+    .step(
+        "install_tools",
+        r#"# Install required packages
+echo ">>> Installing required packages..."
+dnf -y -q install wget xz findutils dnf-plugins-core
+"#,
+    )
+    .step(
+        "add_repos",
+        format!(
+            r#"# Add Oracle Linux debuginfo repository from oss.oracle.com (correct location)
+echo ">>> Adding Oracle Linux debuginfo repository..."
+cat > /etc/yum.repos.d/ol_debuginfo.repo << 'REPOEOF'
+[ol_debuginfo]
+name=Oracle Linux {oracle_version} Debuginfo
+baseurl=https://oss.oracle.com/ol{oracle_version}/debuginfo/
+gpgkey=file:///etc/pki/rpm-gpg/RPM-GPG-KEY-oracle
+gpgcheck=1
+enabled=1
+REPOEOF
+
+# Refresh metadata with new repos
+echo ">>> Refreshing repository metadata..."
+dnf -y makecache 2>&1 | tail -5
+
+# List available debuginfo repos
+echo ">>> Available debuginfo repos:"
+dnf repolist | grep -i debug || true
+"#
+        ),
+    )
+    .step(
+        "install_debuginfo",
+        format!(
+            r#"# Try to install kernel debug symbols
+echo ">>> Installing kernel debug symbols for {kernel}..."
+
+# Detect kernel type and install appropriate debuginfo
+if echo "{kernel}" | grep -q "uek"; then
+    echo ">>> Detected UEK kernel..."
+    dnf -y install kernel-uek-debuginfo-{kernel} 2>&1 | tail -10 || true
+else
+    echo ">>> Detected RHCK kernel..."
+    dnf -y install kernel-debuginfo-{kernel} kernel-debuginfo-common-{rpm_arch}-{kernel} 2>&1 | tail -10 || true
+fi
+"#
+        ),
+    )
+    .step(
+        "find_vmlinux",
+        format!(
+            r#"# Find vmlinux file
+echo ">>> Looking for vmlinux..."
+VMLINUX=$(find /usr/lib/debug -name "vmlinux-{kernel}*" -type f 2>/dev/null | head -1)
+if [ -z "$VMLINUX" ]; then
+    VMLINUX=$(find /usr/lib/debug -name "vmlinux*" -path "*{kernel}*" -type f 2>/dev/null | head -1)
+fi
+
+if [ -z "$VMLINUX" ] || [ ! -f "$VMLINUX" ]; then
+    echo "ERROR: vmlinux not found in debug package"
+    echo ">>> Searching for any vmlinux files..."
+    find /usr/lib/debug -name "vmlinux*" -type f 2>/dev/null || true
+    echo ">>> Listing installed debuginfo packages..."
+    rpm -qa | grep -i debuginfo || true
+    exit 2
+fi
+echo ">>> Found vmlinux: $VMLINUX"
+echo "@@RESULT:vmlinux=$VMLINUX@@"
+"#
+        ),
+    )
+}
+
+/// Snippet fetching debuginfo RPMs straight from the CentOS Stream Koji.
+///
+/// Superseded Stream kernels drop out of the debuginfo mirrors but their
+/// builds stay in Koji, addressed by name/version/release/arch.
+fn centos_koji_fallback(kernel: &str, release: &str, arch: Arch) -> String {
+    let Some((_, koji)) = CENTOS_KOJI.iter().find(|(version, _)| *version == release) else {
+        return String::new();
+    };
+    let rpm_arch = arch.rpm_arch();
+    let nvr = kernel
+        .strip_suffix(&format!(".{}", rpm_arch))
+        .unwrap_or(kernel);
+    let Some((version, rel)) = nvr.split_once('-') else {
+        return String::new();
+    };
+
+    format!(
+        r#"
+# Superseded Stream kernels only remain in Koji
+if ! rpm -q kernel-debuginfo-{kernel} >/dev/null 2>&1; then
+    echo ">>> Not in the repositories, fetching debuginfo from the CentOS Stream Koji..."
+    KOJI_DIR="{koji}/kernel/{version}/{rel}/{rpm_arch}"
+    mkdir -p /tmp/koji
+    if wget -q "$KOJI_DIR/kernel-debuginfo-common-{rpm_arch}-{version}-{rel}.{rpm_arch}.rpm" -P /tmp/koji && \
+       wget -q "$KOJI_DIR/kernel-debuginfo-{version}-{rel}.{rpm_arch}.rpm" -P /tmp/koji; then
+        rpm -i --nodeps /tmp/koji/*.rpm
+    else
+        echo ">>> Build not found in Koji: $KOJI_DIR"
+    fi
+    rm -rf /tmp/koji
+fi
+"#
+    )
+}
+
+/// Repo file pointing at the vault for the minor release a kernel was built for.
+///
+/// The images track the latest minor release, whose mirrors drop older
+/// kernels; `.el9_2` style suffixes name the release that still has them.
+fn vault_repo(kernel: &str, distro_name: &str, arch: Arch) -> String {
+    let Some(caps) = cached_regex!(r"\.el(\d+)_(\d+)").captures(kernel) else {
+        return String::new();
+    };
+    let (major, minor) = (&caps[1], &caps[2]);
+    let rpm_arch = arch.rpm_arch();
+
+    let (baseurl, gpgkey) = match (distro_name, major) {
+        ("Rocky", "8") => (
+            format!("https://dl.rockylinux.org/vault/rocky/{major}.{minor}/BaseOS/{rpm_arch}/debug/tree/"),
+            "RPM-GPG-KEY-rockyofficial",
+        ),
+        ("Rocky", _) => (
+            format!("https://dl.rockylinux.org/vault/rocky/{major}.{minor}/BaseOS/{rpm_arch}/debug/tree/"),
+            "RPM-GPG-KEY-Rocky-9",
+        ),
+        ("Alma", "8") => (
+            format!("https://vault.almalinux.org/{major}.{minor}/BaseOS/debug/{rpm_arch}/"),
+            "RPM-GPG-KEY-AlmaLinux",
+        ),
+        _ => (
+            format!("https://vault.almalinux.org/{major}.{minor}/BaseOS/debug/{rpm_arch}/"),
+            "RPM-GPG-KEY-AlmaLinux-9",
+        ),
+    };
+
+    format!(
+        r#"# Kernels from earlier minor releases only remain in the vault
+echo ">>> Adding {distro_name} {major}.{minor} vault debuginfo repository..."
+cat > /etc/yum.repos.d/symgen-vault.repo << 'REPOEOF'
+[symgen-vault-debuginfo]
+name={distro_name} {major}.{minor} BaseOS debuginfo (vault)
+baseurl={baseurl}
+gpgkey=file:///etc/pki/rpm-gpg/{gpgkey}
+gpgcheck=1
+enabled=1
+skip_if_unavailable=1
+REPOEOF
+"#
+    )
+}
+
+/// `=<version>` suffix pinning an apt install to the requested Ubuntu upload
+fn version_pin(options: &ScriptOptions) -> String {
+    options
+        .package_version
+        .as_ref()
+        .map(|version| format!("={}", version))
+        .unwrap_or_default()
+}
+
+/// Turn off apt's date checks when asked to (`--fix-time`)
+///
+/// Containers share the host's kernel clock, so the time itself cannot be
+/// corrected from inside; dnf does not check metadata dates and needs nothing.
+fn time_prelude(script: Script, version: &DistroVersion, options: &ScriptOptions) -> Script {
+    if options.fix_time && version.distro.uses_apt() {
+        script.prelude(APT_IGNORE_DATES)
+    } else {
+        script
+    }
+}
+
+/// Install the configured CA certificates (`--ca-cert`) ahead of every step,
+/// so downloads through a TLS-intercepting proxy verify.
+///
+/// Images without the update tools pick the certificates up when the
+/// `ca-certificates` package is installed, whose setup rebuilds the bundle.
+fn trust_prelude(script: Script, options: &ScriptOptions) -> Script {
+    if options.ca_certs.is_empty() {
+        return script;
+    }
+    let mut code = String::from(
+        "# Trust the site's CA certificates (--ca-cert)\nmkdir -p /usr/local/share/ca-certificates\n",
+    );
+    for (i, pem) in options.ca_certs.iter().enumerate() {
+        code.push_str(&format!(
+            "cat > /usr/local/share/ca-certificates/symgen-{i}.crt << 'SYMGEN_CA'\n{}\nSYMGEN_CA\n",
+            pem.trim()
+        ));
+    }
+    code.push_str(
+        r#"if [ -d /etc/pki/ca-trust/source/anchors ]; then
+    cp /usr/local/share/ca-certificates/symgen-*.crt /etc/pki/ca-trust/source/anchors/
+fi
+if command -v update-ca-certificates >/dev/null; then
+    update-ca-certificates >/dev/null 2>&1 || true
+fi
+if command -v update-ca-trust >/dev/null; then
+    update-ca-trust extract || true
+fi
+"#,
+    );
+    script.prelude(&code)
+}
+
+/// The site's `--pre-script` as the first step, so it can register
+/// subscriptions or set up repositories before anything is installed
+fn pre_script(script: Script, options: &ScriptOptions) -> Script {
+    match &options.pre_script {
+        Some(code) => {
+            let first = script
+                .steps
+                .first()
+                .map(|step| step.name)
+                .unwrap_or_default();
+            script.step_before(first, "pre_script", hook_body(code, "--pre-script"))
+        }
+        None => script,
+    }
+}
+
+/// Site shell code run inline, so variables it exports reach the later steps
+fn hook_body(code: &str, option: &str) -> String {
+    let mut body = format!("# Site script ({})\n{}", option, code);
+    if !body.ends_with('\n') {
+        body.push('\n');
+    }
+    body
+}
+
+/// Route apt, dnf/yum and the downloads through the configured proxy (`--proxy`
+/// or the host's `HTTP_PROXY`/`HTTPS_PROXY`).
+///
+/// The variables are exported as well as set on the container, so a printed
+/// script run by hand goes through the proxy too.
+fn proxy_prelude(script: Script, options: &ScriptOptions) -> Script {
+    let proxy = &options.proxy;
+    let Some(package_proxy) = proxy.package_proxy() else {
+        return script;
+    };
+    let mut code = String::from("# Reach the repositories through the proxy\n");
+    for variable in proxy.env() {
+        let (name, value) = variable.split_once('=').unwrap_or((&variable, ""));
+        code.push_str(&format!("export {}={}\n", name, shell_quote(value)));
+    }
+    code.push_str("if [ -d /etc/apt/apt.conf.d ]; then\n    cat > /etc/apt/apt.conf.d/99symgen-proxy << 'EOF'\n");
+    if let Some(url) = &proxy.http {
+        code.push_str(&format!("Acquire::http::Proxy \"{}\";\n", url));
+    }
+    if let Some(url) = &proxy.https {
+        code.push_str(&format!("Acquire::https::Proxy \"{}\";\n", url));
+    }
+    code.push_str(&format!(
+        r#"EOF
+fi
+# yum.conf is a link to dnf.conf on newer releases; only the first gets the setting
+for conf in /etc/dnf/dnf.conf /etc/yum.conf; do
+    if [ -f "$conf" ] && ! grep -q '^proxy=' "$conf"; then
+        sed -i '/^\[main\]/a proxy={package_proxy}' "$conf"
+    fi
+done
+"#
+    ));
+    script.prelude(&code)
+}
+
+/// Trap saving package, repository and disk state when the script fails
+fn failure_trap() -> String {
+    format!(
+        r#"# Collect diagnostics for bug reports when any step fails
+collect_diagnostics() {{
+    status=$?
+    [ "$status" -eq 0 ] && return
+    mkdir -p {DIAGNOSTICS_DIR}/repos || return
+    echo "$status" > {DIAGNOSTICS_DIR}/exit-code.txt || true
+    echo "${{SYMGEN_STEP:-}}" > {DIAGNOSTICS_DIR}/failed-step.txt || true
+    (dpkg -l 2>/dev/null || rpm -qa 2>/dev/null) > {DIAGNOSTICS_DIR}/packages.txt || true
+    df -h > {DIAGNOSTICS_DIR}/df.txt 2>&1 || true
+    cp /etc/os-release {DIAGNOSTICS_DIR}/os-release.txt 2>/dev/null || true
+    cp -r /etc/apt/sources.list /etc/apt/sources.list.d /etc/yum.repos.d {DIAGNOSTICS_DIR}/repos/ 2>/dev/null || true
+}}
+trap collect_diagnostics EXIT
+"#
+    )
+}
+
+/// Background dwarf2json download started at the end of `install_tools`
+fn dwarf2json_prefetch(url: &str) -> String {
+    format!(
+        r#"# Fetch dwarf2json in the background while the debug packages download
+wget -q --max-redirect=5 "{url}" -O /usr/local/bin/dwarf2json &
+DWARF2JSON_FETCH_PID=$!
+"#
+    )
+}
+
+/// `setup_dwarf2json` step fetching a prebuilt binary over verified TLS.
+///
+/// Waits for the background download when `install_tools` started one, and
+/// downloads in the foreground when it did not (e.g. the step was replaced).
+/// With an expected digest the step fails on mismatch; without one the
+/// computed digest is still logged so it ends up in the generation result.
+fn dwarf2json_download(url: &str, expected_sha256: Option<&str>) -> String {
+    format!(
+        r#"# Download dwarf2json (certificate checks stay on) and verify its digest
+echo ">>> Setting up dwarf2json..."
+if [ -n "${{DWARF2JSON_FETCH_PID:-}}" ]; then
+    wait "$DWARF2JSON_FETCH_PID"
+else
+    wget -q --max-redirect=5 "{url}" -O /usr/local/bin/dwarf2json
+fi
+{verify}"#,
+        verify = dwarf2json_verify(expected_sha256),
+    )
+}
+
+/// `setup_dwarf2json` step using the binary a prebaked image was built with
+fn dwarf2json_prebaked(expected_sha256: Option<&str>) -> String {
+    format!(
+        r#"# dwarf2json was installed when the image was built (symgen build-images)
+echo ">>> Setting up dwarf2json (prebaked image)..."
+{verify}"#,
+        verify = dwarf2json_verify(expected_sha256),
+    )
+}
+
+/// `setup_dwarf2json` step installing the binary the host downloaded and cached
+fn dwarf2json_install(expected_sha256: Option<&str>) -> String {
+    format!(
+        r#"# Install dwarf2json from the host cache and verify its digest
+echo ">>> Setting up dwarf2json (host cache)..."
+install -m 0755 {DWARF2JSON_INPUT_PATH} /usr/local/bin/dwarf2json
+{verify}"#,
+        verify = dwarf2json_verify(expected_sha256),
+    )
+}
+
+/// Digest dwarf2json must have: the one pinned in the configuration, else the
+/// published digest of the release asset it comes from
+fn expected_dwarf2json_sha256<'a>(
+    overrides: &'a ScriptOverrides,
+    source_url: Option<&str>,
+) -> Option<&'a str> {
+    overrides
+        .dwarf2json_sha256
+        .as_deref()
+        .or_else(|| source_url.and_then(dwarf2json::release_sha256))
+}
+
+/// Report the installed dwarf2json's digest and check it against the pinned one
+fn dwarf2json_verify(expected_sha256: Option<&str>) -> String {
+    let verify = match expected_sha256 {
+        Some(expected) => format!(
+            r#"if [ "$DWARF2JSON_SHA256" != "{expected}" ]; then
+    echo "ERROR: dwarf2json checksum mismatch"
+    echo "ERROR:   expected {expected}"
+    echo "ERROR:   got      $DWARF2JSON_SHA256"
+    rm -f /usr/local/bin/dwarf2json
+    exit 1
+fi
+echo ">>> dwarf2json checksum verified"
+"#
+        ),
+        None => r#"echo "WARNING: dwarf2json checksum not pinned (set dwarf2json_sha256 in the config to verify it)"
+"#
+        .to_string(),
+    };
+
+    format!(
+        r#"DWARF2JSON_SHA256=$(sha256sum /usr/local/bin/dwarf2json | cut -d' ' -f1)
+echo ">>> dwarf2json sha256: $DWARF2JSON_SHA256"
+echo "@@RESULT:dwarf2json_sha256=$DWARF2JSON_SHA256@@"
+{verify}chmod +x /usr/local/bin/dwarf2json
+"#
+    )
+}
+
+/// `setup_dwarf2json` step building dwarf2json with an upstream Go toolchain
+fn dwarf2json_source_build(arch: Arch) -> String {
+    let go_arch = arch.go_arch();
+    format!(
+        r#"# Build dwarf2json from source (no prebuilt binary for {go_arch})
+echo ">>> Building dwarf2json from source..."
+command -v tar >/dev/null || yum -y -q install tar 2>/dev/null || dnf -y -q install tar 2>/dev/null || apt-get install -y -qq tar
+wget -q https://go.dev/dl/go{GO_VERSION}.linux-{go_arch}.tar.gz -O /tmp/go.tar.gz
+tar -C /usr/local -xzf /tmp/go.tar.gz
+rm -f /tmp/go.tar.gz
+# go install checks module sources against sum.golang.org
+GOBIN=/usr/local/bin GOPATH=/tmp/gopath GOCACHE=/tmp/gocache /usr/local/go/bin/go install {DWARF2JSON_MODULE}
+rm -rf /tmp/gopath /tmp/gocache
+DWARF2JSON_SHA256=$(sha256sum /usr/local/bin/dwarf2json | cut -d' ' -f1)
+echo ">>> dwarf2json sha256: $DWARF2JSON_SHA256"
+echo "@@RESULT:dwarf2json_sha256=$DWARF2JSON_SHA256@@"
+"#
+    )
+}
+
+/// Quote a value for safe interpolation into the bash script
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
@@ -0,0 +1,52 @@
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::artifacts::{sidecar_path, SIGNATURE_SUFFIX};
+
+/// Make sure gpg is installed and holds a secret key to sign with: `key` when
+/// given, else gpg's default key
+pub fn check_key(key: Option<&str>) -> Result<()> {
+    let mut command = Command::new("gpg");
+    command.args(["--batch", "--list-secret-keys"]);
+    if let Some(key) = key {
+        command.arg(key);
+    }
+    let result = command
+        .output()
+        .context("Failed to run gpg; install GnuPG to sign symbol files")?;
+    if !result.status.success() || result.stdout.is_empty() {
+        match key {
+            Some(key) => bail!("No GPG secret key matches {}", key),
+            None => bail!("No GPG secret key to sign with; create one or pass --gpg-key"),
+        }
+    }
+    Ok(())
+}
+
+/// Write an ASCII-armored detached signature of `file` to `<file>.asc`.
+///
+/// Passphrases are asked for by gpg-agent's pinentry as usual, so a
+/// cached or passphrase-less key signs without interaction.
+pub fn detach_sign(file: &Path, key: Option<&str>) -> Result<PathBuf> {
+    let signature = sidecar_path(file, SIGNATURE_SUFFIX);
+    let mut command = Command::new("gpg");
+    command.args(["--yes", "--armor", "--detach-sign"]);
+    if let Some(key) = key {
+        command.args(["--local-user", key]);
+    }
+    let result = command
+        .arg("--output")
+        .arg(&signature)
+        .arg(file)
+        .output()
+        .context("Failed to run gpg")?;
+    if !result.status.success() {
+        bail!(
+            "gpg could not sign {}: {}",
+            file.display(),
+            String::from_utf8_lossy(&result.stderr).trim()
+        );
+    }
+    Ok(signature)
+}
@@ -0,0 +1,91 @@
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use std::path::Path;
+use std::process::Command;
+
+use crate::distros::{split_ubuntu_upload, Arch};
+
+/// A kernel snap as shipped to Ubuntu Core devices (`pc-kernel`, `pi-kernel`, ...)
+#[derive(Debug, Clone)]
+pub struct KernelSnap {
+    /// Snap name from `meta/snap.yaml`
+    pub name: String,
+    /// Kernel release of the modules the snap carries (`5.15.0-91-generic`)
+    pub kernel: String,
+    /// Ubuntu upload the snap was built from, when its version is a package version
+    pub upload: Option<String>,
+    pub arch: Option<Arch>,
+}
+
+/// Read the kernel release, upload and architecture of a kernel `.snap` file.
+///
+/// Canonical's kernel snaps repackage the archive's kernel builds, so their
+/// debug symbols are the ddebs of the same release and upload. The snap is a
+/// squashfs image, read with `unsquashfs` from squashfs-tools.
+pub fn inspect(path: &Path) -> Result<KernelSnap> {
+    let yaml = unsquashfs(path, &["-cat", &path.to_string_lossy(), "meta/snap.yaml"])?;
+    let field = |key: &str| {
+        yaml.lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+            .map(|value| value.trim().trim_matches(['"', '\'']).to_string())
+            .filter(|value| !value.is_empty())
+    };
+    if field("type").as_deref() != Some("kernel") {
+        bail!("{} is not a kernel snap", path.display());
+    }
+    let name = field("name").unwrap_or_default();
+
+    // The modules directory names the exact kernel release
+    let listing = unsquashfs(path, &["-l", &path.to_string_lossy()])?;
+    let modules = cached_regex!(r"squashfs-root/(?:lib/)?modules/([^/\s]+)/");
+    let kernel = listing
+        .lines()
+        .find_map(|line| modules.captures(line).map(|cap| cap[1].to_string()))
+        .with_context(|| {
+            format!(
+                "{} carries no kernel modules to tell its release",
+                path.display()
+            )
+        })?;
+
+    // pc-kernel and friends are versioned like the package (`5.15.0-91.101`)
+    let upload = field("version").and_then(|version| {
+        let (abi, upload) = split_ubuntu_upload(&version);
+        upload.filter(|_| kernel.starts_with(&format!("{}-", abi)))
+    });
+    let arch = yaml
+        .lines()
+        .skip_while(|line| !line.starts_with("architectures:"))
+        .skip(1)
+        .take_while(|line| line.trim_start().starts_with('-'))
+        .find_map(|line| {
+            let name = line.trim_start().trim_start_matches('-').trim();
+            Arch::value_variants()
+                .iter()
+                .copied()
+                .find(|arch| arch.deb_arch() == name)
+        })
+        .or_else(|| Arch::from_kernel(&kernel));
+
+    Ok(KernelSnap {
+        name,
+        kernel,
+        upload,
+        arch,
+    })
+}
+
+fn unsquashfs(path: &Path, args: &[&str]) -> Result<String> {
+    let result = Command::new("unsquashfs")
+        .args(args)
+        .output()
+        .context("Failed to run unsquashfs; install squashfs-tools to read kernel snaps")?;
+    if !result.status.success() {
+        bail!(
+            "unsquashfs could not read {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&result.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&result.stdout).to_string())
+}
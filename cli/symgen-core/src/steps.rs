@@ -0,0 +1,171 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Result key carrying the digest of the dwarf2json binary used
+pub const RESULT_DWARF2JSON_SHA256: &str = "dwarf2json_sha256";
+/// Result key carrying the path of the vmlinux the symbols were read from
+pub const RESULT_VMLINUX: &str = "vmlinux";
+/// Result key carrying the path of the System.map used, when one was found
+pub const RESULT_SYSTEM_MAP: &str = "system_map";
+/// Result key saying where the System.map came from: `installed`, `package` or `symtab`
+pub const RESULT_SYSTEM_MAP_SOURCE: &str = "system_map_source";
+/// Result key carrying the unpacked size of the installed debug packages
+pub const RESULT_DEBUGINFO_BYTES: &str = "debuginfo_bytes";
+/// Result key carrying the package that shipped the vmlinux, when a package manager knows it
+pub const RESULT_SOURCE_PACKAGE: &str = "source_package";
+/// Result key repeated once per published kernel, as `<release> <build epoch>`
+pub const RESULT_KERNEL: &str = "kernel";
+/// Result key listing the flavors found by the flavor probe, comma-separated
+pub const RESULT_FLAVORS: &str = "flavors";
+
+/// Machine-readable line printed by the generation scripts
+#[derive(Debug, PartialEq, Eq)]
+pub enum Marker<'a> {
+    /// `@@STEP:<name>@@`: the named step is starting
+    Step(&'a str),
+    /// `@@RESULT:<key>=<value>@@`: a fact established inside the container
+    Result(&'a str, &'a str),
+}
+
+/// Parse a log line as a marker, if it is one
+pub fn parse_marker(line: &str) -> Option<Marker<'_>> {
+    let inner = line.trim().strip_prefix("@@")?.strip_suffix("@@")?;
+    if let Some(name) = inner.strip_prefix("STEP:") {
+        return (!name.is_empty()).then_some(Marker::Step(name));
+    }
+    let (key, value) = inner.strip_prefix("RESULT:")?.split_once('=')?;
+    (!key.is_empty()).then_some(Marker::Result(key, value))
+}
+
+/// Wall time a step took inside the container
+#[derive(Debug, Clone, Serialize)]
+pub struct StepDuration {
+    pub step: String,
+    pub seconds: f64,
+}
+
+/// Follows a script's progress through its steps
+#[derive(Debug, Default)]
+pub struct StepTracker {
+    steps: Vec<&'static str>,
+    current: Option<String>,
+    results: BTreeMap<String, Vec<String>>,
+    /// When each step started, from the container's log timestamps
+    started: Vec<(String, DateTime<Utc>)>,
+    last_seen: Option<DateTime<Utc>>,
+}
+
+impl StepTracker {
+    /// Track a script rendered with the given steps, in order
+    pub fn new(steps: Vec<&'static str>) -> Self {
+        Self {
+            steps,
+            ..Default::default()
+        }
+    }
+
+    /// Feed a log line and the time the container wrote it, if known;
+    /// returns true when it started a new step
+    pub fn observe(&mut self, line: &str, time: Option<DateTime<Utc>>) -> bool {
+        if time.is_some() {
+            self.last_seen = time;
+        }
+        match parse_marker(line) {
+            Some(Marker::Step(name)) => {
+                self.current = Some(name.to_string());
+                if let Some(time) = time {
+                    self.started.push((name.to_string(), time));
+                }
+                true
+            }
+            Some(Marker::Result(key, value)) => {
+                self.results
+                    .entry(key.to_string())
+                    .or_default()
+                    .push(value.to_string());
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Step currently running (or the one that was running when the script stopped)
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    /// Progress label such as `[4/8] install_debuginfo`
+    pub fn label(&self) -> Option<String> {
+        let current = self.current.as_deref()?;
+        match self.steps.iter().position(|s| *s == current) {
+            Some(index) => Some(format!("[{}/{}] {}", index + 1, self.steps.len(), current)),
+            None => Some(current.to_string()),
+        }
+    }
+
+    /// Last value reported by the script for a result key
+    pub fn result(&self, key: &str) -> Option<&str> {
+        self.results
+            .get(key)
+            .and_then(|values| values.last())
+            .map(String::as_str)
+            .filter(|v| !v.is_empty())
+    }
+
+    /// Wall time of every step that started, in order; the last one runs until
+    /// the last log line
+    pub fn durations(&self) -> Vec<StepDuration> {
+        let ends = self
+            .started
+            .iter()
+            .skip(1)
+            .map(|(_, time)| Some(*time))
+            .chain(std::iter::once(self.last_seen));
+        self.started
+            .iter()
+            .zip(ends)
+            .filter_map(|((step, start), end)| {
+                Some(StepDuration {
+                    step: step.clone(),
+                    seconds: (end? - *start).num_milliseconds() as f64 / 1000.0,
+                })
+            })
+            .collect()
+    }
+
+    /// Every value reported for a repeated result key, in order
+    pub fn results(&self, key: &str) -> Vec<&str> {
+        self.results
+            .get(key)
+            .map(|values| {
+                values
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|v| !v.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_markers() {
+        assert_eq!(
+            parse_marker("@@STEP:generate@@"),
+            Some(Marker::Step("generate"))
+        );
+        assert_eq!(
+            parse_marker("  @@RESULT:vmlinux=/usr/lib/debug/boot/vmlinux=x@@\r"),
+            Some(Marker::Result("vmlinux", "/usr/lib/debug/boot/vmlinux=x"))
+        );
+        assert_eq!(parse_marker("@@STEP:@@"), None);
+        assert_eq!(parse_marker("@@RESULT:=value@@"), None);
+        assert_eq!(parse_marker(">>> Installing @@STEP:generate@@"), None);
+        assert_eq!(parse_marker("@@STEP:generate"), None);
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::artifacts::sha256_file;
+use crate::cache::{copy_atomic, FileLock};
+
+/// Directory under the store root holding one directory per content digest
+pub const OBJECTS_DIR: &str = "store";
+
+/// Move `file` to `<root>/store/<sha256>/<name>` and leave a symlink to it in
+/// its place, so output directories become a readable index over shared content.
+///
+/// When the store already holds the same content under the same name, `file`
+/// is dropped in favor of the stored copy. Stored files are made read-only,
+/// since every index entry pointing at them relies on their content.
+pub fn ingest(root: &Path, file: &Path) -> Result<PathBuf> {
+    let name = file
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow!("{} has no usable file name", file.display()))?;
+    let digest = sha256_file(file)?;
+    let dir = root.join(OBJECTS_DIR).join(&digest);
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    // Links are absolute so index directories can live anywhere
+    let stored = dir
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", dir.display()))?
+        .join(name);
+
+    {
+        let _lock = FileLock::acquire(&stored)?;
+        if stored.exists() {
+            std::fs::remove_file(file)
+                .with_context(|| format!("Failed to remove {}", file.display()))?;
+        } else {
+            if std::fs::rename(file, &stored).is_err() {
+                // Different filesystem
+                copy_atomic(file, &stored)?;
+                std::fs::remove_file(file)
+                    .with_context(|| format!("Failed to remove {}", file.display()))?;
+            }
+            let mut permissions = std::fs::metadata(&stored)?.permissions();
+            permissions.set_readonly(true);
+            std::fs::set_permissions(&stored, permissions)
+                .with_context(|| format!("Failed to make {} read-only", stored.display()))?;
+        }
+    }
+
+    std::os::unix::fs::symlink(&stored, file)
+        .with_context(|| format!("Failed to link {} to {}", file.display(), stored.display()))?;
+    Ok(stored)
+}
@@ -0,0 +1,255 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::distros::{get_versions, Distro};
+use crate::dwarf2json::{self, PINNED_VERSION};
+
+/// Reason given for a check whose endpoint is set to an empty string
+const DISABLED: &str = "no endpoint configured";
+
+/// Outcome of one upgrade check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    /// Nothing newer was found
+    Current,
+    /// A newer version or release is out
+    Available,
+    /// The feed could not be reached or read
+    Unreachable,
+    /// The check does not apply or its feed is disabled in the configuration
+    Skipped,
+}
+
+/// What one upgrade check found
+#[derive(Debug, Serialize)]
+pub struct UpgradeCheck {
+    /// `symgen`, `dwarf2json` or a distribution name
+    pub component: String,
+    /// Version in use, or the releases symgen knows about
+    pub current: String,
+    /// Newer versions or releases found upstream
+    pub available: Vec<String>,
+    pub status: CheckStatus,
+    /// What upgrading would change, or why the check could not run
+    pub detail: Option<String>,
+}
+
+impl UpgradeCheck {
+    fn new(
+        component: &str,
+        current: String,
+        result: Result<Vec<String>>,
+        change: impl FnOnce(&[String]) -> String,
+    ) -> Self {
+        let (available, status, detail) = match result {
+            Ok(available) if available.is_empty() => (available, CheckStatus::Current, None),
+            Ok(available) => {
+                let detail = change(&available);
+                (available, CheckStatus::Available, Some(detail))
+            }
+            Err(e) => (
+                Vec::new(),
+                CheckStatus::Unreachable,
+                Some(format!("{:#}", e)),
+            ),
+        };
+        Self {
+            component: component.to_string(),
+            current,
+            available,
+            status,
+            detail,
+        }
+    }
+
+    fn skipped(component: &str, current: String, reason: &str) -> Self {
+        Self {
+            component: component.to_string(),
+            current,
+            available: Vec::new(),
+            status: CheckStatus::Skipped,
+            detail: Some(reason.to_string()),
+        }
+    }
+}
+
+/// Check the configured feeds for a newer symgen, a newer dwarf2json and
+/// distribution releases symgen does not know about yet.
+///
+/// Nothing is changed. Each feed is asked once with a short timeout, and a
+/// feed that cannot be reached is reported as such rather than failing the
+/// whole check, so this is safe to run on offline lab machines.
+pub async fn check(config: &Config) -> Vec<UpgradeCheck> {
+    let endpoints = &config.upgrade_check;
+    let (symgen, dwarf2json, ubuntu, debian, fedora) = futures::join!(
+        check_symgen(&endpoints.symgen),
+        check_dwarf2json(&endpoints.dwarf2json, config.dwarf2json_version.as_deref()),
+        check_distro(Distro::Ubuntu, &endpoints.ubuntu),
+        check_distro(Distro::Debian, &endpoints.debian),
+        check_distro(Distro::Fedora, &endpoints.fedora),
+    );
+    vec![symgen, dwarf2json, ubuntu, debian, fedora]
+}
+
+async fn check_symgen(url: &str) -> UpgradeCheck {
+    let current = env!("CARGO_PKG_VERSION").to_string();
+    if url.is_empty() {
+        return UpgradeCheck::skipped("symgen", current, DISABLED);
+    }
+    let result = dwarf2json::latest_release(url).await.map(|tag| {
+        newer_than(&tag, &current)
+            .then_some(tag)
+            .into_iter()
+            .collect()
+    });
+    UpgradeCheck::new("symgen", current, result, |available| {
+        format!("symgen {} is available", available[0])
+    })
+}
+
+async fn check_dwarf2json(url: &str, configured: Option<&str>) -> UpgradeCheck {
+    let current = configured.unwrap_or(PINNED_VERSION).to_string();
+    if url.is_empty() {
+        return UpgradeCheck::skipped("dwarf2json", current, DISABLED);
+    }
+    if current == "latest" {
+        return UpgradeCheck::skipped(
+            "dwarf2json",
+            current,
+            "dwarf2json_version = \"latest\" follows new releases",
+        );
+    }
+    let result = dwarf2json::latest_release(url).await.map(|tag| {
+        newer_than(&tag, &current)
+            .then_some(tag)
+            .into_iter()
+            .collect()
+    });
+    UpgradeCheck::new("dwarf2json", current, result, |available| {
+        format!(
+            "Set dwarf2json_version = \"{}\" (or \"latest\") in the config to use it",
+            available[0]
+        )
+    })
+}
+
+async fn check_distro(distro: Distro, url: &str) -> UpgradeCheck {
+    let known: Vec<String> = get_versions(distro)
+        .into_iter()
+        .map(|v| v.version)
+        .collect();
+    let current = known.join(", ");
+    if url.is_empty() {
+        return UpgradeCheck::skipped(distro.name(), current, DISABLED);
+    }
+    let result = released_versions(distro, url).await.map(|released| {
+        released
+            .into_iter()
+            .filter(|version| {
+                !known.contains(version) && known.iter().all(|k| newer_than(version, k))
+            })
+            .collect()
+    });
+    UpgradeCheck::new(distro.name(), current, result, |available| {
+        format!(
+            "{} {} released; a newer symgen is needed to generate for it by release",
+            distro.display_name(),
+            available.join(", ")
+        )
+    })
+}
+
+/// Supported releases of a distribution according to its release feed
+async fn released_versions(distro: Distro, url: &str) -> Result<Vec<String>> {
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("symgen/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(15))
+        .build()?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch {}", url))?
+        .error_for_status()
+        .with_context(|| format!("Failed to fetch {}", url))?;
+
+    match distro {
+        Distro::Ubuntu => {
+            // Stanzas of `Dist:`, `Version: 24.04.1 LTS`, `Supported: 1`, ...
+            let text = response.text().await?;
+            let versions = text
+                .split("\n\n")
+                .filter(|stanza| field(stanza, "Supported") == Some("1"))
+                .filter_map(|stanza| field(stanza, "Version"))
+                .filter_map(|version| {
+                    let mut parts = version.split_whitespace().next()?.split('.');
+                    Some(format!("{}.{}", parts.next()?, parts.next()?))
+                })
+                .collect();
+            Ok(versions)
+        }
+        Distro::Debian => {
+            // `Version: 12.7` of the stable suite
+            let text = response.text().await?;
+            let Some(version) = field(&text, "Version") else {
+                bail!("No Version field in {}", url);
+            };
+            Ok(vec![version
+                .split('.')
+                .next()
+                .unwrap_or(version)
+                .to_string()])
+        }
+        Distro::Fedora => {
+            #[derive(Deserialize)]
+            struct Releases {
+                releases: Vec<Release>,
+            }
+            #[derive(Deserialize)]
+            struct Release {
+                version: String,
+                id_prefix: String,
+            }
+            let releases: Releases = response
+                .json()
+                .await
+                .context("Unexpected Bodhi API response")?;
+            Ok(releases
+                .releases
+                .into_iter()
+                .filter(|release| {
+                    release.id_prefix == "FEDORA"
+                        && release.version.chars().all(|c| c.is_ascii_digit())
+                })
+                .map(|release| release.version)
+                .collect())
+        }
+        _ => bail!("No release feed for {}", distro.display_name()),
+    }
+}
+
+/// Value of a `Key: value` line
+fn field<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    text.lines()
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+        .map(str::trim)
+}
+
+/// Whether `candidate` is a higher dotted version than `current`; a leading
+/// `v` and any suffix after the numbers (`-rc1`) are ignored
+fn newer_than(candidate: &str, current: &str) -> bool {
+    fn numbers(version: &str) -> Vec<u64> {
+        version
+            .trim_start_matches('v')
+            .split('.')
+            .map_while(|part| {
+                let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+                digits.parse().ok()
+            })
+            .collect()
+    }
+    numbers(candidate) > numbers(current)
+}
@@ -0,0 +1,333 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+use crate::banner::parse_banner_str;
+use crate::isf;
+
+/// Problems reported per file before the rest are only counted
+const MAX_REPORTED: usize = 20;
+
+const BASE_KINDS: &[&str] = &["int", "float", "char", "bool", "void"];
+const USER_KINDS: &[&str] = &["struct", "union", "class"];
+
+/// What checking a symbol file found
+#[derive(Debug, Default, Serialize)]
+pub struct Verification {
+    pub path: String,
+    /// `metadata.format` of the ISF
+    pub format: Option<String>,
+    /// Kernel banner recorded in `linux_banner`
+    pub banner: Option<String>,
+    /// Kernel release parsed from the banner
+    pub kernel: Option<String>,
+    pub symbols: usize,
+    pub user_types: usize,
+    pub enums: usize,
+    pub base_types: usize,
+    /// Problems that make the file unusable or misleading
+    pub errors: Vec<String>,
+    /// Errors beyond the reported ones
+    pub more_errors: usize,
+    pub warnings: Vec<String>,
+}
+
+impl Verification {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    fn warning(&mut self, message: String) {
+        self.warnings.push(message);
+    }
+
+    fn error(&mut self, message: String) {
+        if self.errors.len() < MAX_REPORTED {
+            self.errors.push(message);
+        } else {
+            self.more_errors += 1;
+        }
+    }
+}
+
+/// Check a symbol file: that it decompresses and parses, follows the
+/// intermediate symbol format, and that its banner matches the kernel in its
+/// file name and `expected_kernel`, when given
+pub fn verify(path: &Path, expected_kernel: Option<&str>) -> Verification {
+    let mut report = Verification {
+        path: path.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    let isf = match isf::load(path) {
+        Ok(isf) => isf,
+        Err(e) => {
+            // A truncated download or an interrupted write ends up here
+            report.error(format!("{:#}", e));
+            return report;
+        }
+    };
+
+    check_schema(&isf, &mut report);
+    check_banner(&isf, path, expected_kernel, &mut report);
+    report
+}
+
+/// Structure of an ISF (schema 6.x): the five sections, the entries in them and
+/// every type reference resolving to a defined type
+fn check_schema(isf: &Value, report: &mut Verification) {
+    let Some(root) = isf.as_object() else {
+        report.error("Top level is not a JSON object".to_string());
+        return;
+    };
+    let empty = Map::new();
+    let metadata = section(root, "metadata", report).unwrap_or(&empty);
+    let base_types = section(root, "base_types", report).unwrap_or(&empty);
+    let user_types = section(root, "user_types", report).unwrap_or(&empty);
+    let enums = section(root, "enums", report).unwrap_or(&empty);
+    let symbols = section(root, "symbols", report).unwrap_or(&empty);
+    report.base_types = base_types.len();
+    report.user_types = user_types.len();
+    report.enums = enums.len();
+    report.symbols = symbols.len();
+
+    report.format = metadata
+        .get("format")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    match report.format.as_deref() {
+        None => report.error("metadata.format is missing".to_string()),
+        Some(format) if !format.split('.').all(|part| part.parse::<u32>().is_ok()) => {
+            report.error(format!("metadata.format '{}' is not a version", format))
+        }
+        Some(_) => {}
+    }
+    if symbols.is_empty() {
+        report.error("No symbols".to_string());
+    }
+    if user_types.is_empty() {
+        report.warning("No user types; only symbol lookups will work".to_string());
+    }
+
+    let tables = Tables {
+        base_types,
+        user_types,
+        enums,
+    };
+    for (name, entry) in base_types {
+        let kind = entry.get("kind").and_then(Value::as_str);
+        if !kind.is_some_and(|kind| BASE_KINDS.contains(&kind)) {
+            report.error(format!("Base type '{}' has an invalid kind", name));
+        }
+        if !entry.get("size").is_some_and(Value::is_u64)
+            || !entry.get("signed").is_some_and(Value::is_boolean)
+        {
+            report.error(format!("Base type '{}' lacks size or signed", name));
+        }
+        if !matches!(
+            entry.get("endian").and_then(Value::as_str),
+            Some("little" | "big")
+        ) {
+            report.error(format!("Base type '{}' has an invalid endian", name));
+        }
+    }
+    for (name, entry) in user_types {
+        let kind = entry.get("kind").and_then(Value::as_str);
+        if !kind.is_some_and(|kind| USER_KINDS.contains(&kind)) {
+            report.error(format!("User type '{}' has an invalid kind", name));
+        }
+        if !entry.get("size").is_some_and(Value::is_u64) {
+            report.error(format!("User type '{}' has no size", name));
+        }
+        let Some(fields) = entry.get("fields").and_then(Value::as_object) else {
+            report.error(format!("User type '{}' has no fields object", name));
+            continue;
+        };
+        for (field, definition) in fields {
+            let context = format!("{}.{}", name, field);
+            if !definition.get("offset").is_some_and(Value::is_u64) {
+                report.error(format!("Field {} has no offset", context));
+            }
+            match definition.get("type") {
+                Some(descriptor) => tables.check_type(descriptor, &context, report),
+                None => report.error(format!("Field {} has no type", context)),
+            }
+        }
+    }
+    for (name, entry) in enums {
+        if !entry.get("size").is_some_and(Value::is_u64) {
+            report.error(format!("Enum '{}' has no size", name));
+        }
+        match entry.get("base").and_then(Value::as_str) {
+            Some(base) if !base_types.contains_key(base) => report.error(format!(
+                "Enum '{}' uses undefined base type '{}'",
+                name, base
+            )),
+            Some(_) => {}
+            None => report.error(format!("Enum '{}' has no base type", name)),
+        }
+        let constants = entry.get("constants").and_then(Value::as_object);
+        if !constants.is_some_and(|constants| {
+            constants
+                .values()
+                .all(|value| value.is_i64() || value.is_u64())
+        }) {
+            report.error(format!("Enum '{}' has invalid constants", name));
+        }
+    }
+    for (name, entry) in symbols {
+        if !entry.get("address").is_some_and(Value::is_u64) {
+            report.error(format!("Symbol '{}' has no address", name));
+        }
+        if let Some(descriptor) = entry.get("type") {
+            tables.check_type(descriptor, name, report);
+        }
+        if entry
+            .get("constant_data")
+            .is_some_and(|data| !data.is_string())
+        {
+            report.error(format!("Symbol '{}' has non-string constant_data", name));
+        }
+    }
+}
+
+/// A top-level section, reporting it when missing or not an object
+fn section<'a>(
+    root: &'a Map<String, Value>,
+    name: &str,
+    report: &mut Verification,
+) -> Option<&'a Map<String, Value>> {
+    match root.get(name).map(Value::as_object) {
+        Some(Some(section)) => Some(section),
+        Some(None) => {
+            report.error(format!("'{}' is not an object", name));
+            None
+        }
+        None => {
+            report.error(format!("Missing '{}' section", name));
+            None
+        }
+    }
+}
+
+/// Sections type descriptors refer into
+struct Tables<'a> {
+    base_types: &'a Map<String, Value>,
+    user_types: &'a Map<String, Value>,
+    enums: &'a Map<String, Value>,
+}
+
+impl Tables<'_> {
+    /// Check a type descriptor and the descriptors nested in it
+    fn check_type(&self, descriptor: &Value, context: &str, report: &mut Verification) {
+        let name = || descriptor.get("name").and_then(Value::as_str);
+        let subtype = |report: &mut Verification| match descriptor.get("subtype") {
+            Some(subtype) => self.check_type(subtype, context, report),
+            None => report.error(format!(
+                "{}: {} type has no subtype",
+                context,
+                kind_of(descriptor)
+            )),
+        };
+        match descriptor.get("kind").and_then(Value::as_str) {
+            Some("base") => check_reference(self.base_types, "base type", name(), context, report),
+            Some("enum") => check_reference(self.enums, "enum", name(), context, report),
+            Some(kind) if USER_KINDS.contains(&kind) => {
+                check_reference(self.user_types, kind, name(), context, report)
+            }
+            Some("pointer") => subtype(report),
+            Some("array") => {
+                if !descriptor.get("count").is_some_and(Value::is_u64) {
+                    report.error(format!("{}: array type has no count", context));
+                }
+                subtype(report);
+            }
+            Some("bitfield") => {
+                if !descriptor.get("bit_position").is_some_and(Value::is_u64)
+                    || !descriptor.get("bit_length").is_some_and(Value::is_u64)
+                {
+                    report.error(format!(
+                        "{}: bitfield lacks bit_position or bit_length",
+                        context
+                    ));
+                }
+                match descriptor.get("type") {
+                    Some(inner) => self.check_type(inner, context, report),
+                    None => report.error(format!("{}: bitfield has no type", context)),
+                }
+            }
+            Some("function") => {}
+            Some(kind) => report.error(format!("{}: unknown type kind '{}'", context, kind)),
+            None => report.error(format!("{}: type has no kind", context)),
+        }
+    }
+}
+
+/// A named reference into one of the type tables
+fn check_reference(
+    table: &Map<String, Value>,
+    what: &str,
+    name: Option<&str>,
+    context: &str,
+    report: &mut Verification,
+) {
+    match name {
+        Some(name) if !table.contains_key(name) => {
+            report.error(format!("{}: undefined {} '{}'", context, what, name))
+        }
+        Some(_) => {}
+        None => report.error(format!("{}: {} reference has no name", context, what)),
+    }
+}
+
+fn kind_of(descriptor: &Value) -> &str {
+    descriptor
+        .get("kind")
+        .and_then(Value::as_str)
+        .unwrap_or("?")
+}
+
+/// The banner is what Volatility matches images on, so it has to be there and
+/// agree with the kernel the file claims to be for
+fn check_banner(
+    isf: &Value,
+    path: &Path,
+    expected_kernel: Option<&str>,
+    report: &mut Verification,
+) {
+    report.banner = isf::banner(isf);
+    let Some(banner) = &report.banner else {
+        report.error("No linux_banner; Volatility cannot match this file to an image".to_string());
+        return;
+    };
+    report.kernel = parse_banner_str(banner).map(|parsed| parsed.kernel_version);
+    let Some(kernel) = report.kernel.clone() else {
+        report.warning("The linux_banner does not name a kernel release".to_string());
+        return;
+    };
+
+    // symgen names files `<Distro>_<release>_<kernel>[_<arch>].json.xz`
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if name.contains('_') && !name.contains(&kernel) {
+        report.error(format!(
+            "The banner is for {}, but the file name says otherwise",
+            kernel
+        ));
+    }
+    if let Some(expected) = expected_kernel {
+        if expected != kernel {
+            report.error(format!("The banner is for {}, not {}", kernel, expected));
+        }
+    }
+    let annotated = isf
+        .pointer("/metadata/symgen/kernel")
+        .and_then(Value::as_str);
+    if let Some(annotated) = annotated.filter(|annotated| *annotated != kernel) {
+        report.warning(format!(
+            "metadata.symgen records kernel {}, the banner {}",
+            annotated, kernel
+        ));
+    }
+}
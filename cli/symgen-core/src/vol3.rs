@@ -0,0 +1,77 @@
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::cache::copy_atomic;
+
+/// Environment variable naming a Volatility3 symbol directory, taking precedence over the installation
+pub const SYMBOL_PATH_ENV: &str = "VOLATILITY3_SYMBOL_PATH";
+
+/// Prints the directory of the volatility3 package the interpreter imports
+const FIND_PACKAGE: &str = "import os, volatility3; print(os.path.dirname(volatility3.__file__))";
+
+/// Linux symbol directory of the local Volatility3.
+///
+/// The first entry of `VOLATILITY3_SYMBOL_PATH` wins, with `linux/` appended
+/// unless it already ends there. Otherwise the package's own `symbols/linux`
+/// is used, found through the interpreter of the `vol` script on the PATH
+/// (which covers pipx and virtualenv installs), then `python3` and `python`.
+pub fn linux_symbol_dir() -> Result<PathBuf> {
+    if let Some(path) = std::env::var_os(SYMBOL_PATH_ENV) {
+        if let Some(dir) = std::env::split_paths(&path).find(|dir| !dir.as_os_str().is_empty()) {
+            return Ok(if dir.ends_with("linux") {
+                dir
+            } else {
+                dir.join("linux")
+            });
+        }
+    }
+
+    let interpreters = vol_interpreter()
+        .into_iter()
+        .chain(["python3".into(), "python".into()]);
+    for interpreter in interpreters {
+        let Ok(result) = Command::new(&interpreter)
+            .args(["-c", FIND_PACKAGE])
+            .output()
+        else {
+            continue;
+        };
+        if result.status.success() {
+            let package = PathBuf::from(String::from_utf8_lossy(&result.stdout).trim());
+            return Ok(package.join("symbols").join("linux"));
+        }
+    }
+    bail!(
+        "No Volatility3 installation found; install it with pip or set {} to its symbol directory",
+        SYMBOL_PATH_ENV
+    )
+}
+
+/// Copy a symbol file into a Volatility3 symbol directory, returning the new path
+pub fn install(symbol_file: &Path, dir: &Path) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let name = symbol_file
+        .file_name()
+        .with_context(|| format!("{} has no file name", symbol_file.display()))?;
+    let dest = dir.join(name);
+    copy_atomic(symbol_file, &dest)?;
+    Ok(dest)
+}
+
+/// Interpreter named in the shebang of the `vol` script on the PATH
+fn vol_interpreter() -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    let script = std::env::split_paths(&path)
+        .map(|dir| dir.join("vol"))
+        .find(|script| script.is_file())?;
+    let contents = std::fs::read(&script).ok()?;
+    let first_line = contents.split(|&b| b == b'\n').next()?;
+    let shebang = String::from_utf8_lossy(first_line.strip_prefix(b"#!")?).to_string();
+    let mut words = shebang.split_whitespace();
+    match words.next()? {
+        // `#!/usr/bin/env python3`
+        env if env.ends_with("/env") => words.next().map(PathBuf::from),
+        interpreter => Some(PathBuf::from(interpreter)),
+    }
+}
@@ -0,0 +1,239 @@
+//! Generation scripts compared against the files in `tests/golden/`.
+//!
+//! After an intended change to the scripts, rewrite the files with
+//! `SYMGEN_BLESS=1 cargo test -p symgen-core --test golden` and review the diff.
+
+use std::path::PathBuf;
+use symgen_core::distros::{find_version, Arch, Distro};
+use symgen_core::proxy::ProxySettings;
+use symgen_core::script::{render_script, ScriptOptions};
+
+fn check(name: &str, distro: Distro, release: &str, kernel: &str, options: &ScriptOptions) {
+    let version = find_version(distro, release).expect("supported release");
+    let rendered = render_script(&version, kernel, options).text;
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(format!("{}.sh", name));
+
+    if std::env::var_os("SYMGEN_BLESS").is_some() {
+        std::fs::write(&path, &rendered).expect("write golden file");
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("missing golden file {}: {}", path.display(), e));
+    if let Some((line, (got, want))) = rendered
+        .lines()
+        .zip(expected.lines())
+        .enumerate()
+        .find(|(_, (got, want))| got != want)
+    {
+        panic!(
+            "{} differs from {} at line {}:\n  got:  {}\n  want: {}",
+            name,
+            path.display(),
+            line + 1,
+            got,
+            want
+        );
+    }
+    assert_eq!(
+        rendered.lines().count(),
+        expected.lines().count(),
+        "{} and {} differ in length",
+        name,
+        path.display()
+    );
+}
+
+fn proxy() -> ProxySettings {
+    ProxySettings {
+        http: Some("http://proxy.corp.local:3128".to_string()),
+        https: Some("http://proxy.corp.local:3128".to_string()),
+        no_proxy: Some("localhost,.corp.local".to_string()),
+    }
+}
+
+#[test]
+fn ubuntu() {
+    let options = ScriptOptions::default();
+    check(
+        "ubuntu",
+        Distro::Ubuntu,
+        "22.04",
+        "5.15.0-91-generic",
+        &options,
+    );
+}
+
+#[test]
+fn ubuntu_flavor() {
+    let options = ScriptOptions::default();
+    check(
+        "ubuntu_flavor",
+        Distro::Ubuntu,
+        "22.04",
+        "5.15.0-1051-aws",
+        &options,
+    );
+}
+
+#[test]
+fn ubuntu_pin() {
+    let mut options = ScriptOptions::default();
+    options.package_version = Some("5.15.0-91.101".to_string());
+    check(
+        "ubuntu_pin",
+        Distro::Ubuntu,
+        "22.04",
+        "5.15.0-91-generic",
+        &options,
+    );
+}
+
+#[test]
+fn ubuntu_proxy() {
+    let mut options = ScriptOptions::default();
+    options.proxy = proxy();
+    check(
+        "ubuntu_proxy",
+        Distro::Ubuntu,
+        "22.04",
+        "5.15.0-91-generic",
+        &options,
+    );
+}
+
+#[test]
+fn ubuntu_arm64() {
+    let mut options = ScriptOptions::default();
+    options.arch = Arch::Arm64;
+    check(
+        "ubuntu_arm64",
+        Distro::Ubuntu,
+        "22.04",
+        "5.15.0-91-generic",
+        &options,
+    );
+}
+
+#[test]
+fn debian() {
+    let options = ScriptOptions::default();
+    check("debian", Distro::Debian, "12", "6.1.0-18-amd64", &options);
+}
+
+#[test]
+fn debian_flavor() {
+    let options = ScriptOptions::default();
+    check(
+        "debian_flavor",
+        Distro::Debian,
+        "12",
+        "6.1.0-18-cloud-amd64",
+        &options,
+    );
+}
+
+#[test]
+fn fedora() {
+    let options = ScriptOptions::default();
+    check(
+        "fedora",
+        Distro::Fedora,
+        "39",
+        "6.5.6-300.fc39.x86_64",
+        &options,
+    );
+}
+
+#[test]
+fn centos() {
+    let options = ScriptOptions::default();
+    check(
+        "centos",
+        Distro::CentOS,
+        "9",
+        "5.14.0-362.el9.x86_64",
+        &options,
+    );
+}
+
+#[test]
+fn rhel() {
+    let options = ScriptOptions::default();
+    check(
+        "rhel",
+        Distro::RHEL,
+        "9",
+        "5.14.0-362.8.1.el9_3.x86_64",
+        &options,
+    );
+}
+
+#[test]
+fn oracle() {
+    let options = ScriptOptions::default();
+    check(
+        "oracle",
+        Distro::Oracle,
+        "8",
+        "5.15.0-200.131.27.el8uek.x86_64",
+        &options,
+    );
+}
+
+#[test]
+fn rocky() {
+    let options = ScriptOptions::default();
+    check(
+        "rocky",
+        Distro::Rocky,
+        "9",
+        "5.14.0-362.8.1.el9_3.x86_64",
+        &options,
+    );
+}
+
+#[test]
+fn rocky_proxy() {
+    let mut options = ScriptOptions::default();
+    options.proxy = proxy();
+    check(
+        "rocky_proxy",
+        Distro::Rocky,
+        "9",
+        "5.14.0-362.8.1.el9_3.x86_64",
+        &options,
+    );
+}
+
+#[test]
+fn alma() {
+    let options = ScriptOptions::default();
+    check(
+        "alma",
+        Distro::Alma,
+        "9",
+        "5.14.0-362.8.1.el9_3.x86_64",
+        &options,
+    );
+}
+
+#[test]
+fn kali() {
+    let options = ScriptOptions::default();
+    check("kali", Distro::Kali, "rolling", "6.6.9-amd64", &options);
+}
+
+#[test]
+fn wsl() {
+    let options = ScriptOptions::default();
+    check(
+        "wsl",
+        Distro::Wsl,
+        "2",
+        "5.15.153.1-microsoft-standard-WSL2",
+        &options,
+    );
+}
@@ -0,0 +1,186 @@
+#!/bin/bash
+set -e
+
+echo "=== Starting symbol generation for Alma 9 kernel 5.14.0-362.8.1.el9_3.x86_64 ==="
+
+# Save output directory (the mounted volume)
+OUTPUT_DIR="$PWD"
+
+# Collect diagnostics for bug reports when any step fails
+collect_diagnostics() {
+    status=$?
+    [ "$status" -eq 0 ] && return
+    mkdir -p /tmp/symgen-failure/repos || return
+    echo "$status" > /tmp/symgen-failure/exit-code.txt || true
+    echo "${SYMGEN_STEP:-}" > /tmp/symgen-failure/failed-step.txt || true
+    (dpkg -l 2>/dev/null || rpm -qa 2>/dev/null) > /tmp/symgen-failure/packages.txt || true
+    df -h > /tmp/symgen-failure/df.txt 2>&1 || true
+    cp /etc/os-release /tmp/symgen-failure/os-release.txt 2>/dev/null || true
+    cp -r /etc/apt/sources.list /etc/apt/sources.list.d /etc/yum.repos.d /tmp/symgen-failure/repos/ 2>/dev/null || true
+}
+trap collect_diagnostics EXIT
+
+SYMGEN_STEP=update
+echo "@@STEP:update@@"
+# Update package lists
+echo ">>> Updating package lists..."
+yum -y -q update 2>/dev/null || dnf -y -q update
+
+SYMGEN_STEP=install_tools
+echo "@@STEP:install_tools@@"
+# Install required packages
+echo ">>> Installing required packages..."
+yum -y -q install wget xz findutils 2>/dev/null || dnf -y -q install wget xz findutils
+# Fetch dwarf2json in the background while the debug packages download
+wget -q --max-redirect=5 "https://github.com/volatilityfoundation/dwarf2json/releases/download/v0.8.0/dwarf2json-linux-amd64" -O /usr/local/bin/dwarf2json &
+DWARF2JSON_FETCH_PID=$!
+
+SYMGEN_STEP=add_repos
+echo "@@STEP:add_repos@@"
+# Enable debuginfo repository
+echo ">>> Adding debug repository..."
+yum -y -q install yum-utils 2>/dev/null || dnf -y -q install dnf-plugins-core
+# Kernels from earlier minor releases only remain in the vault
+echo ">>> Adding Alma 9.3 vault debuginfo repository..."
+cat > /etc/yum.repos.d/symgen-vault.repo << 'REPOEOF'
+[symgen-vault-debuginfo]
+name=Alma 9.3 BaseOS debuginfo (vault)
+baseurl=https://vault.almalinux.org/9.3/BaseOS/debug/x86_64/
+gpgkey=file:///etc/pki/rpm-gpg/RPM-GPG-KEY-AlmaLinux-9
+gpgcheck=1
+enabled=1
+skip_if_unavailable=1
+REPOEOF
+debuginfo-install -y kernel-5.14.0-362.8.1.el9_3.x86_64 2>/dev/null || true
+
+SYMGEN_STEP=install_debuginfo
+echo "@@STEP:install_debuginfo@@"
+# Alternative: try to install kernel-debuginfo directly
+echo ">>> Installing kernel debug symbols for 5.14.0-362.8.1.el9_3.x86_64..."
+if ! yum -y -q install kernel-debuginfo-5.14.0-362.8.1.el9_3.x86_64 2>/dev/null; then
+    if ! dnf -y -q install kernel-debuginfo-5.14.0-362.8.1.el9_3.x86_64 2>/dev/null; then
+        # Try common package
+        yum -y -q install kernel-debuginfo-common-x86_64-5.14.0-362.8.1.el9_3.x86_64 kernel-debuginfo-5.14.0-362.8.1.el9_3.x86_64 2>/dev/null || \
+        dnf -y -q install kernel-debuginfo-common-x86_64-5.14.0-362.8.1.el9_3.x86_64 kernel-debuginfo-5.14.0-362.8.1.el9_3.x86_64 2>/dev/null || true
+    fi
+fi
+
+SYMGEN_STEP=find_vmlinux
+echo "@@STEP:find_vmlinux@@"
+# Find vmlinux file
+echo ">>> Looking for vmlinux..."
+VMLINUX=$(find /usr/lib/debug -name "vmlinux-5.14.0-362.8.1.el9_3.x86_64*" -type f 2>/dev/null | head -1)
+if [ -z "$VMLINUX" ]; then
+    VMLINUX=$(find /usr/lib/debug -name "vmlinux*" -path "*5.14.0-362.8.1.el9_3.x86_64*" -type f 2>/dev/null | head -1)
+fi
+
+if [ -z "$VMLINUX" ] || [ ! -f "$VMLINUX" ]; then
+    echo "ERROR: vmlinux not found in debug package"
+    echo ">>> Searching for any vmlinux files..."
+    find /usr/lib/debug -name "vmlinux*" -type f 2>/dev/null || true
+    exit 2
+fi
+echo ">>> Found vmlinux: $VMLINUX"
+echo "@@RESULT:vmlinux=$VMLINUX@@"
+
+SYMGEN_STEP=setup_dwarf2json
+echo "@@STEP:setup_dwarf2json@@"
+# Download dwarf2json (certificate checks stay on) and verify its digest
+echo ">>> Setting up dwarf2json..."
+if [ -n "${DWARF2JSON_FETCH_PID:-}" ]; then
+    wait "$DWARF2JSON_FETCH_PID"
+else
+    wget -q --max-redirect=5 "https://github.com/volatilityfoundation/dwarf2json/releases/download/v0.8.0/dwarf2json-linux-amd64" -O /usr/local/bin/dwarf2json
+fi
+DWARF2JSON_SHA256=$(sha256sum /usr/local/bin/dwarf2json | cut -d' ' -f1)
+echo ">>> dwarf2json sha256: $DWARF2JSON_SHA256"
+echo "@@RESULT:dwarf2json_sha256=$DWARF2JSON_SHA256@@"
+echo "WARNING: dwarf2json checksum not pinned (set dwarf2json_sha256 in the config to verify it)"
+chmod +x /usr/local/bin/dwarf2json
+
+SYMGEN_STEP=find_system_map
+echo "@@STEP:find_system_map@@"
+# Check for System.map
+SYSTEM_MAP=""
+SYSTEM_MAP_SOURCE=""
+if [ -f "/boot/System.map-5.14.0-362.8.1.el9_3.x86_64" ]; then
+    SYSTEM_MAP="/boot/System.map-5.14.0-362.8.1.el9_3.x86_64"
+    echo ">>> Found System.map: $SYSTEM_MAP"
+    echo "@@RESULT:system_map=$SYSTEM_MAP@@"
+    echo "@@RESULT:system_map_source=installed@@"
+else
+    echo ">>> No System.map installed, unpacking the kernel package for it..."
+    (yum -y -q install cpio yum-utils 2>/dev/null || dnf -y -q install cpio dnf-plugins-core 2>/dev/null) || true
+    mkdir -p /tmp/symgen-map
+    (cd /tmp/symgen-map && for PKG in kernel-core-5.14.0-362.8.1.el9_3.x86_64 kernel-5.14.0-362.8.1.el9_3.x86_64; do (dnf download -q --arch x86_64 "$PKG" 2>/dev/null || yumdownloader -q --archlist=x86_64 "$PKG" 2>/dev/null) && break; done
+        for RPM in *.rpm; do [ -f "$RPM" ] && rpm2cpio "$RPM" | cpio -idm --quiet; done) || true
+    MAP=$(find /tmp/symgen-map -name 'System.map*' -type f 2>/dev/null | head -1)
+    if [ -n "$MAP" ]; then
+        SYSTEM_MAP="$MAP"
+        SYSTEM_MAP_SOURCE="package"
+    fi
+    if [ -z "$SYSTEM_MAP" ]; then
+        echo ">>> Deriving System.map from the vmlinux symbol table..."
+        command -v nm >/dev/null || apt-get install -y -qq binutils >/dev/null 2>&1 \
+            || dnf -y -q install binutils >/dev/null 2>&1 || yum -y -q install binutils >/dev/null 2>&1 || true
+        if command -v nm >/dev/null \
+            && nm -n "$VMLINUX" 2>/dev/null | grep -v -e ' [aNUw] ' -e '__crc_' -e ' \$[adt]' -e ' \.L' > "/tmp/System.map-5.14.0-362.8.1.el9_3.x86_64" \
+            && [ -s "/tmp/System.map-5.14.0-362.8.1.el9_3.x86_64" ]; then
+            SYSTEM_MAP="/tmp/System.map-5.14.0-362.8.1.el9_3.x86_64"
+            SYSTEM_MAP_SOURCE="symtab"
+        fi
+    fi
+    if [ -n "$SYSTEM_MAP" ]; then
+        echo ">>> Using System.map from $SYSTEM_MAP_SOURCE: $SYSTEM_MAP"
+        echo "@@RESULT:system_map=$SYSTEM_MAP@@"
+        echo "@@RESULT:system_map_source=$SYSTEM_MAP_SOURCE@@"
+    else
+        echo ">>> No System.map found or derivable, continuing without it..."
+    fi
+fi
+
+SYMGEN_STEP=generate
+echo "@@STEP:generate@@"
+# Generate symbol file (output to the mounted volume)
+echo ">>> Generating Volatility3 symbol file..."
+SYMBOL_FILE="$OUTPUT_DIR/Alma_9_5.14.0-362.8.1.el9_3.x86_64.json"
+echo "@@RESULT:debuginfo_bytes=$(du -sb /usr/lib/debug 2>/dev/null | cut -f1)@@"
+SOURCE_PACKAGE=""
+if command -v rpm >/dev/null && rpm -qf "$VMLINUX" >/dev/null 2>&1; then
+    SOURCE_PACKAGE=$(rpm -qf "$VMLINUX" | head -1)
+elif command -v dpkg-query >/dev/null && OWNER=$(dpkg-query -S "$VMLINUX" 2>/dev/null); then
+    SOURCE_PACKAGE=$(dpkg-query -W -f '${Package}=${Version}' "${OWNER%%:*}" 2>/dev/null || true)
+fi
+if [ -n "$SOURCE_PACKAGE" ]; then
+    echo "@@RESULT:source_package=$SOURCE_PACKAGE@@"
+fi
+
+if [ -n "$SYSTEM_MAP" ]; then
+    /usr/local/bin/dwarf2json linux --elf "$VMLINUX" --system-map "$SYSTEM_MAP" > "$SYMBOL_FILE" &
+else
+    /usr/local/bin/dwarf2json linux --elf "$VMLINUX" > "$SYMBOL_FILE" &
+fi
+
+# dwarf2json prints nothing for minutes; report the output size periodically
+DWARF2JSON_PID=$!
+ELAPSED=0
+while kill -0 "$DWARF2JSON_PID" 2>/dev/null; do
+    sleep 1
+    ELAPSED=$((ELAPSED + 1))
+    if [ $((ELAPSED % 15)) -eq 0 ]; then
+        WRITTEN=$(stat -c %s "$SYMBOL_FILE" 2>/dev/null || echo 0)
+        echo ">>> dwarf2json running for ${ELAPSED}s, $((WRITTEN / 1048576)) MiB written"
+    fi
+done
+wait "$DWARF2JSON_PID"
+
+SYMGEN_STEP=compress
+echo "@@STEP:compress@@"
+# Compress the symbol file
+echo ">>> Compressing symbol file..."
+xz -9 "$SYMBOL_FILE"
+
+SYMGEN_STEP=finish
+echo "@@STEP:finish@@"
+echo "=== Symbol generation completed successfully ==="
+ls -la "$OUTPUT_DIR"
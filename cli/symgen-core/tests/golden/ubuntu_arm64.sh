@@ -0,0 +1,218 @@
+#!/bin/bash
+set -e
+
+echo "=== Starting symbol generation for Ubuntu kernel 5.15.0-91-generic ==="
+
+# Save output directory (the mounted volume)
+OUTPUT_DIR="$PWD"
+
+# Configure apt for non-interactive mode
+export DEBIAN_FRONTEND=noninteractive
+
+# Collect diagnostics for bug reports when any step fails
+collect_diagnostics() {
+    status=$?
+    [ "$status" -eq 0 ] && return
+    mkdir -p /tmp/symgen-failure/repos || return
+    echo "$status" > /tmp/symgen-failure/exit-code.txt || true
+    echo "${SYMGEN_STEP:-}" > /tmp/symgen-failure/failed-step.txt || true
+    (dpkg -l 2>/dev/null || rpm -qa 2>/dev/null) > /tmp/symgen-failure/packages.txt || true
+    df -h > /tmp/symgen-failure/df.txt 2>&1 || true
+    cp /etc/os-release /tmp/symgen-failure/os-release.txt 2>/dev/null || true
+    cp -r /etc/apt/sources.list /etc/apt/sources.list.d /etc/yum.repos.d /tmp/symgen-failure/repos/ 2>/dev/null || true
+}
+trap collect_diagnostics EXIT
+
+SYMGEN_STEP=update
+echo "@@STEP:update@@"
+# Update package lists
+echo ">>> Updating package lists..."
+apt-get update -qq
+
+SYMGEN_STEP=install_tools
+echo "@@STEP:install_tools@@"
+# Install required packages
+echo ">>> Installing required packages..."
+apt-get install -y -qq wget xz-utils ca-certificates
+
+SYMGEN_STEP=setup_keyring
+echo "@@STEP:setup_keyring@@"
+# Make sure the archive and debug symbol signing keys are present and current
+echo ">>> Refreshing Ubuntu archive keyrings..."
+apt-get install -y -qq --only-upgrade ubuntu-keyring 2>/dev/null || true
+apt-get install -y -qq ubuntu-dbgsym-keyring 2>/dev/null || true
+if [ ! -s /usr/share/keyrings/ubuntu-dbgsym-keyring.gpg ]; then
+    echo ">>> ubuntu-dbgsym-keyring unavailable, fetching the key from keyserver.ubuntu.com..."
+    apt-get install -y -qq gnupg dirmngr
+    GNUPGHOME=$(mktemp -d)
+    export GNUPGHOME
+    if gpg --batch --keyserver hkps://keyserver.ubuntu.com --recv-keys F2EDC64DC5AEE1F6B9C621F0C8CAB6595FDFF622; then
+        gpg --batch --export F2EDC64DC5AEE1F6B9C621F0C8CAB6595FDFF622 > /usr/share/keyrings/ubuntu-dbgsym-keyring.gpg
+    else
+        echo "WARNING: Could not fetch the ddebs signing key (use --allow-unauthenticated for archived repos)"
+    fi
+    rm -rf "$GNUPGHOME"
+    unset GNUPGHOME
+fi
+
+SYMGEN_STEP=add_repos
+echo "@@STEP:add_repos@@"
+# Add Ubuntu proposed repository for newer kernel packages
+echo ">>> Adding proposed repository..."
+cat > /etc/apt/sources.list.d/proposed.sources << 'EOF'
+Types: deb
+URIs: http://ports.ubuntu.com/ubuntu-ports/
+Suites: jammy-proposed
+Components: main restricted universe multiverse
+Signed-by: /usr/share/keyrings/ubuntu-archive-keyring.gpg
+EOF
+
+# Add ddebs repository for debug symbols (using official DEB822 format)
+echo ">>> Adding ddebs repository..."
+cat > /etc/apt/sources.list.d/ddebs.sources << 'EOF'
+Types: deb
+URIs: http://ddebs.ubuntu.com/
+Suites: jammy jammy-updates jammy-proposed
+Components: main restricted universe multiverse
+Signed-by: /usr/share/keyrings/ubuntu-dbgsym-keyring.gpg
+EOF
+
+# Update with new repos
+apt-get update -qq
+
+SYMGEN_STEP=install_debuginfo
+echo "@@STEP:install_debuginfo@@"
+# Install kernel debug symbols package
+echo ">>> Installing kernel debug symbols for 5.15.0-91-generic..."
+# Kernels without a signed image (riscv64, armhf, ...) only ship the unsigned dbgsym
+if ! apt-get install -y -qq linux-image-5.15.0-91-generic-dbgsym 2>/dev/null; then
+    echo ">>> Trying unsigned image package name..."
+    if ! apt-get install -y -qq linux-image-unsigned-5.15.0-91-generic-dbgsym 2>/dev/null; then
+        echo "ERROR: Could not find/install debug symbols for kernel 5.15.0-91-generic"
+        exit 2
+    fi
+fi
+
+# Install linux-modules package to get System.map
+echo ">>> Installing linux-modules for System.map..."
+apt-get install -y -qq linux-modules-5.15.0-91-generic 2>/dev/null || true
+
+SYMGEN_STEP=find_vmlinux
+echo "@@STEP:find_vmlinux@@"
+# Find vmlinux file from installed location
+echo ">>> Looking for vmlinux..."
+VMLINUX="/usr/lib/debug/boot/vmlinux-5.15.0-91-generic"
+if [ ! -f "$VMLINUX" ]; then
+    # Try alternative location
+    VMLINUX=$(find /usr/lib/debug -name "vmlinux-5.15.0-91-generic" -type f 2>/dev/null | head -1)
+fi
+
+if [ -z "$VMLINUX" ] || [ ! -f "$VMLINUX" ]; then
+    echo "ERROR: vmlinux not found in debug package"
+    echo ">>> Searching for any vmlinux files..."
+    find /usr/lib/debug -name "vmlinux*" -type f 2>/dev/null || true
+    exit 2
+fi
+echo ">>> Found vmlinux: $VMLINUX"
+echo "@@RESULT:vmlinux=$VMLINUX@@"
+
+SYMGEN_STEP=setup_dwarf2json
+echo "@@STEP:setup_dwarf2json@@"
+# Build dwarf2json from source (no prebuilt binary for arm64)
+echo ">>> Building dwarf2json from source..."
+command -v tar >/dev/null || yum -y -q install tar 2>/dev/null || dnf -y -q install tar 2>/dev/null || apt-get install -y -qq tar
+wget -q https://go.dev/dl/go1.22.5.linux-arm64.tar.gz -O /tmp/go.tar.gz
+tar -C /usr/local -xzf /tmp/go.tar.gz
+rm -f /tmp/go.tar.gz
+# go install checks module sources against sum.golang.org
+GOBIN=/usr/local/bin GOPATH=/tmp/gopath GOCACHE=/tmp/gocache /usr/local/go/bin/go install github.com/volatilityfoundation/dwarf2json@v0.8.0
+rm -rf /tmp/gopath /tmp/gocache
+DWARF2JSON_SHA256=$(sha256sum /usr/local/bin/dwarf2json | cut -d' ' -f1)
+echo ">>> dwarf2json sha256: $DWARF2JSON_SHA256"
+echo "@@RESULT:dwarf2json_sha256=$DWARF2JSON_SHA256@@"
+
+SYMGEN_STEP=find_system_map
+echo "@@STEP:find_system_map@@"
+# Check for System.map
+SYSTEM_MAP=""
+SYSTEM_MAP_SOURCE=""
+if [ -f "/boot/System.map-5.15.0-91-generic" ]; then
+    SYSTEM_MAP="/boot/System.map-5.15.0-91-generic"
+    echo ">>> Found System.map: $SYSTEM_MAP"
+    echo "@@RESULT:system_map=$SYSTEM_MAP@@"
+    echo "@@RESULT:system_map_source=installed@@"
+else
+    echo ">>> No System.map installed, unpacking the kernel package for it..."
+    mkdir -p /tmp/symgen-map
+    (cd /tmp/symgen-map && for PKG in linux-modules-5.15.0-91-generic linux-image-unsigned-5.15.0-91-generic linux-image-5.15.0-91-generic; do apt-get download -qq "$PKG" 2>/dev/null && break; done
+        for DEB in *.deb; do [ -f "$DEB" ] && dpkg-deb -x "$DEB" .; done) || true
+    MAP=$(find /tmp/symgen-map -name 'System.map*' -type f 2>/dev/null | head -1)
+    if [ -n "$MAP" ]; then
+        SYSTEM_MAP="$MAP"
+        SYSTEM_MAP_SOURCE="package"
+    fi
+    if [ -z "$SYSTEM_MAP" ]; then
+        echo ">>> Deriving System.map from the vmlinux symbol table..."
+        command -v nm >/dev/null || apt-get install -y -qq binutils >/dev/null 2>&1 \
+            || dnf -y -q install binutils >/dev/null 2>&1 || yum -y -q install binutils >/dev/null 2>&1 || true
+        if command -v nm >/dev/null \
+            && nm -n "$VMLINUX" 2>/dev/null | grep -v -e ' [aNUw] ' -e '__crc_' -e ' \$[adt]' -e ' \.L' > "/tmp/System.map-5.15.0-91-generic" \
+            && [ -s "/tmp/System.map-5.15.0-91-generic" ]; then
+            SYSTEM_MAP="/tmp/System.map-5.15.0-91-generic"
+            SYSTEM_MAP_SOURCE="symtab"
+        fi
+    fi
+    if [ -n "$SYSTEM_MAP" ]; then
+        echo ">>> Using System.map from $SYSTEM_MAP_SOURCE: $SYSTEM_MAP"
+        echo "@@RESULT:system_map=$SYSTEM_MAP@@"
+        echo "@@RESULT:system_map_source=$SYSTEM_MAP_SOURCE@@"
+    else
+        echo ">>> No System.map found or derivable, continuing without it..."
+    fi
+fi
+
+SYMGEN_STEP=generate
+echo "@@STEP:generate@@"
+# Generate symbol file (output to the mounted volume)
+echo ">>> Generating Volatility3 symbol file..."
+SYMBOL_FILE="$OUTPUT_DIR/Ubuntu_jammy_5.15.0-91-generic_arm64.json"
+echo "@@RESULT:debuginfo_bytes=$(du -sb /usr/lib/debug 2>/dev/null | cut -f1)@@"
+SOURCE_PACKAGE=""
+if command -v rpm >/dev/null && rpm -qf "$VMLINUX" >/dev/null 2>&1; then
+    SOURCE_PACKAGE=$(rpm -qf "$VMLINUX" | head -1)
+elif command -v dpkg-query >/dev/null && OWNER=$(dpkg-query -S "$VMLINUX" 2>/dev/null); then
+    SOURCE_PACKAGE=$(dpkg-query -W -f '${Package}=${Version}' "${OWNER%%:*}" 2>/dev/null || true)
+fi
+if [ -n "$SOURCE_PACKAGE" ]; then
+    echo "@@RESULT:source_package=$SOURCE_PACKAGE@@"
+fi
+
+if [ -n "$SYSTEM_MAP" ]; then
+    /usr/local/bin/dwarf2json linux --elf "$VMLINUX" --system-map "$SYSTEM_MAP" > "$SYMBOL_FILE" &
+else
+    /usr/local/bin/dwarf2json linux --elf "$VMLINUX" > "$SYMBOL_FILE" &
+fi
+
+# dwarf2json prints nothing for minutes; report the output size periodically
+DWARF2JSON_PID=$!
+ELAPSED=0
+while kill -0 "$DWARF2JSON_PID" 2>/dev/null; do
+    sleep 1
+    ELAPSED=$((ELAPSED + 1))
+    if [ $((ELAPSED % 15)) -eq 0 ]; then
+        WRITTEN=$(stat -c %s "$SYMBOL_FILE" 2>/dev/null || echo 0)
+        echo ">>> dwarf2json running for ${ELAPSED}s, $((WRITTEN / 1048576)) MiB written"
+    fi
+done
+wait "$DWARF2JSON_PID"
+
+SYMGEN_STEP=compress
+echo "@@STEP:compress@@"
+# Compress the symbol file
+echo ">>> Compressing symbol file..."
+xz -9 "$SYMBOL_FILE"
+
+SYMGEN_STEP=finish
+echo "@@STEP:finish@@"
+echo "=== Symbol generation completed successfully ==="
+ls -la "$OUTPUT_DIR"
@@ -0,0 +1,189 @@
+#!/bin/bash
+set -e
+
+echo "=== Starting symbol generation for WSL2 kernel 5.15.153.1-microsoft-standard-WSL2 ==="
+
+# Save output directory (the mounted volume)
+OUTPUT_DIR="$PWD"
+
+# Configure apt for non-interactive mode
+export DEBIAN_FRONTEND=noninteractive
+
+# Collect diagnostics for bug reports when any step fails
+collect_diagnostics() {
+    status=$?
+    [ "$status" -eq 0 ] && return
+    mkdir -p /tmp/symgen-failure/repos || return
+    echo "$status" > /tmp/symgen-failure/exit-code.txt || true
+    echo "${SYMGEN_STEP:-}" > /tmp/symgen-failure/failed-step.txt || true
+    (dpkg -l 2>/dev/null || rpm -qa 2>/dev/null) > /tmp/symgen-failure/packages.txt || true
+    df -h > /tmp/symgen-failure/df.txt 2>&1 || true
+    cp /etc/os-release /tmp/symgen-failure/os-release.txt 2>/dev/null || true
+    cp -r /etc/apt/sources.list /etc/apt/sources.list.d /etc/yum.repos.d /tmp/symgen-failure/repos/ 2>/dev/null || true
+}
+trap collect_diagnostics EXIT
+
+SYMGEN_STEP=update
+echo "@@STEP:update@@"
+# Update package lists
+echo ">>> Updating package lists..."
+apt-get update -qq
+
+SYMGEN_STEP=install_tools
+echo "@@STEP:install_tools@@"
+# Install required packages
+echo ">>> Installing required packages..."
+apt-get install -y -qq wget xz-utils ca-certificates
+# Fetch dwarf2json in the background while the debug packages download
+wget -q --max-redirect=5 "https://github.com/volatilityfoundation/dwarf2json/releases/download/v0.8.0/dwarf2json-linux-amd64" -O /usr/local/bin/dwarf2json &
+DWARF2JSON_FETCH_PID=$!
+
+SYMGEN_STEP=fetch_source
+echo "@@STEP:fetch_source@@"
+# Download the release's source; older releases were tagged with the kernel name
+echo ">>> Downloading WSL2 kernel source for 5.15.153.1..."
+mkdir -p /usr/src/wsl
+FOUND=""
+for TAG in linux-msft-wsl-5.15.153.1 5.15.153.1-microsoft-standard-WSL2; do
+    if wget -q "https://github.com/microsoft/WSL2-Linux-Kernel/archive/refs/tags/$TAG.tar.gz" -O /tmp/wsl-kernel.tar.gz; then
+        FOUND="$TAG"
+        break
+    fi
+done
+if [ -z "$FOUND" ]; then
+    echo "ERROR: No source tag for WSL2 kernel 5.15.153.1-microsoft-standard-WSL2 in microsoft/WSL2-Linux-Kernel"
+    exit 2
+fi
+echo ">>> Found tag $FOUND"
+tar -C /usr/src/wsl --strip-components=1 -xzf /tmp/wsl-kernel.tar.gz
+rm -f /tmp/wsl-kernel.tar.gz
+
+SYMGEN_STEP=build_vmlinux
+echo "@@STEP:build_vmlinux@@"
+# Build vmlinux with Microsoft's config plus full debug info
+echo ">>> Installing the kernel build toolchain..."
+apt-get install -y -qq build-essential flex bison bc cpio kmod libssl-dev libelf-dev python3 dwarves
+cd /usr/src/wsl
+CONFIG=Microsoft/config-wsl
+[ -f "$CONFIG" ] || CONFIG=arch/x86/configs/config-wsl
+if [ ! -f "$CONFIG" ]; then
+    echo "ERROR: The WSL2 source tree carries no kernel config"
+    exit 2
+fi
+cp "$CONFIG" .config
+scripts/config --file .config -e DEBUG_INFO -e DEBUG_INFO_DWARF_TOOLCHAIN_DEFAULT \
+    -d DEBUG_INFO_REDUCED -d DEBUG_INFO_SPLIT -d DEBUG_INFO_NONE -d DEBUG_INFO_BTF
+make -s ARCH=x86_64 olddefconfig
+BUILT=$(make -s ARCH=x86_64 kernelrelease)
+if [ "$BUILT" != "5.15.153.1-microsoft-standard-WSL2" ]; then
+    echo "WARNING: The source builds kernel $BUILT, not 5.15.153.1-microsoft-standard-WSL2"
+fi
+echo ">>> Building vmlinux with $(nproc) jobs (this takes a while)..."
+make -s ARCH=x86_64 -j"$(nproc)" vmlinux
+mkdir -p /usr/lib/debug/boot
+cp vmlinux /usr/lib/debug/boot/vmlinux-5.15.153.1-microsoft-standard-WSL2
+cp System.map /boot/System.map-5.15.153.1-microsoft-standard-WSL2
+cd "$OUTPUT_DIR"
+rm -rf /usr/src/wsl
+
+SYMGEN_STEP=find_vmlinux
+echo "@@STEP:find_vmlinux@@"
+VMLINUX="/usr/lib/debug/boot/vmlinux-5.15.153.1-microsoft-standard-WSL2"
+if [ ! -f "$VMLINUX" ]; then
+    echo "ERROR: vmlinux was not built"
+    exit 2
+fi
+echo ">>> Found vmlinux: $VMLINUX"
+echo "@@RESULT:vmlinux=$VMLINUX@@"
+
+SYMGEN_STEP=setup_dwarf2json
+echo "@@STEP:setup_dwarf2json@@"
+# Download dwarf2json (certificate checks stay on) and verify its digest
+echo ">>> Setting up dwarf2json..."
+if [ -n "${DWARF2JSON_FETCH_PID:-}" ]; then
+    wait "$DWARF2JSON_FETCH_PID"
+else
+    wget -q --max-redirect=5 "https://github.com/volatilityfoundation/dwarf2json/releases/download/v0.8.0/dwarf2json-linux-amd64" -O /usr/local/bin/dwarf2json
+fi
+DWARF2JSON_SHA256=$(sha256sum /usr/local/bin/dwarf2json | cut -d' ' -f1)
+echo ">>> dwarf2json sha256: $DWARF2JSON_SHA256"
+echo "@@RESULT:dwarf2json_sha256=$DWARF2JSON_SHA256@@"
+echo "WARNING: dwarf2json checksum not pinned (set dwarf2json_sha256 in the config to verify it)"
+chmod +x /usr/local/bin/dwarf2json
+
+SYMGEN_STEP=find_system_map
+echo "@@STEP:find_system_map@@"
+# Check for System.map
+SYSTEM_MAP=""
+SYSTEM_MAP_SOURCE=""
+if [ -f "/boot/System.map-5.15.153.1-microsoft-standard-WSL2" ]; then
+    SYSTEM_MAP="/boot/System.map-5.15.153.1-microsoft-standard-WSL2"
+    echo ">>> Found System.map: $SYSTEM_MAP"
+    echo "@@RESULT:system_map=$SYSTEM_MAP@@"
+    echo "@@RESULT:system_map_source=installed@@"
+else
+    if [ -z "$SYSTEM_MAP" ]; then
+        echo ">>> Deriving System.map from the vmlinux symbol table..."
+        command -v nm >/dev/null || apt-get install -y -qq binutils >/dev/null 2>&1 \
+            || dnf -y -q install binutils >/dev/null 2>&1 || yum -y -q install binutils >/dev/null 2>&1 || true
+        if command -v nm >/dev/null \
+            && nm -n "$VMLINUX" 2>/dev/null | grep -v -e ' [aNUw] ' -e '__crc_' -e ' \$[adt]' -e ' \.L' > "/tmp/System.map-5.15.153.1-microsoft-standard-WSL2" \
+            && [ -s "/tmp/System.map-5.15.153.1-microsoft-standard-WSL2" ]; then
+            SYSTEM_MAP="/tmp/System.map-5.15.153.1-microsoft-standard-WSL2"
+            SYSTEM_MAP_SOURCE="symtab"
+        fi
+    fi
+    if [ -n "$SYSTEM_MAP" ]; then
+        echo ">>> Using System.map from $SYSTEM_MAP_SOURCE: $SYSTEM_MAP"
+        echo "@@RESULT:system_map=$SYSTEM_MAP@@"
+        echo "@@RESULT:system_map_source=$SYSTEM_MAP_SOURCE@@"
+    else
+        echo ">>> No System.map found or derivable, continuing without it..."
+    fi
+fi
+
+SYMGEN_STEP=generate
+echo "@@STEP:generate@@"
+# Generate symbol file (output to the mounted volume)
+echo ">>> Generating Volatility3 symbol file..."
+SYMBOL_FILE="$OUTPUT_DIR/WSL2_5.15.153.1-microsoft-standard-WSL2.json"
+echo "@@RESULT:debuginfo_bytes=$(du -sb /usr/lib/debug 2>/dev/null | cut -f1)@@"
+SOURCE_PACKAGE=""
+if command -v rpm >/dev/null && rpm -qf "$VMLINUX" >/dev/null 2>&1; then
+    SOURCE_PACKAGE=$(rpm -qf "$VMLINUX" | head -1)
+elif command -v dpkg-query >/dev/null && OWNER=$(dpkg-query -S "$VMLINUX" 2>/dev/null); then
+    SOURCE_PACKAGE=$(dpkg-query -W -f '${Package}=${Version}' "${OWNER%%:*}" 2>/dev/null || true)
+fi
+if [ -n "$SOURCE_PACKAGE" ]; then
+    echo "@@RESULT:source_package=$SOURCE_PACKAGE@@"
+fi
+
+if [ -n "$SYSTEM_MAP" ]; then
+    /usr/local/bin/dwarf2json linux --elf "$VMLINUX" --system-map "$SYSTEM_MAP" > "$SYMBOL_FILE" &
+else
+    /usr/local/bin/dwarf2json linux --elf "$VMLINUX" > "$SYMBOL_FILE" &
+fi
+
+# dwarf2json prints nothing for minutes; report the output size periodically
+DWARF2JSON_PID=$!
+ELAPSED=0
+while kill -0 "$DWARF2JSON_PID" 2>/dev/null; do
+    sleep 1
+    ELAPSED=$((ELAPSED + 1))
+    if [ $((ELAPSED % 15)) -eq 0 ]; then
+        WRITTEN=$(stat -c %s "$SYMBOL_FILE" 2>/dev/null || echo 0)
+        echo ">>> dwarf2json running for ${ELAPSED}s, $((WRITTEN / 1048576)) MiB written"
+    fi
+done
+wait "$DWARF2JSON_PID"
+
+SYMGEN_STEP=compress
+echo "@@STEP:compress@@"
+# Compress the symbol file
+echo ">>> Compressing symbol file..."
+xz -9 "$SYMBOL_FILE"
+
+SYMGEN_STEP=finish
+echo "@@STEP:finish@@"
+echo "=== Symbol generation completed successfully ==="
+ls -la "$OUTPUT_DIR"
@@ -42,6 +42,8 @@ import {
   AlertTriangle,
   CheckCircle2,
   XCircle,
+  Ban,
+  Archive,
   Loader2,
   Clock,
   Wifi,
@@ -296,7 +298,7 @@ export default function SymGenPage() {
       await symgenApi.cancelJob(cancelledId);
       setJobs(prev => prev.map(j => 
         j.id === cancelledId 
-          ? { ...j, status: "failed" as const, error_message: "Cancelled by user" }
+          ? { ...j, status: "cancelled" as const, error_message: "Cancelled by user" }
           : j
       ));
       toast.success("Job cancelled");
@@ -344,6 +346,10 @@ export default function SymGenPage() {
         return <CheckCircle2 className="h-4 w-4 text-emerald-500" aria-hidden="true" />;
       case "failed":
         return <XCircle className="h-4 w-4 text-red-500" aria-hidden="true" />;
+      case "cancelled":
+        return <Ban className="h-4 w-4 text-zinc-500" aria-hidden="true" />;
+      case "evicted":
+        return <Archive className="h-4 w-4 text-zinc-500" aria-hidden="true" />;
       case "running":
       case "pulling_image":
       case "downloading_kernel":
@@ -360,6 +366,9 @@ export default function SymGenPage() {
         return "bg-emerald-500/10 text-emerald-600 border-emerald-500/20";
       case "failed":
         return "bg-red-500/10 text-red-600 border-red-500/20";
+      case "cancelled":
+      case "evicted":
+        return "bg-zinc-500/10 text-zinc-600 border-zinc-500/20";
       case "running":
       case "pulling_image":
       case "downloading_kernel":
@@ -392,6 +401,8 @@ export default function SymGenPage() {
       case "generating_symbol": return 80;
       case "completed": return 100;
       case "failed": return 100;
+      case "cancelled": return 100;
+      case "evicted": return 100;
       default: return 0;
     }
   };
@@ -723,6 +734,9 @@ export default function SymGenPage() {
                             {job.error_message && job.status === "failed" && (
                               <p className="text-sm text-red-500 mt-1 line-clamp-2">{job.error_message}</p>
                             )}
+                            {job.error_message && job.status === "evicted" && (
+                              <p className="text-sm text-muted-foreground mt-1 line-clamp-2">{job.error_message}</p>
+                            )}
                             {job.symbol_filename && job.status === "completed" && (
                               <p className="text-sm text-muted-foreground mt-1">
                                 {job.symbol_filename}
@@ -762,7 +776,7 @@ export default function SymGenPage() {
                                 </a>
                               </Button>
                             )}
-                            {(job.status === "completed" || job.status === "failed") && (
+                            {(job.status === "completed" || job.status === "failed" || job.status === "evicted") && (
                               <Button
                                 variant="outline"
                                 size="sm"
@@ -10,7 +10,8 @@ const api = axios.create({
   },
 });
 
-export type SymGenStatus = "pending" | "pulling_image" | "running" | "downloading_kernel" | "generating_symbol" | "completed" | "failed";
+export type SymGenStatus = "pending" | "pulling_image" | "running" | "downloading_kernel" | "generating_symbol" | "completed" | "failed" | "cancelled" | "evicted";
+export type JobPriority = "interactive" | "bulk";
 export type LinuxDistro = "ubuntu" | "debian" | "fedora" | "centos" | "rhel" | "oracle" | "rocky" | "alma";
 export type UbuntuVersion = "20.04" | "22.04" | "24.04";
 export type DebianVersion = "10" | "11" | "12";
@@ -37,6 +38,8 @@ export interface SymGenJob {
   alma_version?: AlmaVersion | null;
   status: SymGenStatus;
   status_message?: string | null;
+  priority?: JobPriority | null;
+  submitted_by?: string | null;
   error_message?: string | null;
   symbol_filename?: string | null;
   symbol_file_size?: number | null;
@@ -44,6 +47,7 @@ export interface SymGenJob {
   started_at?: string | null;
   completed_at?: string | null;
   created_at: string;
+  expires_at?: string | null;
 }
 
 export interface SymGenListResponse {
@@ -160,6 +164,7 @@ export interface MetricsResponse {
   total_symbols: number;
   total_storage_bytes: number;
   total_storage_formatted: string;
+  storage_quota_bytes?: number | null;
   total_downloads: number;
   avg_completion_time_seconds: number | null;
   avg_completion_time_formatted: string | null;
@@ -171,7 +176,9 @@ export interface MetricsResponse {
 export interface QueueStatus {
   running: number;
   queued: number;
+  queued_by_priority?: Record<JobPriority, number>;
   max_concurrent: number;
+  draining?: boolean;
 }
 
 // Symbol Generation API
@@ -219,6 +226,7 @@ export const symgenApi = {
       oracleVersion?: OracleVersion;
       rockyVersion?: RockyVersion;
       almaVersion?: AlmaVersion;
+      priority?: JobPriority;
     }
   ): Promise<SymGenJob> => {
     const response = await api.post<SymGenJob>("/api/symgen/generate", {
@@ -232,6 +240,7 @@ export const symgenApi = {
       oracle_version: options?.oracleVersion || null,
       rocky_version: options?.rockyVersion || null,
       alma_version: options?.almaVersion || null,
+      priority: options?.priority || "interactive",
     });
     return response.data;
   },